@@ -0,0 +1,454 @@
+#!/usr/bin/env python3
+# A perft kept apart from the engine's move generator, for checking the node counts of the variant
+# tests in src/perft.rs. It's a plain 0x88 board that makes every pseudo legal move and throws out
+# the ones that leave the king in check, with the rules of each variant written out from scratch.
+#
+#     python3 reference_perft.py <chess|kingofthehill|3check|antichess|atomic|crazyhouse> <depth> "<fen>"
+#
+# Castling rights can be KQkq or the files of the castling rooks, three-check fens have the checks
+# left to give after the en passant square, like `3+3`, and crazyhouse fens have the pockets in
+# brackets after the board with a `~` after each promoted piece. It's slow, so keep to the counts
+# that run in the tests.
+
+import sys
+
+PAWN, KNIGHT, BISHOP, ROOK, QUEEN, KING = 1, 2, 3, 4, 5, 6
+BLACK = 8
+
+KNIGHT_STEPS = [33, 31, 18, 14, -33, -31, -18, -14]
+KING_STEPS = [1, -1, 16, -16, 15, 17, -15, -17]
+DIAGONALS = [15, 17, -15, -17]
+LINES = [1, -1, 16, -16]
+
+
+def on_board(sq):
+    return not sq & 0x88
+
+
+def rank(sq):
+    return sq >> 4
+
+
+def file(sq):
+    return sq & 7
+
+
+def color(piece):
+    return 1 if piece & BLACK else 0
+
+
+def kind(piece):
+    return piece & 7
+
+
+class Position:
+    def __init__(self):
+        self.board = [0] * 128
+        self.side = 0
+        self.ep = None
+        # the file of the castling rook for each side, queenside then kingside
+        self.castling = [[None, None], [None, None]]
+        self.checks = [0, 0]
+        self.pockets = [[0] * 7, [0] * 7]
+        self.promoted = set()
+
+    def copy(self):
+        other = Position.__new__(Position)
+        other.board = self.board[:]
+        other.side = self.side
+        other.ep = self.ep
+        other.castling = [self.castling[0][:], self.castling[1][:]]
+        other.checks = self.checks[:]
+        other.pockets = [self.pockets[0][:], self.pockets[1][:]]
+        other.promoted = set(self.promoted)
+        return other
+
+    def kings(self, side):
+        return [sq for sq in range(128) if on_board(sq) and self.board[sq] == KING | side * BLACK]
+
+
+def attacked(pos, sq, by, with_king=True):
+    c = by * BLACK
+    pawn_rank_step = -16 if by == 0 else 16
+    for step in (-1, 1):
+        s = sq + pawn_rank_step + step
+        if on_board(s) and pos.board[s] == PAWN | c:
+            return True
+    for step in KNIGHT_STEPS:
+        s = sq + step
+        if on_board(s) and pos.board[s] == KNIGHT | c:
+            return True
+    if with_king:
+        for step in KING_STEPS:
+            s = sq + step
+            if on_board(s) and pos.board[s] == KING | c:
+                return True
+    for steps, sliders in ((DIAGONALS, (BISHOP, QUEEN)), (LINES, (ROOK, QUEEN))):
+        for step in steps:
+            s = sq + step
+            while on_board(s):
+                piece = pos.board[s]
+                if piece:
+                    if color(piece) == by and kind(piece) in sliders:
+                        return True
+                    break
+                s += step
+    return False
+
+
+def kings_touch(a, b):
+    return abs(rank(a) - rank(b)) <= 1 and abs(file(a) - file(b)) <= 1
+
+
+class Rules:
+    name = "chess"
+    promotions = (KNIGHT, BISHOP, ROOK, QUEEN)
+    castles = True
+
+    # the side to move is in check, or the square would be attacked for a king of the side to move
+    def in_check(self, pos, side, sq=None):
+        if sq is None:
+            kings = pos.kings(side)
+            if not kings:
+                return False
+            sq = kings[0]
+        return attacked(pos, sq, 1 - side)
+
+    def over(self, pos):
+        return False
+
+    def filter(self, pos, moves):
+        return moves
+
+    # the position after the move, or none if the move is illegal
+    def after(self, pos, move):
+        child = make(pos, move)
+        if self.in_check(child, pos.side):
+            return None
+        return child
+
+
+class KingOfTheHill(Rules):
+    name = "kingofthehill"
+
+    def over(self, pos):
+        return any(rank(sq) in (3, 4) and file(sq) in (3, 4) for sq in pos.kings(1 - pos.side))
+
+
+class ThreeCheck(Rules):
+    name = "3check"
+
+    def over(self, pos):
+        return pos.checks[1 - pos.side] >= 3
+
+    def after(self, pos, move):
+        child = super().after(pos, move)
+        if child is not None and self.in_check(child, child.side):
+            child.checks[pos.side] += 1
+        return child
+
+
+class Antichess(Rules):
+    name = "antichess"
+    promotions = (KNIGHT, BISHOP, ROOK, QUEEN, KING)
+    castles = False
+
+    def in_check(self, pos, side, sq=None):
+        return False
+
+    def filter(self, pos, moves):
+        captures = [move for move in moves if is_capture(pos, move)]
+        return captures or moves
+
+
+class Atomic(Rules):
+    name = "atomic"
+
+    # kings can't capture, so a king next to the other king can't be taken
+    def in_check(self, pos, side, sq=None):
+        own = pos.kings(side)
+        if sq is None:
+            if not own:
+                return False
+            sq = own[0]
+        enemy = pos.kings(1 - side)
+        if enemy and kings_touch(sq, enemy[0]):
+            return False
+        return attacked(pos, sq, 1 - side, with_king=False)
+
+    def over(self, pos):
+        return not pos.kings(pos.side)
+
+    def filter(self, pos, moves):
+        return [
+            move
+            for move in moves
+            if not (kind(pos.board[move[0]]) == KING and is_capture(pos, move))
+        ]
+
+    def after(self, pos, move):
+        child = make(pos, move)
+        if is_capture(pos, move):
+            explode(child, move[1])
+        if not child.kings(pos.side):
+            return None
+        if not child.kings(child.side):
+            return child
+        if self.in_check(child, pos.side):
+            return None
+        return child
+
+
+class Crazyhouse(Rules):
+    name = "crazyhouse"
+
+    def filter(self, pos, moves):
+        side = pos.side
+        for piece_kind in (PAWN, KNIGHT, BISHOP, ROOK, QUEEN):
+            if not pos.pockets[side][piece_kind]:
+                continue
+            for to in range(128):
+                if not on_board(to) or pos.board[to]:
+                    continue
+                if piece_kind == PAWN and rank(to) in (0, 7):
+                    continue
+                moves.append((None, to, piece_kind, "drop"))
+        return moves
+
+    # a captured piece goes to the pocket of the side that took it, as a pawn if it was promoted
+    def after(self, pos, move):
+        child = super().after(pos, move)
+        if child is None:
+            return None
+        from_sq, to, promotion, move_kind = move
+        side = pos.side
+        if move_kind == "drop":
+            child.pockets[side][promotion] -= 1
+        elif move_kind != "castle":
+            if move_kind == "ep":
+                child.pockets[side][PAWN] += 1
+            elif pos.board[to]:
+                captured = PAWN if to in pos.promoted else kind(pos.board[to])
+                child.pockets[side][captured] += 1
+            child.promoted -= {from_sq, to}
+            if from_sq in pos.promoted or promotion:
+                child.promoted.add(to)
+        return child
+
+
+RULES = {
+    rules.name: rules
+    for rules in (Rules(), KingOfTheHill(), ThreeCheck(), Antichess(), Atomic(), Crazyhouse())
+}
+
+
+def is_capture(pos, move):
+    return move[3] == "ep" or (move[3] != "castle" and pos.board[move[1]] != 0)
+
+
+def explode(pos, center):
+    pos.board[center] = 0
+    clear_castling(pos, center)
+    for step in KING_STEPS:
+        sq = center + step
+        if on_board(sq) and pos.board[sq] and kind(pos.board[sq]) != PAWN:
+            pos.board[sq] = 0
+            clear_castling(pos, sq)
+
+
+def clear_castling(pos, sq):
+    for side in (0, 1):
+        if rank(sq) != side * 7:
+            continue
+        for castle in (0, 1):
+            if pos.castling[side][castle] == file(sq):
+                pos.castling[side][castle] = None
+
+
+# moves are (from, to, promotion, kind) where kind is none, "double", "ep", "castle" or "drop". a
+# castle has the castle side, 0 for queenside and 1 for kingside, as its promotion and a drop has
+# the dropped piece
+def pseudo_legal_moves(pos, rules):
+    moves = []
+    side = pos.side
+    forward = 16 if side == 0 else -16
+    for sq in range(128):
+        if not on_board(sq):
+            continue
+        piece = pos.board[sq]
+        if not piece or color(piece) != side:
+            continue
+        piece_kind = kind(piece)
+        if piece_kind == PAWN:
+            last_rank = 7 if side == 0 else 0
+            to = sq + forward
+            if on_board(to) and not pos.board[to]:
+                if rank(to) == last_rank:
+                    moves.extend((sq, to, promotion, None) for promotion in rules.promotions)
+                else:
+                    moves.append((sq, to, None, None))
+                    if rank(sq) == (1 if side == 0 else 6) and not pos.board[to + forward]:
+                        moves.append((sq, to + forward, None, "double"))
+            for step in (-1, 1):
+                to = sq + forward + step
+                if not on_board(to):
+                    continue
+                target = pos.board[to]
+                if target and color(target) != side:
+                    if rank(to) == last_rank:
+                        moves.extend((sq, to, promotion, None) for promotion in rules.promotions)
+                    else:
+                        moves.append((sq, to, None, None))
+                elif to == pos.ep:
+                    moves.append((sq, to, None, "ep"))
+        elif piece_kind in (KNIGHT, KING):
+            for step in KNIGHT_STEPS if piece_kind == KNIGHT else KING_STEPS:
+                to = sq + step
+                if on_board(to) and not (pos.board[to] and color(pos.board[to]) == side):
+                    moves.append((sq, to, None, None))
+        else:
+            steps = []
+            if piece_kind in (BISHOP, QUEEN):
+                steps += DIAGONALS
+            if piece_kind in (ROOK, QUEEN):
+                steps += LINES
+            for step in steps:
+                to = sq + step
+                while on_board(to):
+                    if pos.board[to]:
+                        if color(pos.board[to]) != side:
+                            moves.append((sq, to, None, None))
+                        break
+                    moves.append((sq, to, None, None))
+                    to += step
+
+    if rules.castles:
+        moves.extend(castles(pos, rules))
+    return moves
+
+
+# chess960 castling: the king ends on the c or g file and the rook on the d or f file, everything
+# between them has to be empty and the king can't pass through check
+def castles(pos, rules):
+    side = pos.side
+    home = side * 7 * 16
+    kings = [sq for sq in pos.kings(side) if rank(sq) == side * 7]
+    if not kings:
+        return []
+    king_file = file(kings[0])
+    moves = []
+    for castle in (0, 1):
+        rook_file = pos.castling[side][castle]
+        if rook_file is None:
+            continue
+        king_to, rook_to = (6, 5) if castle else (2, 3)
+        files = [king_file, king_to, rook_file, rook_to]
+        if any(
+            pos.board[home + f] and f not in (king_file, rook_file)
+            for f in range(min(files), max(files) + 1)
+        ):
+            continue
+        path = range(min(king_file, king_to), max(king_file, king_to) + 1)
+        if any(rules.in_check(pos, side, home + f) for f in path):
+            continue
+        moves.append((home + king_file, home + king_to, castle, "castle"))
+    return moves
+
+
+def make(pos, move):
+    from_sq, to, promotion, move_kind = move
+    child = pos.copy()
+    side = pos.side
+    board = child.board
+    child.ep = None
+    if move_kind == "drop":
+        board[to] = promotion | side * BLACK
+    elif move_kind == "castle":
+        home = side * 7 * 16
+        rook_from = home + pos.castling[side][promotion]
+        rook_to = home + (5 if promotion else 3)
+        board[from_sq] = 0
+        board[rook_from] = 0
+        board[to] = KING | side * BLACK
+        board[rook_to] = ROOK | side * BLACK
+        child.castling[side] = [None, None]
+    else:
+        piece = board[from_sq]
+        clear_castling(child, from_sq)
+        clear_castling(child, to)
+        board[from_sq] = 0
+        board[to] = (promotion | side * BLACK) if promotion else piece
+        if move_kind == "ep":
+            board[to - (16 if side == 0 else -16)] = 0
+        elif move_kind == "double":
+            child.ep = (from_sq + to) // 2
+        if kind(piece) == KING and rank(from_sq) == side * 7:
+            child.castling[side] = [None, None]
+    child.side = 1 - side
+    return child
+
+
+def perft(pos, rules, depth):
+    if rules.over(pos):
+        return 0
+    nodes = 0
+    for move in rules.filter(pos, pseudo_legal_moves(pos, rules)):
+        child = rules.after(pos, move)
+        if child is not None:
+            nodes += 1 if depth == 1 else perft(child, rules, depth - 1)
+    return nodes
+
+
+def parse_fen(fen, rules):
+    fields = fen.split()
+    pos = Position()
+    pieces = {"p": PAWN, "n": KNIGHT, "b": BISHOP, "r": ROOK, "q": QUEEN, "k": KING}
+    board, _, pockets = fields[0].partition("[")
+    for c in pockets.rstrip("]"):
+        pos.pockets[1 if c.islower() else 0][pieces[c.lower()]] += 1
+    for r, row in enumerate(board.split("/")):
+        f = 0
+        for c in row:
+            if c.isdigit():
+                f += int(c)
+                continue
+            if c == "~":
+                pos.promoted.add((7 - r) * 16 + f - 1)
+                continue
+            pos.board[(7 - r) * 16 + f] = pieces[c.lower()] | (BLACK if c.islower() else 0)
+            f += 1
+    pos.side = 0 if fields[1] == "w" else 1
+
+    if rules.castles:
+        for c in fields[2].replace("-", ""):
+            side = 1 if c.islower() else 0
+            home = side * 7 * 16
+            rook = ROOK | side * BLACK
+            king_file = file([sq for sq in pos.kings(side) if rank(sq) == side * 7][0])
+            c = c.lower()
+            if c == "k":
+                rook_file = next(f for f in range(7, king_file, -1) if pos.board[home + f] == rook)
+            elif c == "q":
+                rook_file = next(f for f in range(king_file) if pos.board[home + f] == rook)
+            else:
+                rook_file = ord(c) - ord("a")
+            pos.castling[side][int(rook_file > king_file)] = rook_file
+
+    if fields[3] != "-":
+        pos.ep = (int(fields[3][1]) - 1) * 16 + ord(fields[3][0]) - ord("a")
+    if len(fields) > 4 and "+" in fields[4]:
+        white_left, black_left = fields[4].split("+")
+        pos.checks = [3 - int(white_left), 3 - int(black_left)]
+    return pos
+
+
+def main():
+    rules = RULES[sys.argv[1]]
+    depth = int(sys.argv[2])
+    pos = parse_fen(sys.argv[3], rules)
+    for d in range(1, depth + 1):
+        print(f"D{d} {perft(pos, rules, d)}", flush=True)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,652 @@
+#!/usr/bin/env python3
+# Writes the small syzygy tables used by the tests in src/syzygy.rs: KQvK, KRvK and KPvK with wdl
+# and dtz, and KNvK with wdl only. They are solved here by retrograde analysis and written in the
+# syzygy file format (the layout read by Stockfish's tbprobe). Like the published tables the values
+# are pair compressed and then huffman coded, though the pairs and codes chosen differ, so the
+# values are the same as the published tables and the bytes are not. Run it from this directory:
+# python3 generate.py
+
+import hashlib
+import heapq
+from collections import Counter, deque
+
+WDL_MAGIC = bytes([0x71, 0xE8, 0x23, 0x5D])
+DTZ_MAGIC = bytes([0xD7, 0x66, 0x0C, 0xA5])
+
+# header flags
+SPLIT = 1
+HAS_PAWNS = 2
+# pairs data flags
+STM = 1
+WIN_PLIES = 4
+LOSS_PLIES = 8
+SINGLE_VALUE = 128
+
+BLOCK_SIZE_LOG = 9
+SPAN_LOG = 9
+
+# symbols are numbered with 12 bits and 0xfff marks a value, a symbol expands to at most 256
+# values, and the values in a block are counted in 16 bits
+MAX_SYMBOLS = 0xFFF
+MAX_SYMBOL_VALUES = 256
+MAX_BLOCK_VALUES = 1 << 16
+# pairs that occur less often than this aren't worth a symbol
+MIN_PAIR_COUNT = 16
+# pair symbols are numbered from here while they're found, values are always below it
+PAIR_SYMBOL_BASE = 1 << 12
+
+# piece codes as stored in the tables, black pieces have the 8 bit set
+PAWN, KNIGHT, BISHOP, ROOK, QUEEN, KING = 1, 2, 3, 4, 5, 6
+BLACK = 8
+
+WIN, DRAW, LOSS = 2, 0, -2
+
+
+def rank(sq):
+    return sq // 8
+
+
+def file(sq):
+    return sq % 8
+
+
+def king_distance(a, b):
+    return max(abs(rank(a) - rank(b)), abs(file(a) - file(b)))
+
+
+def step_targets(sq, steps):
+    targets = []
+    for dr, df in steps:
+        r, f = rank(sq) + dr, file(sq) + df
+        if 0 <= r < 8 and 0 <= f < 8:
+            targets.append(r * 8 + f)
+    return targets
+
+
+KING_STEPS = [(dr, df) for dr in (-1, 0, 1) for df in (-1, 0, 1) if dr or df]
+KNIGHT_STEPS = [(1, 2), (2, 1), (-1, 2), (-2, 1), (1, -2), (2, -1), (-1, -2), (-2, -1)]
+ROOK_DIRS = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+BISHOP_DIRS = [(1, 1), (1, -1), (-1, 1), (-1, -1)]
+
+
+def slide_targets(sq, dirs, occupied):
+    targets = []
+    for dr, df in dirs:
+        r, f = rank(sq) + dr, file(sq) + df
+        while 0 <= r < 8 and 0 <= f < 8:
+            target = r * 8 + f
+            targets.append(target)
+            if target in occupied:
+                break
+            r, f = r + dr, f + df
+    return targets
+
+
+# squares attacked by the white piece, sliders are blocked by anything in `occupied`
+def attacks(piece, sq, occupied):
+    if piece == KNIGHT:
+        return step_targets(sq, KNIGHT_STEPS)
+    if piece == BISHOP:
+        return slide_targets(sq, BISHOP_DIRS, occupied)
+    if piece == ROOK:
+        return slide_targets(sq, ROOK_DIRS, occupied)
+    if piece == QUEEN:
+        return slide_targets(sq, ROOK_DIRS + BISHOP_DIRS, occupied)
+    if piece == PAWN:
+        return step_targets(sq, [(1, -1), (1, 1)])
+    raise ValueError(piece)
+
+
+# a position of a white king and piece against a black king is (white king, piece, black king,
+# black to move), and the solved tables map each legal position to (wdl, dtz) for the side to move
+
+
+def is_legal(piece, wk, wx, bk, black_to_move):
+    if len({wk, wx, bk}) < 3 or king_distance(wk, bk) < 2:
+        return False
+    if piece == PAWN and rank(wx) in (0, 7):
+        return False
+    # the side that isn't to move can't be in check
+    return black_to_move or bk not in attacks(piece, wx, {wk, bk})
+
+
+# the moves of a position as (position after the move or none when it leaves the table, whether
+# it resets the fifty move counter, the wdl after the move from the moving side's perspective when
+# it leaves the table)
+def moves(piece, wk, wx, bk, black_to_move, promotions):
+    result = []
+    if black_to_move:
+        attacked = set(attacks(piece, wx, {wk}))
+        for to in step_targets(bk, KING_STEPS):
+            if to == wk or king_distance(to, wk) < 2 or to in attacked:
+                continue
+            if to == wx:
+                # a lone king against a lone king is a draw
+                result.append((None, True, DRAW))
+            else:
+                result.append(((wk, wx, to, False), False, None))
+        return result
+
+    for to in step_targets(wk, KING_STEPS):
+        if to != wx and king_distance(to, bk) >= 2:
+            result.append(((to, wx, bk, True), False, None))
+
+    if piece == PAWN:
+        pushes = []
+        if wx + 8 not in (wk, bk):
+            pushes.append(wx + 8)
+            if rank(wx) == 1 and wx + 16 not in (wk, bk):
+                pushes.append(wx + 16)
+        for to in pushes:
+            if rank(to) == 7:
+                for solved in promotions.values():
+                    result.append((None, True, -solved[(wk, to, bk, True)][0]))
+            else:
+                result.append(((wk, to, bk, True), True, None))
+        return result
+
+    for to in attacks(piece, wx, {wk, bk}):
+        if to not in (wk, bk):
+            result.append(((wk, to, bk, True), False, None))
+    return result
+
+
+def in_check(piece, wk, wx, bk, black_to_move):
+    return black_to_move and bk in attacks(piece, wx, {wk})
+
+
+def solve(piece, promotions=None):
+    promotions = promotions or {}
+    positions = [
+        (wk, wx, bk, black_to_move)
+        for wk in range(64)
+        for wx in range(64)
+        for bk in range(64)
+        for black_to_move in (False, True)
+        if is_legal(piece, wk, wx, bk, black_to_move)
+    ]
+    all_moves = {pos: moves(piece, *pos, promotions) for pos in positions}
+    parents = {pos: [] for pos in positions}
+    for pos, pos_moves in all_moves.items():
+        for child, _, _ in pos_moves:
+            if child is not None:
+                parents[child].append(pos)
+
+    # wdl, every move counts including the pawn moves that stay in the table
+    wdl = {}
+    unresolved_moves = {}
+    queue = deque()
+    for pos, pos_moves in all_moves.items():
+        if not pos_moves:
+            wdl[pos] = LOSS if in_check(piece, *pos) else DRAW
+            if wdl[pos] == LOSS:
+                queue.append(pos)
+            continue
+        exits = [value for child, _, value in pos_moves if child is None]
+        if WIN in exits:
+            wdl[pos] = WIN
+            queue.append(pos)
+            continue
+        unresolved_moves[pos] = len(pos_moves) - exits.count(LOSS)
+        if unresolved_moves[pos] == 0:
+            wdl[pos] = LOSS
+            queue.append(pos)
+
+    while queue:
+        pos = queue.popleft()
+        for parent in parents[pos]:
+            if parent in wdl:
+                continue
+            if wdl[pos] == LOSS:
+                wdl[parent] = WIN
+                queue.append(parent)
+            else:
+                unresolved_moves[parent] -= 1
+                if unresolved_moves[parent] == 0:
+                    wdl[parent] = LOSS
+                    queue.append(parent)
+    for pos in positions:
+        wdl.setdefault(pos, DRAW)
+
+    # dtz, plies to the next move that resets the fifty move counter or mates. a win takes the
+    # fastest way there and a loss the slowest
+    dtz = {}
+    levels = [[]]
+    waiting = {}
+    for pos, pos_moves in all_moves.items():
+        if wdl[pos] == DRAW:
+            dtz[pos] = 0
+        elif not pos_moves:
+            dtz[pos] = 0
+            levels[0].append(pos)
+        elif wdl[pos] == WIN:
+            if any(
+                zeroing and (child is None and value == WIN or child and wdl[child] == LOSS)
+                for child, zeroing, value in pos_moves
+            ):
+                dtz[pos] = 1
+        else:
+            waiting[pos] = sum(1 for child, zeroing, _ in pos_moves if not zeroing)
+            if waiting[pos] == 0:
+                dtz[pos] = -1
+    levels.append([pos for pos, value in dtz.items() if abs(value) == 1])
+
+    n = 0
+    while n < len(levels):
+        for pos in levels[n]:
+            for parent in parents[pos]:
+                if parent in dtz:
+                    continue
+                zeroing = next(z for child, z, _ in all_moves[parent] if child == pos)
+                if zeroing:
+                    continue
+                if wdl[parent] == WIN and wdl[pos] == LOSS:
+                    dtz[parent] = n + 1
+                elif wdl[parent] == LOSS and wdl[pos] == WIN:
+                    waiting[parent] -= 1
+                    if waiting[parent] != 0:
+                        continue
+                    dtz[parent] = -(n + 1)
+                else:
+                    continue
+                while len(levels) <= n + 1:
+                    levels.append([])
+                levels[n + 1].append(parent)
+        n += 1
+    assert len(dtz) == len(positions)
+
+    return {pos: (wdl[pos], dtz[pos]) for pos in positions}
+
+
+# indexing, ported from the encoding in tbprobe
+
+def off_a1h8(sq):
+    return rank(sq) - file(sq)
+
+
+def flip_diagonal(sq):
+    return ((sq >> 3) | (sq << 3)) & 63
+
+
+def binomial(k, n):
+    if k > n:
+        return 0
+    result = 1
+    for i in range(k):
+        result = result * (n - i) // (i + 1)
+    return result
+
+
+MAP_B1H1H7 = [0] * 64
+MAP_A1D1D4 = [0] * 64
+MAP_PAWNS = [0] * 64
+LEAD_PAWN_IDX = [0] * 64
+
+
+def init_encoding():
+    code = 0
+    for sq in range(64):
+        if off_a1h8(sq) < 0:
+            MAP_B1H1H7[sq] = code
+            code += 1
+    for sq in range(64):
+        if off_a1h8(sq) == 0:
+            MAP_B1H1H7[sq] = code
+            code += 1
+
+    code = 0
+    diagonal = []
+    for sq in [0, 1, 2, 3, 9, 10, 11, 18, 19, 27]:
+        if off_a1h8(sq) < 0:
+            MAP_A1D1D4[sq] = code
+            code += 1
+        elif off_a1h8(sq) == 0:
+            diagonal.append(sq)
+    for sq in diagonal:
+        MAP_A1D1D4[sq] = code
+        code += 1
+
+    available = 48
+    for f in range(4):
+        for r in range(1, 7):
+            sq = r * 8 + f
+            available -= 1
+            MAP_PAWNS[sq] = available
+            available -= 1
+            MAP_PAWNS[sq ^ 7] = available
+            LEAD_PAWN_IDX[sq] = r - 1
+
+
+init_encoding()
+
+UNIQUE_PIECES_SIZE = 31332
+LEAD_PAWN_SIZE = 6
+
+
+# index of three unique pieces in table order, with the first piece moved into the a1-d1-d4
+# triangle
+def unique_pieces_idx(squares):
+    squares = list(squares)
+    if file(squares[0]) > 3:
+        squares = [sq ^ 7 for sq in squares]
+    if rank(squares[0]) > 3:
+        squares = [sq ^ 56 for sq in squares]
+    for i in range(3):
+        off = off_a1h8(squares[i])
+        if off == 0:
+            continue
+        if off > 0:
+            squares[i:] = [flip_diagonal(sq) for sq in squares[i:]]
+        break
+
+    s0, s1, s2 = squares
+    adjust1 = int(s1 > s0)
+    adjust2 = int(s2 > s0) + int(s2 > s1)
+    if off_a1h8(s0):
+        return (MAP_A1D1D4[s0] * 63 + (s1 - adjust1)) * 62 + s2 - adjust2
+    if off_a1h8(s1):
+        return (6 * 63 + rank(s0) * 28 + MAP_B1H1H7[s1]) * 62 + s2 - adjust2
+    if off_a1h8(s2):
+        return 6 * 63 * 62 + 4 * 28 * 62 + rank(s0) * 7 * 28 + (rank(s1) - adjust1) * 28 + MAP_B1H1H7[s2]
+    return (
+        6 * 63 * 62
+        + 4 * 28 * 62
+        + 4 * 7 * 28
+        + rank(s0) * 7 * 6
+        + (rank(s1) - adjust1) * 6
+        + (rank(s2) - adjust2)
+    )
+
+
+# index of a lone pawn followed by two more pieces, and the file item it's stored in
+def pawn_idx(squares):
+    squares = list(squares)
+    if file(squares[0]) > 3:
+        squares = [sq ^ 7 for sq in squares]
+    idx = LEAD_PAWN_IDX[squares[0]]
+    multiplier = LEAD_PAWN_SIZE
+    for i in range(1, 3):
+        sq = squares[i]
+        adjust = sum(1 for earlier in squares[:i] if sq > earlier)
+        idx += (sq - adjust) * multiplier
+        multiplier *= 64 - i
+    return file(squares[0]), idx
+
+
+PAWN_TABLE_SIZE = LEAD_PAWN_SIZE * 63 * 62
+
+
+# writing
+
+
+# the most common pair of adjacent symbols becomes a new symbol until no pair is common enough.
+# returns the symbols the values are written with, the pair each symbol stands for (None for a
+# value) and the number of values each symbol expands to
+def pair_symbols(values):
+    seq = list(values)
+    pairs = {value: None for value in set(values)}
+    expand = {value: 1 for value in set(values)}
+    while len(pairs) < MAX_SYMBOLS:
+        counts = Counter(zip(seq, seq[1:]))
+        candidates = [
+            (count, pair)
+            for pair, count in counts.items()
+            if expand[pair[0]] + expand[pair[1]] <= MAX_SYMBOL_VALUES
+        ]
+        if not candidates:
+            break
+        count, pair = max(candidates)
+        if count < MIN_PAIR_COUNT:
+            break
+
+        sym = PAIR_SYMBOL_BASE + len(pairs)
+        pairs[sym] = pair
+        expand[sym] = expand[pair[0]] + expand[pair[1]]
+        out = []
+        i = 0
+        while i < len(seq):
+            if tuple(seq[i : i + 2]) == pair:
+                out.append(sym)
+                i += 2
+            else:
+                out.append(seq[i])
+                i += 1
+        seq = out
+    return seq, pairs, expand
+
+
+def huffman_lengths(counts):
+    if len(counts) == 1:
+        return {next(iter(counts)): 1}
+    heap = [(count, i, [symbol]) for i, (symbol, count) in enumerate(sorted(counts.items()))]
+    heapq.heapify(heap)
+    lengths = Counter()
+    tiebreak = len(heap)
+    while len(heap) > 1:
+        count_a, _, symbols_a = heapq.heappop(heap)
+        count_b, _, symbols_b = heapq.heappop(heap)
+        for symbol in symbols_a + symbols_b:
+            lengths[symbol] += 1
+        heapq.heappush(heap, (count_a + count_b, tiebreak, symbols_a + symbols_b))
+        tiebreak += 1
+    return dict(lengths)
+
+
+class Item:
+    def __init__(self, values, flags=0):
+        self.flags = flags
+        counts = Counter(values)
+        if len(counts) == 1:
+            self.flags |= SINGLE_VALUE
+            self.single_value = values[0]
+            return
+
+        seq, self.pairs, self.expand = pair_symbols(values)
+        # symbols that only appear inside other pairs still need a number, so they get a code too
+        counts = Counter(seq)
+        for sym in self.pairs:
+            counts.setdefault(sym, 0)
+        lengths = huffman_lengths(counts)
+        self.min_len = min(lengths.values())
+        self.max_len = max(lengths.values())
+        # the decoder keeps at least 32 bits of the block in its buffer
+        assert self.max_len <= 32
+        # symbols are numbered from the longest codes to the shortest, and within a length the
+        # codes count up from the lowest free code of that length
+        by_length = sorted(lengths, key=lambda sym: (-lengths[sym], sym))
+        self.symbols = by_length
+        size = self.max_len - self.min_len + 1
+        count = [0] * size
+        for value in by_length:
+            count[lengths[value] - self.min_len] += 1
+        self.lowest_sym = [0] * size
+        base = [0] * size
+        for i in range(size - 2, -1, -1):
+            self.lowest_sym[i] = self.lowest_sym[i + 1] + count[i + 1]
+            total = base[i + 1] + count[i + 1]
+            assert total % 2 == 0
+            base[i] = total // 2
+        assert base[0] + count[0] == 1 << self.min_len
+
+        codes = {}
+        self.numbers = {}
+        for number, sym in enumerate(by_length):
+            i = lengths[sym] - self.min_len
+            codes[sym] = (base[i] + number - self.lowest_sym[i], lengths[sym])
+            self.numbers[sym] = number
+
+        self.blocks, self.block_starts = encode_blocks(seq, codes, self.expand)
+        self.sparse_index = sparse_index(len(values), self.block_starts)
+
+    def sizes(self):
+        if self.flags & SINGLE_VALUE:
+            return bytes([self.flags, self.single_value])
+        out = bytearray([self.flags, BLOCK_SIZE_LOG, SPAN_LOG, 0])
+        out += len(self.blocks).to_bytes(4, "little")
+        out += bytes([self.max_len, self.min_len])
+        for lowest in self.lowest_sym:
+            out += lowest.to_bytes(2, "little")
+        out += len(self.symbols).to_bytes(2, "little")
+        for sym in self.symbols:
+            # 12 bits each for the left and right of a pair, a value has 0xfff on the right and
+            # the value on the left
+            if self.pairs[sym] is None:
+                left, right = sym, 0xFFF
+            else:
+                left, right = (self.numbers[half] for half in self.pairs[sym])
+            out += bytes([left & 0xFF, (left >> 8) | (right & 0xF) << 4, right >> 4])
+        if len(self.symbols) % 2:
+            out.append(0)
+        return bytes(out)
+
+    def sparse_bytes(self):
+        if self.flags & SINGLE_VALUE:
+            return b""
+        out = bytearray()
+        for block, offset in self.sparse_index:
+            out += block.to_bytes(4, "little") + offset.to_bytes(2, "little")
+        return bytes(out)
+
+    def block_length_bytes(self):
+        if self.flags & SINGLE_VALUE:
+            return b""
+        ends = self.block_starts[1:]
+        out = bytearray()
+        for start, end in zip(self.block_starts, ends):
+            out += (end - start - 1).to_bytes(2, "little")
+        return bytes(out)
+
+    def data(self):
+        if self.flags & SINGLE_VALUE:
+            return b""
+        return b"".join(self.blocks)
+
+
+# packs the codes of the symbols into blocks, a code never crosses into the next block. returns
+# the blocks and the index of the first value of each block, with the number of values at the end
+def encode_blocks(seq, codes, expand):
+    block_bits = (1 << BLOCK_SIZE_LOG) * 8
+    blocks = []
+    starts = [0]
+    bits = 0
+    used = 0
+    value_idx = 0
+    for sym in seq:
+        code, length = codes[sym]
+        if used + length > block_bits or value_idx + expand[sym] - starts[-1] > MAX_BLOCK_VALUES:
+            blocks.append((bits << (block_bits - used)).to_bytes(block_bits // 8, "big"))
+            starts.append(value_idx)
+            bits = 0
+            used = 0
+        bits = (bits << length) | code
+        used += length
+        value_idx += expand[sym]
+    blocks.append((bits << (block_bits - used)).to_bytes(block_bits // 8, "big"))
+    starts.append(value_idx)
+    return blocks, starts
+
+
+# the block and offset within it of the value in the middle of each span
+def sparse_index(size, starts):
+    span = 1 << SPAN_LOG
+    entries = []
+    block = 0
+    last_block = len(starts) - 2
+    for k in range((size + span - 1) // span):
+        middle = k * span + span // 2
+        while block < last_block and starts[block + 1] <= middle:
+            block += 1
+        offset = middle - starts[block]
+        assert offset < 1 << 16
+        entries.append((block, offset))
+    return entries
+
+
+def write_table(path, magic, header_flags, piece_bytes, items_by_file):
+    out = bytearray(magic)
+    out.append(header_flags)
+    for pieces in piece_bytes:
+        # the lead group comes first and there is no second pawn group
+        out.append(0x00)
+        out += bytes(pieces)
+    if len(out) % 2:
+        out.append(0)
+
+    items = [item for file_items in items_by_file for item in file_items]
+    for item in items:
+        out += item.sizes()
+    if magic == DTZ_MAGIC and len(out) % 2:
+        out.append(0)
+    for item in items:
+        out += item.sparse_bytes()
+    for item in items:
+        out += item.block_length_bytes()
+    for item in items:
+        out += bytes(-len(out) % 64)
+        out += item.data()
+    out += bytes(-len(out) % 64)
+    out += hashlib.md5(out).digest()
+
+    with open(path, "wb") as f:
+        f.write(out)
+
+
+def fill(table, value):
+    return [value if v is None else v for v in table]
+
+
+def most_common(values):
+    return Counter(v for v in values if v is not None).most_common(1)[0][0]
+
+
+def set_value(table, idx, value):
+    assert table[idx] in (None, value), "two positions with one index disagree"
+    table[idx] = value
+
+
+def write_piece_table(name, piece, solved, with_dtz=True):
+    # pieces in table order: white king, white piece, black king
+    order = [KING, piece, KING | BLACK]
+    wdl = [[None] * UNIQUE_PIECES_SIZE, [None] * UNIQUE_PIECES_SIZE]
+    dtz = [None] * UNIQUE_PIECES_SIZE
+    for (wk, wx, bk, black_to_move), (value, distance) in solved.items():
+        idx = unique_pieces_idx([wk, wx, bk])
+        set_value(wdl[black_to_move], idx, value + 2)
+        if not black_to_move:
+            set_value(dtz, idx, max(abs(distance) - 1, 0))
+
+    piece_bytes = [[code | code << 4 for code in order]]
+    items = [Item(fill(side, most_common(side))) for side in wdl]
+    write_table(name + ".rtbw", WDL_MAGIC, SPLIT, piece_bytes, [items])
+    if with_dtz:
+        item = Item(fill(dtz, most_common(dtz)), WIN_PLIES | LOSS_PLIES)
+        write_table(name + ".rtbz", DTZ_MAGIC, 0, piece_bytes, [[item]])
+
+
+def write_pawn_table(name, solved):
+    order = [PAWN, KING, KING | BLACK]
+    wdl = [[[None] * PAWN_TABLE_SIZE for _ in range(2)] for _ in range(4)]
+    dtz = [[None] * PAWN_TABLE_SIZE for _ in range(4)]
+    for (wk, wx, bk, black_to_move), (value, distance) in solved.items():
+        tb_file, idx = pawn_idx([wx, wk, bk])
+        set_value(wdl[tb_file][black_to_move], idx, value + 2)
+        if not black_to_move:
+            set_value(dtz[tb_file], idx, max(abs(distance) - 1, 0))
+
+    piece_bytes = [[code | code << 4 for code in order] for _ in range(4)]
+    wdl_items = [[Item(fill(side, most_common(side))) for side in sides] for sides in wdl]
+    write_table(name + ".rtbw", WDL_MAGIC, SPLIT | HAS_PAWNS, piece_bytes, wdl_items)
+    dtz_items = [[Item(fill(values, most_common(values)), WIN_PLIES | LOSS_PLIES)] for values in dtz]
+    write_table(name + ".rtbz", DTZ_MAGIC, HAS_PAWNS, piece_bytes, dtz_items)
+
+
+def main():
+    solved = {piece: solve(piece) for piece in (QUEEN, ROOK, BISHOP, KNIGHT)}
+    write_piece_table("KQvK", QUEEN, solved[QUEEN])
+    write_piece_table("KRvK", ROOK, solved[ROOK])
+    write_piece_table("KNvK", KNIGHT, solved[KNIGHT], with_dtz=False)
+    write_pawn_table("KPvK", solve(PAWN, solved))
+
+
+if __name__ == "__main__":
+    main()
@@ -1,5 +1,3 @@
-pub mod squares_between;
-
 use crate::util::grid_to_string;
 use std::{
     fmt,
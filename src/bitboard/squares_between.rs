@@ -1,4235 +0,0 @@
-use crate::bitboard::BB;
-use crate::square::Square;
-
-use super::BOARD_LENGTH;
-
-pub fn bb_squares_between(a: Square, b: Square) -> BB {
-    SQUARES_BETWEEN[a.to_usize()][b.to_usize()]
-}
-
-static SQUARES_BETWEEN: [[BB; BOARD_LENGTH]; BOARD_LENGTH] = [
-    [
-        BB(0),
-        BB(0),
-        BB(2),
-        BB(6),
-        BB(14),
-        BB(30),
-        BB(62),
-        BB(126),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(256),
-        BB(0),
-        BB(512),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(65792),
-        BB(0),
-        BB(0),
-        BB(262656),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(16843008),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(134480384),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4311810304),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(68853957120),
-        BB(0),
-        BB(0),
-        BB(1103823438080),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35253226045952),
-        BB(0),
-        BB(282578800148736),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18049651735527936),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4),
-        BB(12),
-        BB(28),
-        BB(60),
-        BB(124),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(512),
-        BB(0),
-        BB(1024),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(131584),
-        BB(0),
-        BB(0),
-        BB(525312),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(33686016),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(268960768),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8623620608),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(137707914240),
-        BB(0),
-        BB(0),
-        BB(2207646876160),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(70506452091904),
-        BB(0),
-        BB(565157600297472),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(2),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8),
-        BB(24),
-        BB(56),
-        BB(120),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(512),
-        BB(0),
-        BB(1024),
-        BB(0),
-        BB(2048),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(263168),
-        BB(0),
-        BB(0),
-        BB(1050624),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(67372032),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(537921536),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17247241216),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(275415828480),
-        BB(0),
-        BB(0),
-        BB(4415293752320),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1130315200594944),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(6),
-        BB(4),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(16),
-        BB(48),
-        BB(112),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1024),
-        BB(0),
-        BB(2048),
-        BB(0),
-        BB(4096),
-        BB(0),
-        BB(0),
-        BB(132096),
-        BB(0),
-        BB(0),
-        BB(526336),
-        BB(0),
-        BB(0),
-        BB(2101248),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(134744064),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1075843072),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(34494482432),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8830587504640),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2260630401189888),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(14),
-        BB(12),
-        BB(8),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(32),
-        BB(96),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2048),
-        BB(0),
-        BB(4096),
-        BB(0),
-        BB(8192),
-        BB(0),
-        BB(0),
-        BB(264192),
-        BB(0),
-        BB(0),
-        BB(1052672),
-        BB(0),
-        BB(0),
-        BB(4202496),
-        BB(33818624),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(269488128),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(68988964864),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17661175009280),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4521260802379776),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(30),
-        BB(28),
-        BB(24),
-        BB(16),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(64),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4096),
-        BB(0),
-        BB(8192),
-        BB(0),
-        BB(16384),
-        BB(0),
-        BB(0),
-        BB(528384),
-        BB(0),
-        BB(0),
-        BB(2105344),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(67637248),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(538976256),
-        BB(0),
-        BB(0),
-        BB(8657571840),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(137977929728),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35322350018560),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9042521604759552),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(62),
-        BB(60),
-        BB(56),
-        BB(48),
-        BB(32),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8192),
-        BB(0),
-        BB(16384),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1056768),
-        BB(0),
-        BB(0),
-        BB(4210688),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(135274496),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1077952512),
-        BB(0),
-        BB(0),
-        BB(17315143680),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(275955859456),
-        BB(0),
-        BB(2216338399232),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(70644700037120),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18085043209519104),
-        BB(0),
-    ],
-    [
-        BB(126),
-        BB(124),
-        BB(120),
-        BB(112),
-        BB(96),
-        BB(64),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(16384),
-        BB(0),
-        BB(32768),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2113536),
-        BB(0),
-        BB(0),
-        BB(8421376),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(270548992),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2155905024),
-        BB(0),
-        BB(0),
-        BB(34630287360),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(551911718912),
-        BB(0),
-        BB(4432676798464),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(141289400074240),
-        BB(567382630219776),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(36170086419038208),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(512),
-        BB(1536),
-        BB(3584),
-        BB(7680),
-        BB(15872),
-        BB(32256),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(65536),
-        BB(0),
-        BB(131072),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(16842752),
-        BB(0),
-        BB(0),
-        BB(67239936),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4311810048),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(34426978304),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1103823437824),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17626613022720),
-        BB(0),
-        BB(0),
-        BB(282578800148480),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9024825867763712),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1024),
-        BB(3072),
-        BB(7168),
-        BB(15360),
-        BB(31744),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(131072),
-        BB(0),
-        BB(262144),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(33685504),
-        BB(0),
-        BB(0),
-        BB(134479872),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8623620096),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(68853956608),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2207646875648),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35253226045440),
-        BB(0),
-        BB(0),
-        BB(565157600296960),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18049651735527424),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(512),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2048),
-        BB(6144),
-        BB(14336),
-        BB(30720),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(131072),
-        BB(0),
-        BB(262144),
-        BB(0),
-        BB(524288),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(67371008),
-        BB(0),
-        BB(0),
-        BB(268959744),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17247240192),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(137707913216),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4415293751296),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(70506452090880),
-        BB(0),
-        BB(0),
-        BB(1130315200593920),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1536),
-        BB(1024),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4096),
-        BB(12288),
-        BB(28672),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(262144),
-        BB(0),
-        BB(524288),
-        BB(0),
-        BB(1048576),
-        BB(0),
-        BB(0),
-        BB(33816576),
-        BB(0),
-        BB(0),
-        BB(134742016),
-        BB(0),
-        BB(0),
-        BB(537919488),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(34494480384),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(275415826432),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8830587502592),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2260630401187840),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(3584),
-        BB(3072),
-        BB(2048),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8192),
-        BB(24576),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(524288),
-        BB(0),
-        BB(1048576),
-        BB(0),
-        BB(2097152),
-        BB(0),
-        BB(0),
-        BB(67633152),
-        BB(0),
-        BB(0),
-        BB(269484032),
-        BB(0),
-        BB(0),
-        BB(1075838976),
-        BB(8657567744),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(68988960768),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17661175005184),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4521260802375680),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(7680),
-        BB(7168),
-        BB(6144),
-        BB(4096),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(16384),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1048576),
-        BB(0),
-        BB(2097152),
-        BB(0),
-        BB(4194304),
-        BB(0),
-        BB(0),
-        BB(135266304),
-        BB(0),
-        BB(0),
-        BB(538968064),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17315135488),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(137977921536),
-        BB(0),
-        BB(0),
-        BB(2216338391040),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35322350010368),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9042521604751360),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(15872),
-        BB(15360),
-        BB(14336),
-        BB(12288),
-        BB(8192),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2097152),
-        BB(0),
-        BB(4194304),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(270532608),
-        BB(0),
-        BB(0),
-        BB(1077936128),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(34630270976),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(275955843072),
-        BB(0),
-        BB(0),
-        BB(4432676782080),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(70644700020736),
-        BB(0),
-        BB(567382630203392),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18085043209502720),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(32256),
-        BB(31744),
-        BB(30720),
-        BB(28672),
-        BB(24576),
-        BB(16384),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4194304),
-        BB(0),
-        BB(8388608),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(541065216),
-        BB(0),
-        BB(0),
-        BB(2155872256),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(69260541952),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(551911686144),
-        BB(0),
-        BB(0),
-        BB(8865353564160),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(141289400041472),
-        BB(0),
-        BB(1134765260406784),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(36170086419005440),
-    ],
-    [
-        BB(256),
-        BB(0),
-        BB(512),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(131072),
-        BB(393216),
-        BB(917504),
-        BB(1966080),
-        BB(4063232),
-        BB(8257536),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(16777216),
-        BB(0),
-        BB(33554432),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4311744512),
-        BB(0),
-        BB(0),
-        BB(17213423616),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1103823372288),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8813306445824),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(282578800082944),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4512412933816320),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(512),
-        BB(0),
-        BB(1024),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(262144),
-        BB(786432),
-        BB(1835008),
-        BB(3932160),
-        BB(8126464),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(33554432),
-        BB(0),
-        BB(67108864),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8623489024),
-        BB(0),
-        BB(0),
-        BB(34426847232),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2207646744576),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17626612891648),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(565157600165888),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9024825867632640),
-        BB(0),
-    ],
-    [
-        BB(512),
-        BB(0),
-        BB(1024),
-        BB(0),
-        BB(2048),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(131072),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(524288),
-        BB(1572864),
-        BB(3670016),
-        BB(7864320),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(33554432),
-        BB(0),
-        BB(67108864),
-        BB(0),
-        BB(134217728),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17246978048),
-        BB(0),
-        BB(0),
-        BB(68853694464),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4415293489152),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35253225783296),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1130315200331776),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18049651735265280),
-    ],
-    [
-        BB(0),
-        BB(1024),
-        BB(0),
-        BB(2048),
-        BB(0),
-        BB(4096),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(393216),
-        BB(262144),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1048576),
-        BB(3145728),
-        BB(7340032),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(67108864),
-        BB(0),
-        BB(134217728),
-        BB(0),
-        BB(268435456),
-        BB(0),
-        BB(0),
-        BB(8657043456),
-        BB(0),
-        BB(0),
-        BB(34493956096),
-        BB(0),
-        BB(0),
-        BB(137707388928),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8830586978304),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(70506451566592),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2260630400663552),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(2048),
-        BB(0),
-        BB(4096),
-        BB(0),
-        BB(8192),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(917504),
-        BB(786432),
-        BB(524288),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2097152),
-        BB(6291456),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(134217728),
-        BB(0),
-        BB(268435456),
-        BB(0),
-        BB(536870912),
-        BB(0),
-        BB(0),
-        BB(17314086912),
-        BB(0),
-        BB(0),
-        BB(68987912192),
-        BB(0),
-        BB(0),
-        BB(275414777856),
-        BB(2216337342464),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17661173956608),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4521260801327104),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4096),
-        BB(0),
-        BB(8192),
-        BB(0),
-        BB(16384),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1966080),
-        BB(1835008),
-        BB(1572864),
-        BB(1048576),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4194304),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(268435456),
-        BB(0),
-        BB(536870912),
-        BB(0),
-        BB(1073741824),
-        BB(0),
-        BB(0),
-        BB(34628173824),
-        BB(0),
-        BB(0),
-        BB(137975824384),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4432674684928),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35322347913216),
-        BB(0),
-        BB(0),
-        BB(567382628106240),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9042521602654208),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8192),
-        BB(0),
-        BB(16384),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4063232),
-        BB(3932160),
-        BB(3670016),
-        BB(3145728),
-        BB(2097152),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(536870912),
-        BB(0),
-        BB(1073741824),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(69256347648),
-        BB(0),
-        BB(0),
-        BB(275951648768),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8865349369856),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(70644695826432),
-        BB(0),
-        BB(0),
-        BB(1134765256212480),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18085043205308416),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(16384),
-        BB(0),
-        BB(32768),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8257536),
-        BB(8126464),
-        BB(7864320),
-        BB(7340032),
-        BB(6291456),
-        BB(4194304),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1073741824),
-        BB(0),
-        BB(2147483648),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(138512695296),
-        BB(0),
-        BB(0),
-        BB(551903297536),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17730698739712),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(141289391652864),
-        BB(0),
-        BB(0),
-        BB(2269530512424960),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(36170086410616832),
-    ],
-    [
-        BB(65792),
-        BB(0),
-        BB(0),
-        BB(132096),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(65536),
-        BB(0),
-        BB(131072),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(33554432),
-        BB(100663296),
-        BB(234881024),
-        BB(503316480),
-        BB(1040187392),
-        BB(2113929216),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4294967296),
-        BB(0),
-        BB(8589934592),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1103806595072),
-        BB(0),
-        BB(0),
-        BB(4406636445696),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(282578783305728),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2256206450130944),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(131584),
-        BB(0),
-        BB(0),
-        BB(264192),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(131072),
-        BB(0),
-        BB(262144),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(67108864),
-        BB(201326592),
-        BB(469762048),
-        BB(1006632960),
-        BB(2080374784),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8589934592),
-        BB(0),
-        BB(17179869184),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2207613190144),
-        BB(0),
-        BB(0),
-        BB(8813272891392),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(565157566611456),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4512412900261888),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(263168),
-        BB(0),
-        BB(0),
-        BB(528384),
-        BB(0),
-        BB(0),
-        BB(131072),
-        BB(0),
-        BB(262144),
-        BB(0),
-        BB(524288),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(33554432),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(134217728),
-        BB(402653184),
-        BB(939524096),
-        BB(2013265920),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8589934592),
-        BB(0),
-        BB(17179869184),
-        BB(0),
-        BB(34359738368),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4415226380288),
-        BB(0),
-        BB(0),
-        BB(17626545782784),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1130315133222912),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9024825800523776),
-        BB(0),
-    ],
-    [
-        BB(262656),
-        BB(0),
-        BB(0),
-        BB(526336),
-        BB(0),
-        BB(0),
-        BB(1056768),
-        BB(0),
-        BB(0),
-        BB(262144),
-        BB(0),
-        BB(524288),
-        BB(0),
-        BB(1048576),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(100663296),
-        BB(67108864),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(268435456),
-        BB(805306368),
-        BB(1879048192),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17179869184),
-        BB(0),
-        BB(34359738368),
-        BB(0),
-        BB(68719476736),
-        BB(0),
-        BB(0),
-        BB(2216203124736),
-        BB(0),
-        BB(0),
-        BB(8830452760576),
-        BB(0),
-        BB(0),
-        BB(35253091565568),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2260630266445824),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18049651601047552),
-    ],
-    [
-        BB(0),
-        BB(525312),
-        BB(0),
-        BB(0),
-        BB(1052672),
-        BB(0),
-        BB(0),
-        BB(2113536),
-        BB(0),
-        BB(0),
-        BB(524288),
-        BB(0),
-        BB(1048576),
-        BB(0),
-        BB(2097152),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(234881024),
-        BB(201326592),
-        BB(134217728),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(536870912),
-        BB(1610612736),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(34359738368),
-        BB(0),
-        BB(68719476736),
-        BB(0),
-        BB(137438953472),
-        BB(0),
-        BB(0),
-        BB(4432406249472),
-        BB(0),
-        BB(0),
-        BB(17660905521152),
-        BB(0),
-        BB(0),
-        BB(70506183131136),
-        BB(567382359670784),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4521260532891648),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(1050624),
-        BB(0),
-        BB(0),
-        BB(2105344),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1048576),
-        BB(0),
-        BB(2097152),
-        BB(0),
-        BB(4194304),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(503316480),
-        BB(469762048),
-        BB(402653184),
-        BB(268435456),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1073741824),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(68719476736),
-        BB(0),
-        BB(137438953472),
-        BB(0),
-        BB(274877906944),
-        BB(0),
-        BB(0),
-        BB(8864812498944),
-        BB(0),
-        BB(0),
-        BB(35321811042304),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1134764719341568),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9042521065783296),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2101248),
-        BB(0),
-        BB(0),
-        BB(4210688),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2097152),
-        BB(0),
-        BB(4194304),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1040187392),
-        BB(1006632960),
-        BB(939524096),
-        BB(805306368),
-        BB(536870912),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(137438953472),
-        BB(0),
-        BB(274877906944),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17729624997888),
-        BB(0),
-        BB(0),
-        BB(70643622084608),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2269529438683136),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18085042131566592),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4202496),
-        BB(0),
-        BB(0),
-        BB(8421376),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4194304),
-        BB(0),
-        BB(8388608),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2113929216),
-        BB(2080374784),
-        BB(2013265920),
-        BB(1879048192),
-        BB(1610612736),
-        BB(1073741824),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(274877906944),
-        BB(0),
-        BB(549755813888),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35459249995776),
-        BB(0),
-        BB(0),
-        BB(141287244169216),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4539058877366272),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(36170084263133184),
-    ],
-    [
-        BB(16843008),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(33818624),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(16842752),
-        BB(0),
-        BB(0),
-        BB(33816576),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(16777216),
-        BB(0),
-        BB(33554432),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8589934592),
-        BB(25769803776),
-        BB(60129542144),
-        BB(128849018880),
-        BB(266287972352),
-        BB(541165879296),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1099511627776),
-        BB(0),
-        BB(2199023255552),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(282574488338432),
-        BB(0),
-        BB(0),
-        BB(1128098930098176),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(33686016),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(67637248),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(33685504),
-        BB(0),
-        BB(0),
-        BB(67633152),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(33554432),
-        BB(0),
-        BB(67108864),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17179869184),
-        BB(51539607552),
-        BB(120259084288),
-        BB(257698037760),
-        BB(532575944704),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2199023255552),
-        BB(0),
-        BB(4398046511104),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(565148976676864),
-        BB(0),
-        BB(0),
-        BB(2256197860196352),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(67372032),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(135274496),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(67371008),
-        BB(0),
-        BB(0),
-        BB(135266304),
-        BB(0),
-        BB(0),
-        BB(33554432),
-        BB(0),
-        BB(67108864),
-        BB(0),
-        BB(134217728),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8589934592),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(34359738368),
-        BB(103079215104),
-        BB(240518168576),
-        BB(515396075520),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2199023255552),
-        BB(0),
-        BB(4398046511104),
-        BB(0),
-        BB(8796093022208),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1130297953353728),
-        BB(0),
-        BB(0),
-        BB(4512395720392704),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(134744064),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(270548992),
-        BB(67239936),
-        BB(0),
-        BB(0),
-        BB(134742016),
-        BB(0),
-        BB(0),
-        BB(270532608),
-        BB(0),
-        BB(0),
-        BB(67108864),
-        BB(0),
-        BB(134217728),
-        BB(0),
-        BB(268435456),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(25769803776),
-        BB(17179869184),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(68719476736),
-        BB(206158430208),
-        BB(481036337152),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4398046511104),
-        BB(0),
-        BB(8796093022208),
-        BB(0),
-        BB(17592186044416),
-        BB(0),
-        BB(0),
-        BB(567347999932416),
-        BB(0),
-        BB(0),
-        BB(2260595906707456),
-        BB(0),
-        BB(0),
-        BB(9024791440785408),
-        BB(0),
-    ],
-    [
-        BB(134480384),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(269488128),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(134479872),
-        BB(0),
-        BB(0),
-        BB(269484032),
-        BB(0),
-        BB(0),
-        BB(541065216),
-        BB(0),
-        BB(0),
-        BB(134217728),
-        BB(0),
-        BB(268435456),
-        BB(0),
-        BB(536870912),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(60129542144),
-        BB(51539607552),
-        BB(34359738368),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(137438953472),
-        BB(412316860416),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8796093022208),
-        BB(0),
-        BB(17592186044416),
-        BB(0),
-        BB(35184372088832),
-        BB(0),
-        BB(0),
-        BB(1134695999864832),
-        BB(0),
-        BB(0),
-        BB(4521191813414912),
-        BB(0),
-        BB(0),
-        BB(18049582881570816),
-    ],
-    [
-        BB(0),
-        BB(268960768),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(538976256),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(268959744),
-        BB(0),
-        BB(0),
-        BB(538968064),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(268435456),
-        BB(0),
-        BB(536870912),
-        BB(0),
-        BB(1073741824),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(128849018880),
-        BB(120259084288),
-        BB(103079215104),
-        BB(68719476736),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(274877906944),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17592186044416),
-        BB(0),
-        BB(35184372088832),
-        BB(0),
-        BB(70368744177664),
-        BB(0),
-        BB(0),
-        BB(2269391999729664),
-        BB(0),
-        BB(0),
-        BB(9042383626829824),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(537921536),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1077952512),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(537919488),
-        BB(0),
-        BB(0),
-        BB(1077936128),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(536870912),
-        BB(0),
-        BB(1073741824),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(266287972352),
-        BB(257698037760),
-        BB(240518168576),
-        BB(206158430208),
-        BB(137438953472),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35184372088832),
-        BB(0),
-        BB(70368744177664),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4538783999459328),
-        BB(0),
-        BB(0),
-        BB(18084767253659648),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1075843072),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2155905024),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1075838976),
-        BB(0),
-        BB(0),
-        BB(2155872256),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1073741824),
-        BB(0),
-        BB(2147483648),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(541165879296),
-        BB(532575944704),
-        BB(515396075520),
-        BB(481036337152),
-        BB(412316860416),
-        BB(274877906944),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(70368744177664),
-        BB(0),
-        BB(140737488355328),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9077567998918656),
-        BB(0),
-        BB(0),
-        BB(36169534507319296),
-    ],
-    [
-        BB(4311810304),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8657571840),
-        BB(0),
-        BB(0),
-        BB(4311810048),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8657567744),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4311744512),
-        BB(0),
-        BB(0),
-        BB(8657043456),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4294967296),
-        BB(0),
-        BB(8589934592),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2199023255552),
-        BB(6597069766656),
-        BB(15393162788864),
-        BB(32985348833280),
-        BB(68169720922112),
-        BB(138538465099776),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(281474976710656),
-        BB(0),
-        BB(562949953421312),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(8623620608),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17315143680),
-        BB(0),
-        BB(0),
-        BB(8623620096),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17315135488),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8623489024),
-        BB(0),
-        BB(0),
-        BB(17314086912),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8589934592),
-        BB(0),
-        BB(17179869184),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4398046511104),
-        BB(13194139533312),
-        BB(30786325577728),
-        BB(65970697666560),
-        BB(136339441844224),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(562949953421312),
-        BB(0),
-        BB(1125899906842624),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(17247241216),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(34630287360),
-        BB(0),
-        BB(0),
-        BB(17247240192),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(34630270976),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17246978048),
-        BB(0),
-        BB(0),
-        BB(34628173824),
-        BB(0),
-        BB(0),
-        BB(8589934592),
-        BB(0),
-        BB(17179869184),
-        BB(0),
-        BB(34359738368),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2199023255552),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8796093022208),
-        BB(26388279066624),
-        BB(61572651155456),
-        BB(131941395333120),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(562949953421312),
-        BB(0),
-        BB(1125899906842624),
-        BB(0),
-        BB(2251799813685248),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(34494482432),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(34494480384),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(69260541952),
-        BB(17213423616),
-        BB(0),
-        BB(0),
-        BB(34493956096),
-        BB(0),
-        BB(0),
-        BB(69256347648),
-        BB(0),
-        BB(0),
-        BB(17179869184),
-        BB(0),
-        BB(34359738368),
-        BB(0),
-        BB(68719476736),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(6597069766656),
-        BB(4398046511104),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17592186044416),
-        BB(52776558133248),
-        BB(123145302310912),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1125899906842624),
-        BB(0),
-        BB(2251799813685248),
-        BB(0),
-        BB(4503599627370496),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(68988964864),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(34426978304),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(68988960768),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(34426847232),
-        BB(0),
-        BB(0),
-        BB(68987912192),
-        BB(0),
-        BB(0),
-        BB(138512695296),
-        BB(0),
-        BB(0),
-        BB(34359738368),
-        BB(0),
-        BB(68719476736),
-        BB(0),
-        BB(137438953472),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(15393162788864),
-        BB(13194139533312),
-        BB(8796093022208),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35184372088832),
-        BB(105553116266496),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2251799813685248),
-        BB(0),
-        BB(4503599627370496),
-        BB(0),
-        BB(9007199254740992),
-        BB(0),
-    ],
-    [
-        BB(68853957120),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(137977929728),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(68853956608),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(137977921536),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(68853694464),
-        BB(0),
-        BB(0),
-        BB(137975824384),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(68719476736),
-        BB(0),
-        BB(137438953472),
-        BB(0),
-        BB(274877906944),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(32985348833280),
-        BB(30786325577728),
-        BB(26388279066624),
-        BB(17592186044416),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(70368744177664),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4503599627370496),
-        BB(0),
-        BB(9007199254740992),
-        BB(0),
-        BB(18014398509481984),
-    ],
-    [
-        BB(0),
-        BB(137707914240),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(275955859456),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(137707913216),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(275955843072),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(137707388928),
-        BB(0),
-        BB(0),
-        BB(275951648768),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(137438953472),
-        BB(0),
-        BB(274877906944),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(68169720922112),
-        BB(65970697666560),
-        BB(61572651155456),
-        BB(52776558133248),
-        BB(35184372088832),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9007199254740992),
-        BB(0),
-        BB(18014398509481984),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(275415828480),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(551911718912),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(275415826432),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(551911686144),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(275414777856),
-        BB(0),
-        BB(0),
-        BB(551903297536),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(274877906944),
-        BB(0),
-        BB(549755813888),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(138538465099776),
-        BB(136339441844224),
-        BB(131941395333120),
-        BB(123145302310912),
-        BB(105553116266496),
-        BB(70368744177664),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18014398509481984),
-        BB(0),
-        BB(36028797018963968),
-    ],
-    [
-        BB(1103823438080),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2216338399232),
-        BB(0),
-        BB(1103823437824),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2216338391040),
-        BB(0),
-        BB(0),
-        BB(1103823372288),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2216337342464),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1103806595072),
-        BB(0),
-        BB(0),
-        BB(2216203124736),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1099511627776),
-        BB(0),
-        BB(2199023255552),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(562949953421312),
-        BB(1688849860263936),
-        BB(3940649673949184),
-        BB(8444249301319680),
-        BB(17451448556060672),
-        BB(35465847065542656),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(2207646876160),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4432676798464),
-        BB(0),
-        BB(2207646875648),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4432676782080),
-        BB(0),
-        BB(0),
-        BB(2207646744576),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4432674684928),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2207613190144),
-        BB(0),
-        BB(0),
-        BB(4432406249472),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2199023255552),
-        BB(0),
-        BB(4398046511104),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1125899906842624),
-        BB(3377699720527872),
-        BB(7881299347898368),
-        BB(16888498602639360),
-        BB(34902897112121344),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(4415293752320),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4415293751296),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8865353564160),
-        BB(0),
-        BB(0),
-        BB(4415293489152),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8865349369856),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4415226380288),
-        BB(0),
-        BB(0),
-        BB(8864812498944),
-        BB(0),
-        BB(0),
-        BB(2199023255552),
-        BB(0),
-        BB(4398046511104),
-        BB(0),
-        BB(8796093022208),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(562949953421312),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2251799813685248),
-        BB(6755399441055744),
-        BB(15762598695796736),
-        BB(33776997205278720),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8830587504640),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8830587502592),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8830586978304),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17730698739712),
-        BB(4406636445696),
-        BB(0),
-        BB(0),
-        BB(8830452760576),
-        BB(0),
-        BB(0),
-        BB(17729624997888),
-        BB(0),
-        BB(0),
-        BB(4398046511104),
-        BB(0),
-        BB(8796093022208),
-        BB(0),
-        BB(17592186044416),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1688849860263936),
-        BB(1125899906842624),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4503599627370496),
-        BB(13510798882111488),
-        BB(31525197391593472),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17661175009280),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17661175005184),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8813306445824),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17661173956608),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8813272891392),
-        BB(0),
-        BB(0),
-        BB(17660905521152),
-        BB(0),
-        BB(0),
-        BB(35459249995776),
-        BB(0),
-        BB(0),
-        BB(8796093022208),
-        BB(0),
-        BB(17592186044416),
-        BB(0),
-        BB(35184372088832),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(3940649673949184),
-        BB(3377699720527872),
-        BB(2251799813685248),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9007199254740992),
-        BB(27021597764222976),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35322350018560),
-        BB(0),
-        BB(0),
-        BB(17626613022720),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35322350010368),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17626612891648),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35322347913216),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17626545782784),
-        BB(0),
-        BB(0),
-        BB(35321811042304),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17592186044416),
-        BB(0),
-        BB(35184372088832),
-        BB(0),
-        BB(70368744177664),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(8444249301319680),
-        BB(7881299347898368),
-        BB(6755399441055744),
-        BB(4503599627370496),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18014398509481984),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(35253226045952),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(70644700037120),
-        BB(0),
-        BB(0),
-        BB(35253226045440),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(70644700020736),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35253225783296),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(70644695826432),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35253091565568),
-        BB(0),
-        BB(0),
-        BB(70643622084608),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35184372088832),
-        BB(0),
-        BB(70368744177664),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(17451448556060672),
-        BB(16888498602639360),
-        BB(15762598695796736),
-        BB(13510798882111488),
-        BB(9007199254740992),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(0),
-        BB(70506452091904),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(141289400074240),
-        BB(0),
-        BB(0),
-        BB(70506452090880),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(141289400041472),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(70506451566592),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(141289391652864),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(70506183131136),
-        BB(0),
-        BB(0),
-        BB(141287244169216),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(70368744177664),
-        BB(0),
-        BB(140737488355328),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(35465847065542656),
-        BB(34902897112121344),
-        BB(33776997205278720),
-        BB(31525197391593472),
-        BB(27021597764222976),
-        BB(18014398509481984),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(282578800148736),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(567382630219776),
-        BB(282578800148480),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(567382630203392),
-        BB(0),
-        BB(282578800082944),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(567382628106240),
-        BB(0),
-        BB(0),
-        BB(282578783305728),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(567382359670784),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(282574488338432),
-        BB(0),
-        BB(0),
-        BB(567347999932416),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(281474976710656),
-        BB(0),
-        BB(562949953421312),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(144115188075855872),
-        BB(432345564227567616),
-        BB(1008806316530991104),
-        BB(2161727821137838080),
-        BB(4467570830351532032),
-        BB(9079256848778919936),
-    ],
-    [
-        BB(0),
-        BB(565157600297472),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(565157600296960),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1134765260406784),
-        BB(0),
-        BB(565157600165888),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1134765256212480),
-        BB(0),
-        BB(0),
-        BB(565157566611456),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1134764719341568),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(565148976676864),
-        BB(0),
-        BB(0),
-        BB(1134695999864832),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(562949953421312),
-        BB(0),
-        BB(1125899906842624),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(288230376151711744),
-        BB(864691128455135232),
-        BB(2017612633061982208),
-        BB(4323455642275676160),
-        BB(8935141660703064064),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(1130315200594944),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1130315200593920),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1130315200331776),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2269530512424960),
-        BB(0),
-        BB(0),
-        BB(1130315133222912),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2269529438683136),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1130297953353728),
-        BB(0),
-        BB(0),
-        BB(2269391999729664),
-        BB(0),
-        BB(0),
-        BB(562949953421312),
-        BB(0),
-        BB(1125899906842624),
-        BB(0),
-        BB(2251799813685248),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(144115188075855872),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(576460752303423488),
-        BB(1729382256910270464),
-        BB(4035225266123964416),
-        BB(8646911284551352320),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2260630401189888),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2260630401187840),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2260630400663552),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2260630266445824),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4539058877366272),
-        BB(1128098930098176),
-        BB(0),
-        BB(0),
-        BB(2260595906707456),
-        BB(0),
-        BB(0),
-        BB(4538783999459328),
-        BB(0),
-        BB(0),
-        BB(1125899906842624),
-        BB(0),
-        BB(2251799813685248),
-        BB(0),
-        BB(4503599627370496),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(432345564227567616),
-        BB(288230376151711744),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1152921504606846976),
-        BB(3458764513820540928),
-        BB(8070450532247928832),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4521260802379776),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4521260802375680),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4521260801327104),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2256206450130944),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4521260532891648),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2256197860196352),
-        BB(0),
-        BB(0),
-        BB(4521191813414912),
-        BB(0),
-        BB(0),
-        BB(9077567998918656),
-        BB(0),
-        BB(0),
-        BB(2251799813685248),
-        BB(0),
-        BB(4503599627370496),
-        BB(0),
-        BB(9007199254740992),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(1008806316530991104),
-        BB(864691128455135232),
-        BB(576460752303423488),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2305843009213693952),
-        BB(6917529027641081856),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9042521604759552),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9042521604751360),
-        BB(0),
-        BB(0),
-        BB(4512412933816320),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9042521602654208),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4512412900261888),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9042521065783296),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4512395720392704),
-        BB(0),
-        BB(0),
-        BB(9042383626829824),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4503599627370496),
-        BB(0),
-        BB(9007199254740992),
-        BB(0),
-        BB(18014398509481984),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(2161727821137838080),
-        BB(2017612633061982208),
-        BB(1729382256910270464),
-        BB(1152921504606846976),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4611686018427387904),
-    ],
-    [
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18085043209519104),
-        BB(0),
-        BB(9024825867763712),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18085043209502720),
-        BB(0),
-        BB(0),
-        BB(9024825867632640),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18085043205308416),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9024825800523776),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18085042131566592),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9024791440785408),
-        BB(0),
-        BB(0),
-        BB(18084767253659648),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9007199254740992),
-        BB(0),
-        BB(18014398509481984),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(4467570830351532032),
-        BB(4323455642275676160),
-        BB(4035225266123964416),
-        BB(3458764513820540928),
-        BB(2305843009213693952),
-        BB(0),
-        BB(0),
-        BB(0),
-    ],
-    [
-        BB(18049651735527936),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(36170086419038208),
-        BB(0),
-        BB(18049651735527424),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(36170086419005440),
-        BB(0),
-        BB(0),
-        BB(18049651735265280),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(36170086410616832),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18049651601047552),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(36170084263133184),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18049582881570816),
-        BB(0),
-        BB(0),
-        BB(36169534507319296),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(18014398509481984),
-        BB(0),
-        BB(36028797018963968),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(0),
-        BB(9079256848778919936),
-        BB(8935141660703064064),
-        BB(8646911284551352320),
-        BB(8070450532247928832),
-        BB(6917529027641081856),
-        BB(4611686018427387904),
-        BB(0),
-        BB(0),
-    ],
-];
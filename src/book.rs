@@ -0,0 +1,337 @@
+use std::fs;
+
+use crate::{
+    game::Game,
+    mv::{castle::Castle, Move},
+    piece_type::PieceType,
+    square::Square,
+    state::polyglot::polyglot_key,
+    uci::algebra_to_move,
+};
+
+// polyglot book entries are 16 bytes, all big endian
+// u64 key, u16 move, u16 weight, u32 learn
+pub const ENTRY_SIZE: usize = 16;
+
+pub const PROMOTION_CHARS: [char; 5] = [' ', 'n', 'b', 'r', 'q'];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BookEntry {
+    pub key: u64,
+    pub mv: u16,
+    pub weight: u16,
+    pub learn: u32,
+}
+
+impl BookEntry {
+    fn from_bytes(bytes: &[u8]) -> BookEntry {
+        BookEntry {
+            key: u64::from_be_bytes(bytes[0..8].try_into().unwrap()),
+            mv: u16::from_be_bytes(bytes[8..10].try_into().unwrap()),
+            weight: u16::from_be_bytes(bytes[10..12].try_into().unwrap()),
+            learn: u32::from_be_bytes(bytes[12..16].try_into().unwrap()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BookSelection {
+    // always the move with the highest weight
+    Best,
+    // any move, with the odds of each move set by its weight
+    WeightedRandom,
+}
+
+pub struct Book {
+    // sorted by key so the moves of a position are next to each other
+    entries: Vec<BookEntry>,
+}
+
+impl Book {
+    pub fn new(mut entries: Vec<BookEntry>) -> Book {
+        entries.sort_by(|a, b| a.key.cmp(&b.key).then(b.weight.cmp(&a.weight)));
+        Book { entries }
+    }
+
+    pub fn load(path: &str) -> Result<Book, String> {
+        let bytes =
+            fs::read(path).map_err(|err| format!("unable to read book from {}: {}", path, err))?;
+
+        Book::from_bytes(&bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Book, String> {
+        if !bytes.len().is_multiple_of(ENTRY_SIZE) {
+            return Err("book file is not made of whole entries".to_string());
+        }
+
+        Ok(Book::new(
+            bytes
+                .chunks_exact(ENTRY_SIZE)
+                .map(BookEntry::from_bytes)
+                .collect(),
+        ))
+    }
+
+    pub fn entries(&self, key: u64) -> &[BookEntry] {
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let end = self.entries.partition_point(|entry| entry.key <= key);
+        &self.entries[start..end]
+    }
+
+    // the legal book moves for the position along with their weights, moves that can't be
+    // played in the position are left out in case of key collisions
+    pub fn moves(&self, game: &mut Game) -> Vec<(Move, u16)> {
+        let key = polyglot_key(game.position(), game.state());
+        self.entries(key)
+            .iter()
+            .filter_map(|entry| {
+                let mv = decode_move(game, entry.mv)?;
+                Some((mv, entry.weight))
+            })
+            .collect()
+    }
+
+    // `random` only matters for weighted random selection. moves with no weight are never picked
+    pub fn pick(&self, game: &mut Game, selection: BookSelection, random: u64) -> Option<Move> {
+        let moves: Vec<(Move, u16)> = self
+            .moves(game)
+            .into_iter()
+            .filter(|(_, weight)| *weight > 0)
+            .collect();
+
+        match selection {
+            BookSelection::Best => moves
+                .iter()
+                .fold(None, |best: Option<(Move, u16)>, (mv, weight)| match best {
+                    Some((_, best_weight)) if best_weight >= *weight => best,
+                    _ => Some((*mv, *weight)),
+                })
+                .map(|(mv, _)| mv),
+            BookSelection::WeightedRandom => {
+                let total: u64 = moves.iter().map(|(_, weight)| *weight as u64).sum();
+                if total == 0 {
+                    return None;
+                }
+
+                let mut remaining = random % total;
+                for (mv, weight) in moves {
+                    if remaining < weight as u64 {
+                        return Some(mv);
+                    }
+                    remaining -= weight as u64;
+                }
+
+                None
+            }
+        }
+    }
+}
+
+fn square_from_bits(bits: u16) -> Square {
+    let file = (bits & 7) as usize;
+    let rank = ((bits >> 3) & 7) as usize;
+    Square::from(rank, file)
+}
+
+// book moves are stored as from and to squares, with castling written as the king taking its own
+// rook. none if the move isn't legal in the position
+pub fn decode_move(game: &mut Game, mv: u16) -> Option<Move> {
+    let to = square_from_bits(mv);
+    let from = square_from_bits(mv >> 6);
+    let promotion = PROMOTION_CHARS[((mv >> 12) & 7).min(4) as usize];
+
+    // only a castle if the king lands on a rook it can still castle with, wherever the king and
+    // rooks start
+    let side = game.state().side_to_move();
+    let is_king = game
+        .position()
+        .at(from)
+        .is_some_and(|pc| pc.piece_type() == PieceType::King);
+    if is_king {
+        for castle in [Castle::Queenside, Castle::Kingside] {
+            let (rook_sq, _) = game.state().castle_files().rook_squares(side, castle);
+            if to == rook_sq && game.state().castle_rights().can(side, castle) {
+                let mv = Move::Castle(castle);
+                return game.legal_moves().contains(&mv).then_some(mv);
+            }
+        }
+    }
+
+    let mut notation = format!("{}{}", from, to).to_lowercase();
+    if promotion != ' ' {
+        notation.push(promotion);
+    }
+
+    let mv = algebra_to_move(&notation, game).ok()?;
+
+    game.legal_moves().contains(&mv).then_some(mv)
+}
+
+// xorshift, good enough to vary the book moves between games
+pub fn next_random(seed: u64) -> u64 {
+    let mut x = seed.max(1);
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    x
+}
+
+#[cfg(test)]
+pub mod test_book {
+    use super::*;
+    use crate::{fen::STARTING_POSITION_FEN, uci::move_to_algebra};
+
+    fn load(fen: &str) -> Game {
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        result.unwrap()
+    }
+
+    fn encode(notation: &str) -> u16 {
+        let sq = |file: u8, rank: u8| ((rank - b'1') as u16) << 3 | (file - b'a') as u16;
+        let bytes = notation.as_bytes();
+        let mut mv = sq(bytes[0], bytes[1]) << 6 | sq(bytes[2], bytes[3]);
+        if let Some(promotion) = bytes.get(4) {
+            mv |= (PROMOTION_CHARS
+                .iter()
+                .position(|c| *c as u8 == *promotion)
+                .unwrap() as u16)
+                << 12;
+        }
+        mv
+    }
+
+    fn entry(game: &Game, notation: &str, weight: u16) -> BookEntry {
+        BookEntry {
+            key: polyglot_key(game.position(), game.state()),
+            mv: encode(notation),
+            weight,
+            learn: 0,
+        }
+    }
+
+    fn notation(game: &Game, mv: Move) -> String {
+        move_to_algebra(mv, game.state().side_to_move())
+    }
+
+    #[test]
+    fn from_bytes() {
+        let game = load(STARTING_POSITION_FEN);
+        let mut bytes = vec![];
+        for (notation, weight) in [("e2e4", 10u16), ("d2d4", 5)] {
+            bytes.extend(polyglot_key(game.position(), game.state()).to_be_bytes());
+            bytes.extend(encode(notation).to_be_bytes());
+            bytes.extend(weight.to_be_bytes());
+            bytes.extend(0u32.to_be_bytes());
+        }
+
+        let result = Book::from_bytes(&bytes);
+        assert!(result.is_ok());
+        let book = result.unwrap();
+        assert_eq!(
+            book.entries,
+            vec![entry(&game, "e2e4", 10), entry(&game, "d2d4", 5)]
+        );
+
+        assert!(Book::from_bytes(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn illegal_moves_are_skipped() {
+        let mut game = load(STARTING_POSITION_FEN);
+        let book = Book::new(vec![
+            entry(&game, "e2e4", 10),
+            entry(&game, "e2e5", 10),
+            entry(&game, "e7e5", 10),
+        ]);
+
+        let moves = book.moves(&mut game);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(notation(&game, moves[0].0), "e2e4");
+    }
+
+    #[test]
+    fn castling_is_king_takes_rook() {
+        let mut game = load("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        let book = Book::new(vec![entry(&game, "e1h1", 1)]);
+        assert_eq!(
+            book.pick(&mut game, BookSelection::Best, 0),
+            Some(Move::Castle(Castle::Kingside))
+        );
+
+        let mut game = load("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1");
+        let book = Book::new(vec![entry(&game, "e8a8", 1)]);
+        assert_eq!(
+            book.pick(&mut game, BookSelection::Best, 0),
+            Some(Move::Castle(Castle::Queenside))
+        );
+    }
+
+    #[test]
+    fn chess960_castling() {
+        let mut game = load("4k3/8/8/8/8/8/8/RK4R1 w GA - 0 1");
+        let book = Book::new(vec![entry(&game, "b1a1", 1)]);
+        assert_eq!(
+            book.pick(&mut game, BookSelection::Best, 0),
+            Some(Move::Castle(Castle::Queenside))
+        );
+        let book = Book::new(vec![entry(&game, "b1g1", 1)]);
+        assert_eq!(
+            book.pick(&mut game, BookSelection::Best, 0),
+            Some(Move::Castle(Castle::Kingside))
+        );
+
+        // without the right the king can't take its own rook
+        let mut game = load("4k3/8/8/8/8/8/8/RK4R1 w G - 0 1");
+        let book = Book::new(vec![entry(&game, "b1a1", 1)]);
+        assert_eq!(book.pick(&mut game, BookSelection::Best, 0), None);
+        let mut game = load("r3k2r/8/8/8/8/8/8/R3K2R w Qkq - 0 1");
+        let book = Book::new(vec![entry(&game, "e1h1", 1)]);
+        assert_eq!(book.pick(&mut game, BookSelection::Best, 0), None);
+    }
+
+    #[test]
+    fn promotion() {
+        let mut game = load("8/4P3/8/8/8/8/k7/4K3 w - - 0 1");
+        let book = Book::new(vec![entry(&game, "e7e8n", 1)]);
+        let mv = book.pick(&mut game, BookSelection::Best, 0);
+        assert!(mv.is_some());
+        assert_eq!(notation(&game, mv.unwrap()), "e7e8n");
+    }
+
+    #[test]
+    fn best_and_weighted_selection() {
+        let mut game = load(STARTING_POSITION_FEN);
+        let book = Book::new(vec![
+            entry(&game, "d2d4", 5),
+            entry(&game, "e2e4", 10),
+            entry(&game, "g1f3", 0),
+        ]);
+
+        let best = book.pick(&mut game, BookSelection::Best, 0);
+        assert!(best.is_some());
+        assert_eq!(notation(&game, best.unwrap()), "e2e4");
+
+        // sorted by weight, so the first 10 of every 15 go to e2e4
+        let mut counts = [0, 0];
+        for random in 0..15 {
+            let mv = book.pick(&mut game, BookSelection::WeightedRandom, random);
+            assert!(mv.is_some());
+            match notation(&game, mv.unwrap()).as_str() {
+                "e2e4" => counts[0] += 1,
+                "d2d4" => counts[1] += 1,
+                other => panic!("unexpected book move {}", other),
+            }
+        }
+        assert_eq!(counts, [10, 5]);
+    }
+
+    #[test]
+    fn position_not_in_book() {
+        let mut game = load("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1");
+        let start = load(STARTING_POSITION_FEN);
+        let book = Book::new(vec![entry(&start, "e2e4", 10)]);
+        assert_eq!(book.pick(&mut game, BookSelection::WeightedRandom, 3), None);
+    }
+}
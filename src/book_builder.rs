@@ -0,0 +1,400 @@
+use std::{collections::HashMap, env, fs};
+
+use crate::{
+    book::{BookEntry, ENTRY_SIZE, PROMOTION_CHARS},
+    game::Game,
+    mv::Move,
+    pgn::{read_pgn, GameRecord},
+    side::Side,
+    state::polyglot::polyglot_key,
+    uci::move_to_algebra,
+};
+
+const DEFAULT_OUT_PATH: &str = "book.bin";
+const DEFAULT_MAX_PLY: usize = 16;
+const DEFAULT_MIN_GAMES: u32 = 1;
+const DEFAULT_MIN_SCORE: f64 = 0.0;
+
+fn square_to_bits(notation: &[u8]) -> u16 {
+    ((notation[1] - b'1') as u16) << 3 | (notation[0] - b'a') as u16
+}
+
+// the inverse of `book::decode_move`, castles are written as the king taking its own rook
+pub fn encode_move(game: &Game, mv: Move) -> u16 {
+    let side = game.state().side_to_move();
+    let mut notation = move_to_algebra(mv, side).into_bytes();
+    if let Move::Castle(castle) = mv {
+        let (rook_sq, _) = game.state().castle_files().rook_squares(side, castle);
+        notation = format!("{}{}", game.position().king_sq(side), rook_sq)
+            .to_lowercase()
+            .into_bytes();
+    }
+
+    let mut encoded = square_to_bits(&notation[0..2]) << 6 | square_to_bits(&notation[2..4]);
+    if let Some(promotion) = notation.get(4) {
+        let promotion = PROMOTION_CHARS
+            .iter()
+            .position(|c| *c as u8 == *promotion)
+            .unwrap_or(0);
+        encoded |= (promotion as u16) << 12;
+    }
+    encoded
+}
+
+// entries must already be sorted by key for other polyglot readers
+pub fn to_bytes(entries: &[BookEntry]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(entries.len() * ENTRY_SIZE);
+    for entry in entries {
+        bytes.extend(entry.key.to_be_bytes());
+        bytes.extend(entry.mv.to_be_bytes());
+        bytes.extend(entry.weight.to_be_bytes());
+        bytes.extend(entry.learn.to_be_bytes());
+    }
+    bytes
+}
+
+// none for unfinished games
+fn white_score(result: &str) -> Option<f64> {
+    match result {
+        "1-0" => Some(1.0),
+        "0-1" => Some(0.0),
+        "1/2-1/2" => Some(0.5),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MoveStats {
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl MoveStats {
+    // from the perspective of the side playing the move
+    pub fn score(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games as f64
+    }
+
+    fn weight(&self) -> u32 {
+        2 * self.wins + self.draws
+    }
+}
+
+pub struct BookBuilder {
+    max_ply: usize,
+    // keyed by polyglot key and encoded book move
+    stats: HashMap<(u64, u16), MoveStats>,
+}
+
+impl BookBuilder {
+    pub fn new(max_ply: usize) -> BookBuilder {
+        BookBuilder {
+            max_ply,
+            stats: HashMap::new(),
+        }
+    }
+
+    pub fn stats(&self, key: u64, mv: u16) -> Option<&MoveStats> {
+        self.stats.get(&(key, mv))
+    }
+
+    // replays the mainline up to the max ply, unfinished games are left out
+    pub fn add_game(&mut self, record: &GameRecord) -> Result<(), String> {
+        let Some(result) = white_score(&record.result) else {
+            return Err("game has no result".to_string());
+        };
+
+        let mut game = Game::from_fen(record.start_fen())?;
+        for mv in record
+            .mainline
+            .moves
+            .iter()
+            .map(|pgn_move| pgn_move.mv)
+            .take(self.max_ply)
+        {
+            let key = polyglot_key(game.position(), game.state());
+            let stats = self.stats.entry((key, encode_move(&game, mv))).or_default();
+
+            let white_to_move = game.state().side_to_move() == Side::White;
+            let score = if white_to_move { result } else { 1.0 - result };
+            stats.games += 1;
+            if score == 1.0 {
+                stats.wins += 1;
+            } else if score == 0.0 {
+                stats.losses += 1;
+            } else {
+                stats.draws += 1;
+            }
+
+            game.make_move(mv);
+        }
+
+        Ok(())
+    }
+
+    // moves played in fewer than `min_games` games or scoring under `min_score` are left out, as
+    // are moves that never won or drew. weights are 2 per win and 1 per draw, scaled down when
+    // they don't fit in a u16. sorted by key, then by weight
+    pub fn build(&self, min_games: u32, min_score: f64) -> Vec<BookEntry> {
+        let kept: Vec<(&(u64, u16), &MoveStats)> = self
+            .stats
+            .iter()
+            .filter(|(_, stats)| {
+                stats.games >= min_games && stats.score() >= min_score && stats.weight() > 0
+            })
+            .collect();
+
+        let max_weight = kept
+            .iter()
+            .map(|(_, stats)| stats.weight())
+            .max()
+            .unwrap_or(0);
+        let scale = (u16::MAX as f64 / max_weight as f64).min(1.0);
+
+        let mut entries: Vec<BookEntry> = kept
+            .into_iter()
+            .map(|((key, mv), stats)| BookEntry {
+                key: *key,
+                mv: *mv,
+                weight: ((stats.weight() as f64 * scale) as u16).max(1),
+                learn: 0,
+            })
+            .collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key).then(b.weight.cmp(&a.weight)));
+        entries
+    }
+}
+
+fn print_usage() {
+    println!(
+        "usage: build_book <pgn files> [--out <file>] [--max-ply <n>] [--min-games <n>] \
+         [--min-score <0-1>]"
+    );
+}
+
+pub fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let mut pgn_paths = vec![];
+    let mut out_path = DEFAULT_OUT_PATH.to_string();
+    let mut max_ply = DEFAULT_MAX_PLY;
+    let mut min_games = DEFAULT_MIN_GAMES;
+    let mut min_score = DEFAULT_MIN_SCORE;
+
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--out" => match args_iter.next() {
+                Some(path) => out_path = path.clone(),
+                None => return print_usage(),
+            },
+            "--max-ply" => match args_iter.next().map(|n| n.parse()) {
+                Some(Ok(n)) => max_ply = n,
+                _ => return print_usage(),
+            },
+            "--min-games" => match args_iter.next().map(|n| n.parse()) {
+                Some(Ok(n)) => min_games = n,
+                _ => return print_usage(),
+            },
+            "--min-score" => match args_iter.next().map(|n| n.parse()) {
+                Some(Ok(n)) => min_score = n,
+                _ => return print_usage(),
+            },
+            path => pgn_paths.push(path.to_string()),
+        }
+    }
+
+    if pgn_paths.is_empty() {
+        return print_usage();
+    }
+
+    let mut builder = BookBuilder::new(max_ply);
+    let mut added = 0;
+    let mut skipped = 0;
+    for path in pgn_paths {
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) => return println!("unable to read {}: {}", path, err),
+        };
+
+        for (i, record) in read_pgn(&text).into_iter().enumerate() {
+            match record.and_then(|record| builder.add_game(&record)) {
+                Ok(()) => added += 1,
+                Err(err) => {
+                    println!("{} game {}: {}", path, i + 1, err);
+                    skipped += 1;
+                }
+            }
+        }
+    }
+    println!("added {} games, skipped {}", added, skipped);
+
+    let entries = builder.build(min_games, min_score);
+    if entries.is_empty() {
+        return println!("no moves passed the filters");
+    }
+    match fs::write(&out_path, to_bytes(&entries)) {
+        Ok(()) => println!("{} book entries written to {}", entries.len(), out_path),
+        Err(err) => println!("unable to write book to {}: {}", out_path, err),
+    }
+}
+
+#[cfg(test)]
+pub mod test_book_builder {
+    use super::*;
+    use crate::{
+        book::{decode_move, Book, BookSelection},
+        fen::STARTING_POSITION_FEN,
+        mv::castle::Castle,
+    };
+
+    const PGN: &str = r#"[Event "one"]
+[Result "1-0"]
+
+1. e4 {best by test} e5 2. Nf3 (2. f4 exf4 3. Nf3) Nc6 $1 3. Bb5 a6 1-0
+
+[Event "two"]
+[Result "1/2-1/2"]
+
+1.e4 c5 2.Nf3 ; open sicilian
+d6 3. d4 cxd4 1/2-1/2
+
+[Event "three"]
+[Result "0-1"]
+
+1. d4 d5 2. c4 e6 0-1
+"#;
+
+    #[test]
+    fn encode_round_trip() {
+        let result = Game::from_fen("r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        // e1h1 and e1a1
+        assert_eq!(encode_move(&game, Move::Castle(Castle::Kingside)), 263);
+        assert_eq!(encode_move(&game, Move::Castle(Castle::Queenside)), 256);
+        for mv in game.legal_moves() {
+            let encoded = encode_move(&game, mv);
+            assert_eq!(decode_move(&mut game, encoded), Some(mv));
+        }
+
+        // b1a1 and b1g1
+        let result = Game::from_fen("4k3/8/8/8/8/8/8/RK4R1 w GA - 0 1");
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        assert_eq!(encode_move(&game, Move::Castle(Castle::Queenside)), 64);
+        assert_eq!(encode_move(&game, Move::Castle(Castle::Kingside)), 70);
+        for mv in game.legal_moves() {
+            let encoded = encode_move(&game, mv);
+            assert_eq!(decode_move(&mut game, encoded), Some(mv));
+        }
+    }
+
+    fn records(text: &str) -> Vec<GameRecord> {
+        read_pgn(text)
+            .into_iter()
+            .map(|result| {
+                assert!(result.is_ok());
+                result.unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fen_and_unfinished_games() {
+        let records =
+            records("[FEN \"4k3/8/8/8/8/8/8/R3K3 w Q - 0 1\"]\n\n1. O-O-O Kf7 1-0\n\n1. e4 e5 *");
+        assert_eq!(records.len(), 2);
+
+        let mut builder = BookBuilder::new(10);
+        assert!(builder.add_game(&records[0]).is_ok());
+        assert_eq!(builder.stats.len(), 2);
+        assert!(builder.add_game(&records[1]).is_err());
+        assert_eq!(builder.stats.len(), 2);
+    }
+
+    #[test]
+    fn aggregate_stats() {
+        let mut builder = BookBuilder::new(2);
+        for record in records(PGN) {
+            assert!(builder.add_game(&record).is_ok());
+        }
+
+        let mut game = Game::from_fen(STARTING_POSITION_FEN).unwrap();
+        let start_key = polyglot_key(game.position(), game.state());
+        let e4 = game.parse_san("e4").unwrap();
+        let d4 = game.parse_san("d4").unwrap();
+        assert_eq!(
+            builder.stats(start_key, encode_move(&game, e4)),
+            Some(&MoveStats {
+                games: 2,
+                wins: 1,
+                draws: 1,
+                losses: 0
+            })
+        );
+        assert_eq!(
+            builder.stats(start_key, encode_move(&game, d4)),
+            Some(&MoveStats {
+                games: 1,
+                wins: 0,
+                draws: 0,
+                losses: 1
+            })
+        );
+
+        // black's reply is scored from black's perspective
+        game.make_move(e4);
+        let key = polyglot_key(game.position(), game.state());
+        let e5 = game.parse_san("e5").unwrap();
+        assert_eq!(
+            builder.stats(key, encode_move(&game, e5)),
+            Some(&MoveStats {
+                games: 1,
+                wins: 0,
+                draws: 0,
+                losses: 1
+            })
+        );
+
+        // past the max ply
+        game.make_move(e5);
+        let key = polyglot_key(game.position(), game.state());
+        let nf3 = game.parse_san("Nf3").unwrap();
+        assert_eq!(builder.stats(key, encode_move(&game, nf3)), None);
+    }
+
+    #[test]
+    fn build_filters_and_round_trips() {
+        let mut builder = BookBuilder::new(4);
+        for record in records(PGN) {
+            assert!(builder.add_game(&record).is_ok());
+        }
+
+        let result = Book::from_bytes(&to_bytes(&builder.build(1, 0.0)));
+        assert!(result.is_ok());
+        let book = result.unwrap();
+
+        let mut game = Game::from_fen(STARTING_POSITION_FEN).unwrap();
+        let moves = book.moves(&mut game);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(
+            move_to_algebra(moves[0].0, game.state().side_to_move()),
+            "e2e4"
+        );
+        assert_eq!(moves[0].1, 3);
+
+        // e4 scores 0.75 from two games, d4 lost its only game
+        let book = Book::new(builder.build(2, 0.0));
+        let mv = book.pick(&mut game, BookSelection::Best, 0);
+        assert!(mv.is_some());
+        assert_eq!(
+            move_to_algebra(mv.unwrap(), game.state().side_to_move()),
+            "e2e4"
+        );
+        assert!(Book::new(builder.build(1, 0.8)).moves(&mut game).is_empty());
+        assert!(builder.build(3, 0.0).is_empty());
+    }
+}
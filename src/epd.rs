@@ -0,0 +1,424 @@
+use std::{env, fs, time::Duration};
+
+use crate::{
+    eval::MAX_EVAL,
+    fen::FenError,
+    game::Game,
+    mv::Move,
+    search::{Depth, MoveFinder, DEFAULT_DEPTH, DEFAULT_MAX_DEPTH},
+    side::Side,
+};
+
+// deepest iteration when only a time or node limit is given
+const MAX_SEARCH_DEPTH: Depth = 64;
+
+// an opcode followed by its operands, eg. `bm Qd1+ Qd2` or `id "WAC.001"`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Operation {
+    pub opcode: String,
+    pub operands: Vec<String>,
+}
+
+// the first four fen fields followed by operations separated by semicolons. the move counters
+// come from the hmvc and fmvn operations, or a pair of numbers right after the fen fields
+#[derive(Clone, Debug, PartialEq)]
+pub struct Epd {
+    pub fen: String,
+    pub operations: Vec<Operation>,
+}
+
+impl Epd {
+    pub fn operation(&self, opcode: &str) -> Option<&[String]> {
+        self.operations
+            .iter()
+            .find(|operation| operation.opcode == opcode)
+            .map(|operation| operation.operands.as_slice())
+    }
+
+    // the first operand of the operation
+    pub fn operand(&self, opcode: &str) -> Option<&str> {
+        self.operation(opcode)?
+            .first()
+            .map(|operand| operand.as_str())
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.operand("id")
+    }
+
+    pub fn game(&self) -> Result<Game, FenError> {
+        Game::from_fen(&self.fen)
+    }
+}
+
+// splits on whitespace, keeping quoted strings together without their quotes
+fn split_operands(text: &str) -> Vec<String> {
+    let mut operands = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let mut operand = String::new();
+        if c == '"' {
+            for c in chars.by_ref() {
+                if c == '"' {
+                    break;
+                }
+                operand.push(c);
+            }
+        } else {
+            operand.push(c);
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                operand.push(c);
+            }
+        }
+        operands.push(operand);
+    }
+    operands
+}
+
+// semicolons inside quoted strings don't end the operation
+fn split_operations(text: &str) -> Vec<&str> {
+    let mut operations = vec![];
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                operations.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    operations.push(&text[start..]);
+
+    operations
+        .into_iter()
+        .map(|operation| operation.trim())
+        .filter(|operation| !operation.is_empty())
+        .collect()
+}
+
+// the next whitespace separated field, moving `rest` past it
+fn next_field<'a>(rest: &mut &'a str) -> &'a str {
+    let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+    let field = &rest[..end];
+    *rest = rest[end..].trim_start();
+    field
+}
+
+pub fn parse_epd(line: &str) -> Result<Epd, String> {
+    let mut rest = line.trim();
+    let mut fields = vec![];
+    for _ in 0..4 {
+        if rest.is_empty() {
+            return Err(format!("epd record is missing fen fields: {}", line));
+        }
+        fields.push(next_field(&mut rest));
+    }
+
+    // a full fen has the move counters after the four epd fields
+    let mut halfmoves = "0".to_string();
+    let mut fullmoves = "1".to_string();
+    let mut after_counters = rest;
+    let halfmoves_field = next_field(&mut after_counters);
+    let fullmoves_field = next_field(&mut after_counters).trim_end_matches(';');
+    if halfmoves_field.parse::<u16>().is_ok() && fullmoves_field.parse::<u16>().is_ok() {
+        halfmoves = halfmoves_field.to_string();
+        fullmoves = fullmoves_field.to_string();
+        rest = after_counters;
+    }
+
+    let mut operations = vec![];
+    for text in split_operations(rest) {
+        let mut operands = split_operands(text);
+        let opcode = operands.remove(0);
+        if !opcode.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return Err(format!("{} is an invalid epd opcode", opcode));
+        }
+        operations.push(Operation { opcode, operands });
+    }
+
+    let mut epd = Epd {
+        fen: String::new(),
+        operations,
+    };
+    if let Some(hmvc) = epd.operand("hmvc") {
+        halfmoves = hmvc.to_string();
+    }
+    if let Some(fmvn) = epd.operand("fmvn") {
+        fullmoves = fmvn.to_string();
+    }
+    epd.fen = format!("{} {} {}", fields.join(" "), halfmoves, fullmoves);
+
+    Ok(epd)
+}
+
+// blank lines and lines starting with # are skipped
+pub fn read_epd(text: &str) -> Vec<Result<Epd, String>> {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(parse_epd)
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    Solved,
+    Failed,
+    // no bm, am or dm operation to check the move against
+    Untested,
+}
+
+fn parse_moves(game: &mut Game, operands: &[String]) -> Result<Vec<Move>, String> {
+    operands.iter().map(|san| game.parse_san(san)).collect()
+}
+
+// checks the move against the bm, am and dm operations. `eval` is from white's perspective like
+// the result of `MoveFinder::get`
+pub fn check(epd: &Epd, game: &mut Game, mv: Move, eval: i32) -> Result<Outcome, String> {
+    let mut outcome = Outcome::Untested;
+    let mut fail_if = |failed: bool| {
+        outcome = match (outcome, failed) {
+            (Outcome::Failed, _) | (_, true) => Outcome::Failed,
+            _ => Outcome::Solved,
+        }
+    };
+
+    if let Some(operands) = epd.operation("bm") {
+        fail_if(!parse_moves(game, operands)?.contains(&mv));
+    }
+    if let Some(operands) = epd.operation("am") {
+        fail_if(parse_moves(game, operands)?.contains(&mv));
+    }
+    if let Some(moves_to_mate) = epd.operand("dm") {
+        let moves_to_mate: i32 = moves_to_mate
+            .parse()
+            .map_err(|_| format!("{} is an invalid dm operand", moves_to_mate))?;
+        let eval = if game.state().side_to_move() == Side::White {
+            eval
+        } else {
+            -eval
+        };
+        fail_if(MAX_EVAL - eval > 2 * moves_to_mate - 1);
+    }
+
+    Ok(outcome)
+}
+
+// searches the position with the finder's limits. an acd operation sets the depth for the
+// position when `use_acd` is true
+pub fn solve(
+    epd: &Epd,
+    mv_finder: &mut MoveFinder,
+    use_acd: bool,
+) -> Result<(Outcome, String), String> {
+    let mut game = epd.game()?;
+
+    if use_acd {
+        let depth = match epd.operand("acd").map(|acd| acd.parse::<Depth>()) {
+            Some(Ok(depth)) => depth,
+            Some(Err(_)) => return Err("invalid acd operand".to_string()),
+            None => DEFAULT_DEPTH,
+        };
+        mv_finder.change_search_depth(depth);
+        mv_finder.change_max_depth(depth.saturating_add(DEFAULT_MAX_DEPTH - DEFAULT_DEPTH));
+    }
+
+    let (mv, eval) = mv_finder
+        .get(&mut game)
+        .ok_or("position has no legal moves")?;
+    let san = game.move_to_san(mv);
+
+    Ok((check(epd, &mut game, mv, eval)?, san))
+}
+
+fn print_usage() {
+    println!("usage: epd_suite <epd files> [--depth <n>] [--nodes <n>] [--time <ms>]");
+}
+
+pub fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let mut epd_paths = vec![];
+    let mut depth = None;
+    let mut node_limit = None;
+    let mut move_time = None;
+
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--depth" => match args_iter.next().map(|n| n.parse()) {
+                Some(Ok(n)) => depth = Some(n),
+                _ => return print_usage(),
+            },
+            "--nodes" => match args_iter.next().map(|n| n.parse()) {
+                Some(Ok(n)) => node_limit = Some(n),
+                _ => return print_usage(),
+            },
+            "--time" => match args_iter.next().map(|n| n.parse()) {
+                Some(Ok(n)) => move_time = Some(Duration::from_millis(n)),
+                _ => return print_usage(),
+            },
+            path => epd_paths.push(path.to_string()),
+        }
+    }
+
+    if epd_paths.is_empty() {
+        return print_usage();
+    }
+
+    // without any limits each position is searched to its acd, or the default depth
+    let use_acd = depth.is_none() && node_limit.is_none() && move_time.is_none();
+    let depth = depth.unwrap_or(MAX_SEARCH_DEPTH);
+    let mut mv_finder = MoveFinder::new(
+        depth,
+        depth.saturating_add(DEFAULT_MAX_DEPTH - DEFAULT_DEPTH),
+    );
+    mv_finder.set_node_limit(node_limit);
+    mv_finder.set_move_time(move_time);
+
+    let (mut solved, mut failed, mut untested, mut errors) = (0, 0, 0, 0);
+    for path in epd_paths {
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) => return println!("unable to read {}: {}", path, err),
+        };
+
+        for (i, epd) in read_epd(&text).into_iter().enumerate() {
+            let name = match &epd {
+                Ok(epd) => epd.id().map(|id| id.to_string()),
+                Err(_) => None,
+            }
+            .unwrap_or_else(|| format!("{} #{}", path, i + 1));
+
+            match epd.and_then(|epd| solve(&epd, &mut mv_finder, use_acd)) {
+                Ok((Outcome::Solved, san)) => {
+                    solved += 1;
+                    println!("{}: solved {}", name, san);
+                }
+                Ok((Outcome::Failed, san)) => {
+                    failed += 1;
+                    println!("{}: failed {}", name, san);
+                }
+                Ok((Outcome::Untested, san)) => {
+                    untested += 1;
+                    println!("{}: played {}, nothing to check", name, san);
+                }
+                Err(err) => {
+                    errors += 1;
+                    println!("{}: {}", name, err);
+                }
+            }
+        }
+    }
+
+    println!(
+        "solved {} of {}, {} failed, {} untested, {} errors",
+        solved,
+        solved + failed,
+        failed,
+        untested,
+        errors
+    );
+}
+
+#[cfg(test)]
+pub mod test_epd {
+    use super::*;
+
+    #[test]
+    fn operations() {
+        let result = parse_epd(
+            r#"2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001"; c0 "a; b";"#,
+        );
+        assert!(result.is_ok());
+        let epd = result.unwrap();
+
+        assert_eq!(
+            epd.fen,
+            "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - 0 1"
+        );
+        assert_eq!(epd.operation("bm"), Some(&["Qg6".to_string()][..]));
+        assert_eq!(epd.id(), Some("WAC.001"));
+        assert_eq!(epd.operand("c0"), Some("a; b"));
+        assert_eq!(epd.operation("am"), None);
+    }
+
+    #[test]
+    fn move_counters() {
+        let result = parse_epd("4k3/8/8/8/8/8/8/4K3 b - - 12 40 ;D1 5 ;D2 25");
+        assert!(result.is_ok());
+        let epd = result.unwrap();
+        assert_eq!(epd.fen, "4k3/8/8/8/8/8/8/4K3 b - - 12 40");
+        assert_eq!(epd.operand("D2"), Some("25"));
+
+        let result = parse_epd("4k3/8/8/8/8/8/8/4K3 b - - hmvc 3; fmvn 20; acd 4;");
+        assert!(result.is_ok());
+        let epd = result.unwrap();
+        assert_eq!(epd.fen, "4k3/8/8/8/8/8/8/4K3 b - - 3 20");
+        assert_eq!(epd.operand("acd"), Some("4"));
+    }
+
+    #[test]
+    fn invalid_records() {
+        assert!(parse_epd("4k3/8/8/8/8/8/8/4K3 w -").is_err());
+        assert!(parse_epd("4k3/8/8/8/8/8/8/4K3 w - - 1x 2;").is_err());
+
+        let epds = read_epd("# comment\n\n4k3/8/8/8/8/8/8/4K3 w - - id \"one\";\nbad\n");
+        assert_eq!(epds.len(), 2);
+        assert!(epds[0].is_ok());
+        assert!(epds[1].is_err());
+    }
+
+    #[test]
+    fn check_operations() {
+        let result = parse_epd("6k1/5ppp/8/8/8/8/8/R5K1 w - - bm Ra8+; am Ra7; dm 1;");
+        assert!(result.is_ok());
+        let epd = result.unwrap();
+        let result = epd.game();
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+
+        let mate = game.parse_san("Ra8").unwrap();
+        let other = game.parse_san("Ra7").unwrap();
+        assert_eq!(
+            check(&epd, &mut game, mate, MAX_EVAL - 1),
+            Ok(Outcome::Solved)
+        );
+        assert_eq!(check(&epd, &mut game, other, 0), Ok(Outcome::Failed));
+        // the right move, but the mate wasn't found
+        assert_eq!(check(&epd, &mut game, mate, 500), Ok(Outcome::Failed));
+
+        let result = parse_epd("6k1/5ppp/8/8/8/8/8/R5K1 w - - id \"none\";");
+        assert!(result.is_ok());
+        let epd = result.unwrap();
+        assert_eq!(check(&epd, &mut game, mate, 0), Ok(Outcome::Untested));
+    }
+
+    #[test]
+    fn solve_with_limits() {
+        let result = parse_epd("6k1/5ppp/8/8/8/8/8/R5K1 w - - bm Ra8; acd 3;");
+        assert!(result.is_ok());
+        let epd = result.unwrap();
+
+        let mut mv_finder = MoveFinder::new(DEFAULT_DEPTH, DEFAULT_MAX_DEPTH);
+        assert_eq!(
+            solve(&epd, &mut mv_finder, true),
+            Ok((Outcome::Solved, "Ra8#".to_string()))
+        );
+
+        let mut mv_finder = MoveFinder::new(MAX_SEARCH_DEPTH, MAX_SEARCH_DEPTH);
+        mv_finder.set_node_limit(Some(20_000));
+        assert_eq!(
+            solve(&epd, &mut mv_finder, false),
+            Ok((Outcome::Solved, "Ra8#".to_string()))
+        );
+        assert!(mv_finder.nodes() <= 20_000);
+    }
+}
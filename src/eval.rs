@@ -1,14 +1,23 @@
+pub mod endgame;
 mod king_heuristics;
+pub mod kpk;
+pub mod params;
 mod pawn_heuristics;
+mod variant_heuristics;
 
 use crate::{
     bitboard::{self, BB},
     game::Game,
-    move_gen::{check_legal::LegalCheckPreprocessing, controlled_squares},
+    move_gen::{check_legal::LegalCheckPreprocessing, controlled_squares, legal::MoveGen},
+    nnue::accumulator::Accumulator,
+    piece_type::{PieceType, PIECE_TYPE_MAP},
     score::Score,
+    side::Side,
+    state::position::Position,
+    variant::Variant,
 };
 
-use self::king_heuristics::king_safety;
+use self::{king_heuristics::king_safety, params::EvalParams};
 
 // need to subtract one because of the zero window width used in principal variation search
 // if you dont subtract one youll get a "attempt to negate with overflow" error
@@ -19,10 +28,27 @@ pub fn eval(
     game: &mut Game,
     legal_check_preprocessing: &LegalCheckPreprocessing,
     levels_searched: u8,
+    params: &EvalParams,
+    accumulator: Option<&Accumulator>,
 ) -> i32 {
     if game.is_draw() {
         return DRAW_SCORE.get(game.position().phase());
     }
+    if game.is_variant_loss() {
+        return -(MAX_EVAL - levels_searched as i32);
+    }
+    let variant = game.state().variant();
+    if variant == Variant::Antichess {
+        // running out of moves wins
+        if game
+            .legal_move_list(MoveGen::All, legal_check_preprocessing)
+            .list()
+            .is_empty()
+        {
+            return MAX_EVAL - levels_searched as i32;
+        }
+        return variant_heuristics::antichess(game.position(), game.state().side_to_move());
+    }
     if game.is_checkmate(legal_check_preprocessing) {
         return -(MAX_EVAL - levels_searched as i32);
     }
@@ -31,42 +57,102 @@ pub fn eval(
     }
 
     let side = game.state().side_to_move();
+    let position = game.position();
+    // the endgame knowledge is about standard chess
+    if variant == Variant::Standard {
+        if let Some(eval) = endgame::probe(position, side, params) {
+            return eval;
+        }
+    }
+    if let Some(accumulator) = accumulator {
+        return scale(position, side, accumulator.evaluate(side));
+    }
+
     let piece_score: i32 =
-        game.position().piece_score(side) - game.position().piece_score(side.opposite());
-    let sq_score: i32 = game.position().sq_score(side) - game.position().sq_score(side.opposite());
+        piece_score(position, side, params) - piece_score(position, side.opposite(), params);
+    let sq_score: i32 =
+        sq_score(position, side, params) - sq_score(position, side.opposite(), params);
 
-    let controlled_squares = controlled_squares(game.position(), side);
-    let center_control = center_control(controlled_squares) as i32
-        - center_control(legal_check_preprocessing.controlled_squares_with_king_gone_bb()) as i32;
+    let controlled_squares = controlled_squares(position, side);
+    let center_control = center_control(controlled_squares, params)
+        - center_control(
+            legal_check_preprocessing.controlled_squares_with_king_gone_bb(),
+            params,
+        );
 
-    let mobility_bonus = mobility(controlled_squares) as i32
-        - mobility(legal_check_preprocessing.controlled_squares_with_king_gone_bb()) as i32;
+    let mobility_bonus = mobility(controlled_squares, params)
+        - mobility(
+            legal_check_preprocessing.controlled_squares_with_king_gone_bb(),
+            params,
+        );
 
     let king_safety_bonus = king_safety(
-        game.position().king_sq(side),
-        game.position()
-            .bb_pc(crate::piece_type::PieceType::Pawn, side),
-    ) as i32
-        - king_safety(
-            game.position().king_sq(side.opposite()),
-            game.position()
-                .bb_pc(crate::piece_type::PieceType::Pawn, side.opposite()),
-        ) as i32;
-
-    sq_score + piece_score + center_control + king_safety_bonus + mobility_bonus
+        position.king_sq(side),
+        position.bb_pc(PieceType::Pawn, side),
+        params.pawns_in_front_of_king(),
+    ) - king_safety(
+        position.king_sq(side.opposite()),
+        position.bb_pc(PieceType::Pawn, side.opposite()),
+        params.pawns_in_front_of_king(),
+    );
+
+    let variant_bonus = match variant {
+        Variant::KingOfTheHill => variant_heuristics::king_of_the_hill(position, side),
+        Variant::ThreeCheck => variant_heuristics::three_check(game.state(), side),
+        Variant::Crazyhouse => variant_heuristics::crazyhouse(position, side, params),
+        Variant::Standard | Variant::Antichess | Variant::Atomic => 0,
+    };
+
+    scale(
+        position,
+        side,
+        sq_score + piece_score + center_control + king_safety_bonus + mobility_bonus,
+    ) + variant_bonus
+}
+
+// pulls evals of drawish endgames towards zero
+fn scale(position: &Position, side: Side, eval: i32) -> i32 {
+    let strong_side = if eval >= 0 { side } else { side.opposite() };
+    eval * endgame::scale_factor(position, strong_side) / endgame::SCALE_NORMAL
+}
+
+// the material a side has on the board
+pub fn piece_score(position: &Position, side: Side, params: &EvalParams) -> i32 {
+    position
+        .bb_pieces()
+        .iter()
+        .enumerate()
+        .map(|(i, piece_bb)| {
+            (*piece_bb & position.bb_side(side)).count_ones() as i32
+                * params.piece_value(PIECE_TYPE_MAP[i])
+        })
+        .sum()
+}
+
+fn sq_score(position: &Position, side: Side, params: &EvalParams) -> i32 {
+    let phase = position.phase();
+
+    position
+        .bb_side(side)
+        .iter()
+        .map(|sq| {
+            let piece_type = position.at(sq).unwrap().piece_type();
+            params.psqt(side, piece_type, sq).get(phase)
+        })
+        .sum()
 }
 
-const INNER_CENTER_CONTROL_MULTIPLER: u32 = 30;
-const OUTER_CENTER_CONTROL_MULTIPLER: u32 = 20;
-fn center_control(controlled_squares: BB) -> u32 {
+const INNER_CENTER_CONTROL_MULTIPLER: i32 = 30;
+const OUTER_CENTER_CONTROL_MULTIPLER: i32 = 20;
+fn center_control(controlled_squares: BB, params: &EvalParams) -> i32 {
     let inner_center_control_bb = controlled_squares & bitboard::INNER_CENTER;
     let outer_center_control_bb = controlled_squares & bitboard::OUTER_CENTER;
 
-    inner_center_control_bb.count_ones() * INNER_CENTER_CONTROL_MULTIPLER
-        + outer_center_control_bb.count_ones() * OUTER_CENTER_CONTROL_MULTIPLER
+    inner_center_control_bb.count_ones() as i32 * params.inner_center_control()
+        + outer_center_control_bb.count_ones() as i32 * params.outer_center_control()
 }
 
-const MOBILITY_MULTIPLIER: u32 = 1;
-fn mobility(controlled_squares: BB) -> u32 {
-    controlled_squares.count_ones() * MOBILITY_MULTIPLIER
+const MOBILITY_MULTIPLIER: i32 = 1;
+fn mobility(controlled_squares: BB, params: &EvalParams) -> i32 {
+    controlled_squares.count_ones() as i32 * params.mobility()
 }
@@ -0,0 +1,491 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use crate::{
+    piece_type::{PieceType, PIECE_TYPE_MAP},
+    side::Side,
+    square::Square,
+    state::position::Position,
+};
+
+use super::{kpk, params::EvalParams};
+
+// eval given to endgames that are won but where mate hasn't been found yet
+pub const KNOWN_WIN: i32 = 5000;
+// scale factors are out of SCALE_NORMAL, so a factor of 16 keeps a quarter of the eval
+pub const SCALE_NORMAL: i32 = 64;
+
+// counts of each piece besides the king for both sides, white's in the low bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MaterialKey(u64);
+
+const COUNTED_PIECE_TYPES: usize = 5;
+const COUNT_BITS: usize = 4;
+
+impl MaterialKey {
+    pub fn from_position(position: &Position) -> MaterialKey {
+        let mut key = 0;
+        for side in [Side::White, Side::Black] {
+            for (i, piece_type) in PIECE_TYPE_MAP.iter().take(COUNTED_PIECE_TYPES).enumerate() {
+                let count = position.bb_pc(*piece_type, side).count_ones() as u64;
+                key |= count << (COUNT_BITS * (side.to_usize() * COUNTED_PIECE_TYPES + i));
+            }
+        }
+
+        MaterialKey(key)
+    }
+
+    // parses a signature like "KBNK", the strong side's pieces come first
+    fn from_signature(signature: &str, strong_side: Side) -> MaterialKey {
+        let weak_start = signature[1..].find('K').unwrap() + 1;
+        let (strong, weak) = signature.split_at(weak_start);
+
+        let mut key = 0;
+        for (side, pieces) in [(strong_side, strong), (strong_side.opposite(), weak)] {
+            for c in pieces[1..].chars() {
+                let piece_type = PieceType::try_from(c.to_ascii_lowercase()).unwrap();
+                key += 1
+                    << (COUNT_BITS
+                        * (side.to_usize() * COUNTED_PIECE_TYPES + piece_type.to_usize()));
+            }
+        }
+
+        MaterialKey(key)
+    }
+}
+
+type EndgameEval = fn(&Position, Side, Side, &EvalParams) -> i32;
+type EndgameScale = fn(&Position, Side) -> i32;
+
+#[derive(Clone, Copy)]
+enum Endgame {
+    Eval(EndgameEval),
+    Scale(EndgameScale),
+}
+
+const ENDGAMES: [(&str, Endgame); 7] = [
+    ("KBNK", Endgame::Eval(kbnk)),
+    ("KPK", Endgame::Eval(kpk)),
+    ("KRKP", Endgame::Eval(krkp)),
+    ("KQKR", Endgame::Eval(kqkr)),
+    ("KRKB", Endgame::Scale(|_, _| 8)),
+    ("KRKN", Endgame::Scale(|_, _| 8)),
+    ("KNNK", Endgame::Scale(|_, _| 0)),
+];
+
+fn endgames() -> &'static HashMap<MaterialKey, (Endgame, Side)> {
+    static ENDGAME_MAP: OnceLock<HashMap<MaterialKey, (Endgame, Side)>> = OnceLock::new();
+    ENDGAME_MAP.get_or_init(|| {
+        let mut map = HashMap::new();
+        for (signature, endgame) in ENDGAMES {
+            for strong_side in [Side::White, Side::Black] {
+                map.insert(
+                    MaterialKey::from_signature(signature, strong_side),
+                    (endgame, strong_side),
+                );
+            }
+        }
+
+        map
+    })
+}
+
+// an exact eval for endgames with a specialised evaluator, relative to the side to move
+pub fn probe(position: &Position, side_to_move: Side, params: &EvalParams) -> Option<i32> {
+    let key = MaterialKey::from_position(position);
+
+    let (eval, strong_side) = match endgames().get(&key) {
+        Some((Endgame::Eval(eval), strong_side)) => (*eval, *strong_side),
+        _ => {
+            let strong_side = [Side::White, Side::Black]
+                .into_iter()
+                .find(|side| is_kxk(position, *side))?;
+            (kxk as EndgameEval, strong_side)
+        }
+    };
+
+    let score = eval(position, strong_side, side_to_move, params);
+    if strong_side == side_to_move {
+        Some(score)
+    } else {
+        Some(-score)
+    }
+}
+
+// how much of the eval to keep when `strong_side` is the side the eval favours
+pub fn scale_factor(position: &Position, strong_side: Side) -> i32 {
+    let key = MaterialKey::from_position(position);
+    if let Some((Endgame::Scale(scale), side)) = endgames().get(&key) {
+        if *side == strong_side {
+            return scale(position, strong_side);
+        }
+    }
+
+    let weak_side = strong_side.opposite();
+    if position.bb_pc(PieceType::Pawn, strong_side).empty() {
+        let strong_npm = non_pawn_material(position, strong_side);
+        let weak_npm = non_pawn_material(position, weak_side);
+        if strong_npm - weak_npm <= BISHOP_VALUE {
+            return if strong_npm < ROOK_VALUE {
+                0
+            } else if weak_npm <= BISHOP_VALUE {
+                4
+            } else {
+                14
+            };
+        }
+    }
+
+    if is_wrong_bishop(position, strong_side) {
+        return 0;
+    }
+
+    if is_opposite_bishops(position) {
+        return 16;
+    }
+
+    SCALE_NORMAL
+}
+
+// material counted the same way no matter the params, so tuning can't change which endgames are
+// considered drawish
+const BISHOP_VALUE: i32 = 350;
+const ROOK_VALUE: i32 = 500;
+
+fn non_pawn_material(position: &Position, side: Side) -> i32 {
+    [
+        (PieceType::Knight, 300),
+        (PieceType::Bishop, BISHOP_VALUE),
+        (PieceType::Rook, ROOK_VALUE),
+        (PieceType::Queen, 900),
+    ]
+    .iter()
+    .map(|(piece_type, value)| position.bb_pc(*piece_type, side).count_ones() as i32 * value)
+    .sum()
+}
+
+fn is_dark_sq(sq: Square) -> bool {
+    (sq.rank() + sq.file()).is_multiple_of(2)
+}
+
+fn distance(sq_1: Square, sq_2: Square) -> i32 {
+    let rank_distance = (sq_1.rank() as i32 - sq_2.rank() as i32).abs();
+    let file_distance = (sq_1.file() as i32 - sq_2.file() as i32).abs();
+    rank_distance.max(file_distance)
+}
+
+fn relative_rank(side: Side, sq: Square) -> i32 {
+    match side {
+        Side::White => sq.rank() as i32,
+        Side::Black => 7 - sq.rank() as i32,
+    }
+}
+
+// bonus for the losing king being near the edge of the board
+fn push_to_edge(sq: Square) -> i32 {
+    let file_distance = sq.file().min(7 - sq.file()) as i32;
+    let rank_distance = sq.rank().min(7 - sq.rank()) as i32;
+    90 - (7 * file_distance * file_distance / 2 + 7 * rank_distance * rank_distance / 2)
+}
+
+// bonus for the kings being close to each other
+fn push_close(sq_1: Square, sq_2: Square) -> i32 {
+    140 - 20 * distance(sq_1, sq_2)
+}
+
+// bonus for being close to a1 or h8
+fn push_to_dark_corner(sq: Square) -> i32 {
+    (7 - sq.rank() as i32 - sq.file() as i32).abs()
+}
+
+fn material(position: &Position, side: Side, params: &EvalParams) -> i32 {
+    PIECE_TYPE_MAP
+        .iter()
+        .take(COUNTED_PIECE_TYPES)
+        .map(|piece_type| {
+            position.bb_pc(*piece_type, side).count_ones() as i32 * params.piece_value(*piece_type)
+        })
+        .sum()
+}
+
+fn is_bare_king(position: &Position, side: Side) -> bool {
+    position.bb_side(side).count_ones() == 1
+}
+
+// a lone king against enough material to force mate
+fn is_kxk(position: &Position, strong_side: Side) -> bool {
+    if !is_bare_king(position, strong_side.opposite()) {
+        return false;
+    }
+
+    let bishops = position.bb_pc(PieceType::Bishop, strong_side);
+    let has_both_bishops =
+        bishops.iter().any(is_dark_sq) && bishops.iter().any(|sq| !is_dark_sq(sq));
+    (position.bb_pc(PieceType::Queen, strong_side) | position.bb_pc(PieceType::Rook, strong_side))
+        .not_empty()
+        || has_both_bishops
+        || (bishops.not_empty() && position.bb_pc(PieceType::Knight, strong_side).not_empty())
+}
+
+// drive the lone king to the edge and bring the kings together
+fn kxk(position: &Position, strong_side: Side, _: Side, params: &EvalParams) -> i32 {
+    let strong_king = position.king_sq(strong_side);
+    let weak_king = position.king_sq(strong_side.opposite());
+
+    KNOWN_WIN
+        + material(position, strong_side, params)
+        + push_to_edge(weak_king)
+        + push_close(strong_king, weak_king)
+}
+
+// mate can only be forced in the corners the bishop controls
+fn kbnk(position: &Position, strong_side: Side, _: Side, params: &EvalParams) -> i32 {
+    let strong_king = position.king_sq(strong_side);
+    let weak_king = position.king_sq(strong_side.opposite());
+    let bishop = position.bb_pc(PieceType::Bishop, strong_side).bitscan();
+
+    // flip the file so the bishop's corners become a1 and h8
+    let corner_sq = if is_dark_sq(bishop) {
+        weak_king
+    } else {
+        Square::from(weak_king.rank(), 7 - weak_king.file())
+    };
+
+    KNOWN_WIN
+        + material(position, strong_side, params)
+        + push_close(strong_king, weak_king)
+        + 40 * push_to_dark_corner(corner_sq)
+}
+
+// exact result from the bitbase, pushing the pawn is preferred once the position is won
+fn kpk(position: &Position, strong_side: Side, side_to_move: Side, params: &EvalParams) -> i32 {
+    if kpk::probe(position, side_to_move) != Some(true) {
+        return 0;
+    }
+
+    let pawn = position.bb_pc(PieceType::Pawn, strong_side).bitscan();
+    KNOWN_WIN + params.piece_value(PieceType::Pawn) + 10 * relative_rank(strong_side, pawn)
+}
+
+// port of the usual rook against pawn rules, the rook wins unless the pawn is far advanced and
+// supported by its king
+fn krkp(position: &Position, strong_side: Side, side_to_move: Side, params: &EvalParams) -> i32 {
+    let weak_side = strong_side.opposite();
+    let strong_king = position.king_sq(strong_side);
+    let weak_king = position.king_sq(weak_side);
+    let rook = position.bb_pc(PieceType::Rook, strong_side).bitscan();
+    let pawn = position.bb_pc(PieceType::Pawn, weak_side).bitscan();
+    let rook_value = params.piece_value(PieceType::Rook);
+
+    let queening_sq = Square::from(if weak_side == Side::White { 7 } else { 0 }, pawn.file());
+    // the square in front of the pawn from the pawn's point of view
+    let push_sq = if weak_side == Side::White {
+        Square::from((pawn.rank() + 1).min(7), pawn.file())
+    } else {
+        Square::from(pawn.rank().saturating_sub(1), pawn.file())
+    };
+
+    let strong_king_in_front = strong_king.file() == pawn.file()
+        && relative_rank(weak_side, strong_king) > relative_rank(weak_side, pawn);
+    if strong_king_in_front {
+        return rook_value - distance(strong_king, pawn);
+    }
+
+    if distance(weak_king, pawn) >= 3 + (side_to_move == strong_side) as i32
+        && distance(weak_king, rook) >= 3
+    {
+        return rook_value - distance(strong_king, pawn);
+    }
+
+    if relative_rank(strong_side, weak_king) <= 2
+        && distance(weak_king, pawn) == 1
+        && relative_rank(strong_side, strong_king) >= 3
+        && distance(strong_king, pawn) > 2 + (side_to_move == strong_side) as i32
+    {
+        return 80 - 8 * distance(strong_king, pawn);
+    }
+
+    200 - 8
+        * (distance(strong_king, push_sq)
+            - distance(weak_king, push_sq)
+            - distance(pawn, queening_sq))
+}
+
+fn kqkr(position: &Position, strong_side: Side, _: Side, params: &EvalParams) -> i32 {
+    let strong_king = position.king_sq(strong_side);
+    let weak_king = position.king_sq(strong_side.opposite());
+
+    params.piece_value(PieceType::Queen) - params.piece_value(PieceType::Rook)
+        + push_to_edge(weak_king)
+        + push_close(strong_king, weak_king)
+}
+
+// bishop and rook pawns where the bishop doesn't control the promotion square and the weak king
+// is already in the corner
+fn is_wrong_bishop(position: &Position, strong_side: Side) -> bool {
+    let weak_side = strong_side.opposite();
+    let pawns = position.bb_pc(PieceType::Pawn, strong_side);
+    let bishops = position.bb_pc(PieceType::Bishop, strong_side);
+    let other_pieces = position.bb_side(strong_side)
+        ^ pawns
+        ^ bishops
+        ^ position.bb_pc(PieceType::King, strong_side);
+    if pawns.empty() || bishops.count_ones() != 1 || other_pieces.not_empty() {
+        return false;
+    }
+    if position.bb_side(weak_side) != position.bb_pc(PieceType::King, weak_side) {
+        return false;
+    }
+
+    let file = pawns.bitscan().file();
+    if (file != 0 && file != 7) || pawns.iter().any(|sq| sq.file() != file) {
+        return false;
+    }
+
+    let promotion_sq = Square::from(if strong_side == Side::White { 7 } else { 0 }, file);
+    is_dark_sq(bishops.bitscan()) != is_dark_sq(promotion_sq)
+        && distance(position.king_sq(weak_side), promotion_sq) <= 1
+}
+
+// each side only has a bishop and pawns, and the bishops are on different colours
+fn is_opposite_bishops(position: &Position) -> bool {
+    let bishops = position.bb_pieces()[PieceType::Bishop.to_usize()];
+    let pawns_and_kings = position.bb_pieces()[PieceType::Pawn.to_usize()]
+        | position.bb_pieces()[PieceType::King.to_usize()];
+    if bishops.count_ones() != 2 || (position.bb_occupied() ^ bishops ^ pawns_and_kings).not_empty()
+    {
+        return false;
+    }
+
+    let white_bishop = position.bb_pc(PieceType::Bishop, Side::White);
+    let black_bishop = position.bb_pc(PieceType::Bishop, Side::Black);
+    white_bishop.not_empty()
+        && black_bishop.not_empty()
+        && is_dark_sq(white_bishop.bitscan()) != is_dark_sq(black_bishop.bitscan())
+}
+
+#[cfg(test)]
+pub mod test_endgame {
+    use super::*;
+    use crate::game::Game;
+
+    fn probe_fen(fen: &str) -> Option<i32> {
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        probe(
+            game.position(),
+            game.state().side_to_move(),
+            &EvalParams::default(),
+        )
+    }
+
+    fn scale_fen(fen: &str, strong_side: Side) -> i32 {
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        scale_factor(game.position(), strong_side)
+    }
+
+    #[test]
+    fn material_key_matches_signature() {
+        let result = Game::from_fen("8/8/4k3/8/8/2BNK3/8/8 w - - 0 1");
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(
+            MaterialKey::from_position(game.position()),
+            MaterialKey::from_signature("KBNK", Side::White)
+        );
+        assert_ne!(
+            MaterialKey::from_position(game.position()),
+            MaterialKey::from_signature("KBNK", Side::Black)
+        );
+    }
+
+    #[test]
+    fn kxk_drives_king_to_edge() {
+        let edge = probe_fen("4k3/8/4K3/8/8/8/8/7R w - - 0 1");
+        let center = probe_fen("8/8/8/4k3/8/8/4K3/7R w - - 0 1");
+        assert!(edge.is_some() && center.is_some());
+        assert!(edge.unwrap() > center.unwrap());
+        assert!(center.unwrap() > KNOWN_WIN);
+
+        // relative to the side to move
+        let black_to_move = probe_fen("4k3/8/4K3/8/8/8/8/7R b - - 0 1");
+        assert_eq!(black_to_move, edge.map(|eval| -eval));
+    }
+
+    #[test]
+    fn kbnk_prefers_bishop_corner() {
+        // light squared bishop, so h1 and a8 are the mating corners
+        let right_corner = probe_fen("k7/8/1K6/8/8/8/8/3BN3 w - - 0 1");
+        let wrong_corner = probe_fen("8/8/8/8/8/1K6/8/k2BN3 w - - 0 1");
+        assert!(right_corner.is_some() && wrong_corner.is_some());
+        assert!(right_corner.unwrap() > wrong_corner.unwrap());
+    }
+
+    #[test]
+    fn kpk() {
+        // pawn runs away from the king
+        let win = probe_fen("8/8/8/8/P7/8/8/k6K w - - 0 1");
+        assert!(win.is_some() && win.unwrap() > KNOWN_WIN);
+
+        // rook pawn with the king in the corner
+        let draw = probe_fen("k7/8/8/P7/8/8/8/7K w - - 0 1");
+        assert_eq!(draw, Some(0));
+
+        // king on a key square
+        let key_sq = probe_fen("8/4k3/8/3K4/8/4P3/8/8 w - - 0 1");
+        assert!(key_sq.is_some() && key_sq.unwrap() > KNOWN_WIN);
+    }
+
+    #[test]
+    fn krkp() {
+        // strong king in front of the pawn
+        let win = probe_fen("8/8/8/8/3k4/3p4/3K4/7R w - - 0 1");
+        assert!(win.is_some() && win.unwrap() > 400);
+
+        // advanced pawn supported by its king with the strong king far away
+        let drawish = probe_fen("K7/8/8/8/8/8/2kp4/7R w - - 0 1");
+        assert!(drawish.is_some() && drawish.unwrap() < 100);
+    }
+
+    #[test]
+    fn kqkr() {
+        let eval = probe_fen("8/8/8/3k4/8/8/3r4/3QK3 w - - 0 1");
+        assert!(eval.is_some() && eval.unwrap() > 400);
+    }
+
+    #[test]
+    fn general_positions_are_not_probed() {
+        assert!(probe_fen("8/8/4k3/4p3/3P4/8/4K3/3R4 w - - 0 1").is_none());
+        assert!(probe_fen(crate::fen::STARTING_POSITION_FEN).is_none());
+    }
+
+    #[test]
+    fn drawish_scale_factors() {
+        assert_eq!(
+            scale_fen("8/8/4k3/4b3/8/8/4K3/3R4 w - - 0 1", Side::White),
+            8
+        );
+        assert_eq!(
+            scale_fen("8/8/4k3/8/8/8/3NK3/3N4 w - - 0 1", Side::White),
+            0
+        );
+        // opposite coloured bishops
+        assert_eq!(
+            scale_fen("8/5k2/4pb2/8/3P4/2PB4/4K3/8 w - - 0 1", Side::White),
+            16
+        );
+        // wrong rook pawn
+        assert_eq!(scale_fen("7k/8/8/7P/8/8/4B3/4K3 w - - 0 1", Side::White), 0);
+        // minor piece up with no pawns
+        assert_eq!(
+            scale_fen("8/8/4k3/4r3/8/8/2BRK3/8 w - - 0 1", Side::White),
+            14
+        );
+        assert_eq!(
+            scale_fen(crate::fen::STARTING_POSITION_FEN, Side::White),
+            SCALE_NORMAL
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use std::sync::OnceLock;
+
+use crate::{
+    bitboard::{BB, KING_MOVES, PAWN_CAPTURES, PAWN_PUSHES},
+    piece_type::PieceType,
+    side::Side,
+    square::Square,
+    state::position::Position,
+};
+
+// every king and pawn against king position with white as the side with the pawn and the pawn on
+// files a to d, other positions are flipped and mirrored onto these
+// index = side to move + 2 * (black king + 64 * (white king + 64 * (pawn file + 4 * (pawn rank - 1))))
+const KPK_SIZE: usize = 2 * 64 * 64 * 4 * 6;
+
+// results found by the retrograde analysis, these are flags so the results of all the moves
+// from a position can be or'd together
+const INVALID: u8 = 0;
+const UNKNOWN: u8 = 1;
+const DRAW: u8 = 2;
+const WIN: u8 = 4;
+
+pub struct KpkBitbase {
+    wins: Vec<u64>,
+}
+
+fn index(side_to_move: Side, black_king: Square, white_king: Square, pawn: Square) -> usize {
+    side_to_move.to_usize()
+        + 2 * (black_king.to_usize()
+            + 64 * (white_king.to_usize() + 64 * (pawn.file() + 4 * (pawn.rank() - 1))))
+}
+
+fn decode(idx: usize) -> (Side, Square, Square, Square) {
+    let side_to_move = if idx & 1 == 0 {
+        Side::White
+    } else {
+        Side::Black
+    };
+    let black_king = Square((idx >> 1) & 63);
+    let white_king = Square((idx >> 7) & 63);
+    let pawn_file = (idx >> 13) & 3;
+    let pawn_rank = (idx >> 15) + 1;
+
+    (
+        side_to_move,
+        black_king,
+        white_king,
+        Square::from(pawn_rank, pawn_file),
+    )
+}
+
+// results that are known without looking at any moves
+fn initial_result(idx: usize) -> u8 {
+    let (side_to_move, black_king, white_king, pawn) = decode(idx);
+    let white_king_moves = KING_MOVES[white_king.to_usize()];
+    let pawn_attacks = PAWN_CAPTURES[Side::White.to_usize()][pawn.to_usize()];
+
+    if white_king == black_king
+        || white_king_moves.is_set(black_king)
+        || white_king == pawn
+        || black_king == pawn
+    {
+        return INVALID;
+    }
+
+    match side_to_move {
+        Side::White => {
+            // black can't be in check with white to move
+            if pawn_attacks.is_set(black_king) {
+                return INVALID;
+            }
+
+            // the pawn promotes and the queen can't be taken
+            let promotion_sq = Square(pawn.to_usize() + 8);
+            if pawn.rank() == 6
+                && promotion_sq != white_king
+                && promotion_sq != black_king
+                && (!KING_MOVES[black_king.to_usize()].is_set(promotion_sq)
+                    || white_king_moves.is_set(promotion_sq))
+            {
+                return WIN;
+            }
+
+            UNKNOWN
+        }
+        Side::Black => {
+            let black_king_moves = KING_MOVES[black_king.to_usize()];
+
+            // stalemate
+            if (black_king_moves & !(white_king_moves | pawn_attacks)).empty() {
+                return DRAW;
+            }
+
+            // the pawn can be taken
+            if black_king_moves.is_set(pawn) && !white_king_moves.is_set(pawn) {
+                return DRAW;
+            }
+
+            UNKNOWN
+        }
+    }
+}
+
+// combines the results of every move, white is looking for a win and black for a draw
+fn classify(results: &[u8], idx: usize) -> u8 {
+    let (side_to_move, black_king, white_king, pawn) = decode(idx);
+    let white_king_moves = KING_MOVES[white_king.to_usize()];
+    let black_king_moves = KING_MOVES[black_king.to_usize()];
+
+    let mut result = INVALID;
+    let (good, bad) = match side_to_move {
+        Side::White => {
+            let king_moves = white_king_moves & !black_king_moves & !BB::new(pawn);
+            for to in king_moves.iter() {
+                result |= results[index(Side::Black, black_king, to, pawn)];
+            }
+
+            // pushes to the last rank are handled as wins when the position is set up
+            if pawn.rank() < 6 {
+                let push = PAWN_PUSHES[Side::White.to_usize()][pawn.to_usize()].bitscan();
+                if push != white_king && push != black_king {
+                    result |= results[index(Side::Black, black_king, white_king, push)];
+
+                    let double_push = Square(push.to_usize() + 8);
+                    if pawn.rank() == 1 && double_push != white_king && double_push != black_king {
+                        result |= results[index(Side::Black, black_king, white_king, double_push)];
+                    }
+                }
+            }
+
+            (WIN, DRAW)
+        }
+        Side::Black => {
+            let pawn_attacks = PAWN_CAPTURES[Side::White.to_usize()][pawn.to_usize()];
+            let king_moves = black_king_moves & !white_king_moves & !pawn_attacks;
+            for to in king_moves.iter() {
+                result |= results[index(Side::White, to, white_king, pawn)];
+            }
+
+            (DRAW, WIN)
+        }
+    };
+
+    if result & good != 0 {
+        good
+    } else if result & UNKNOWN != 0 {
+        UNKNOWN
+    } else {
+        bad
+    }
+}
+
+impl KpkBitbase {
+    pub fn generate() -> KpkBitbase {
+        let mut results: Vec<u8> = (0..KPK_SIZE).map(initial_result).collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for idx in 0..KPK_SIZE {
+                if results[idx] == UNKNOWN {
+                    results[idx] = classify(&results, idx);
+                    changed |= results[idx] != UNKNOWN;
+                }
+            }
+        }
+
+        // anything still unknown can't be forced into a win
+        let mut wins = vec![0; KPK_SIZE / 64];
+        for (idx, result) in results.iter().enumerate() {
+            if *result == WIN {
+                wins[idx / 64] |= 1 << (idx % 64);
+            }
+        }
+
+        KpkBitbase { wins }
+    }
+
+    fn is_win(&self, idx: usize) -> bool {
+        self.wins[idx / 64] & (1 << (idx % 64)) != 0
+    }
+}
+
+fn bitbase() -> &'static KpkBitbase {
+    static BITBASE: OnceLock<KpkBitbase> = OnceLock::new();
+    BITBASE.get_or_init(KpkBitbase::generate)
+}
+
+// builds the bitbase ahead of time so the first search that reaches kpk doesn't have to
+pub fn init() {
+    bitbase();
+}
+
+// whether the side with the pawn wins, none if the position isn't king and pawn against king
+pub fn probe(position: &Position, side_to_move: Side) -> Option<bool> {
+    if position.bb_occupied().count_ones() != 3 {
+        return None;
+    }
+    let pawns = position.bb_pieces()[PieceType::Pawn.to_usize()];
+    if pawns.count_ones() != 1 {
+        return None;
+    }
+
+    let pawn = pawns.bitscan();
+    let strong_side = if position.bb_side(Side::White).is_set(pawn) {
+        Side::White
+    } else {
+        Side::Black
+    };
+
+    // flip so the pawn is white's, then mirror so it's on files a to d
+    let flip = |sq: Square| match strong_side {
+        Side::White => sq.to_usize(),
+        Side::Black => sq.to_usize() ^ 56,
+    };
+    let mirror = if pawn.file() >= 4 { 7 } else { 0 };
+    let normalise = |sq: Square| Square(flip(sq) ^ mirror);
+
+    let side_to_move = if strong_side == Side::White {
+        side_to_move
+    } else {
+        side_to_move.opposite()
+    };
+    let idx = index(
+        side_to_move,
+        normalise(position.king_sq(strong_side.opposite())),
+        normalise(position.king_sq(strong_side)),
+        normalise(pawn),
+    );
+
+    Some(bitbase().is_win(idx))
+}
+
+#[cfg(test)]
+pub mod test_kpk {
+    use super::*;
+    use crate::game::Game;
+
+    fn probe_fen(fen: &str) -> Option<bool> {
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        probe(game.position(), game.state().side_to_move())
+    }
+
+    #[test]
+    fn index_round_trip() {
+        for idx in [0, 1, 12345, KPK_SIZE - 1] {
+            let (side_to_move, black_king, white_king, pawn) = decode(idx);
+            assert_eq!(index(side_to_move, black_king, white_king, pawn), idx);
+        }
+    }
+
+    #[test]
+    fn not_kpk() {
+        assert_eq!(probe_fen("4k3/8/8/8/8/8/4P3/3RK3 w - - 0 1"), None);
+        assert_eq!(probe_fen("4k3/8/8/8/8/8/8/3NK3 w - - 0 1"), None);
+    }
+
+    #[test]
+    fn king_in_front_on_sixth_wins() {
+        assert_eq!(probe_fen("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1"), Some(true));
+        assert_eq!(probe_fen("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1"), Some(true));
+    }
+
+    #[test]
+    fn opposition() {
+        // whoever has to move loses the opposition
+        assert_eq!(probe_fen("8/8/4k3/8/4K3/4P3/8/8 w - - 0 1"), Some(false));
+        assert_eq!(probe_fen("8/8/4k3/8/4K3/4P3/8/8 b - - 0 1"), Some(true));
+    }
+
+    #[test]
+    fn stalemate_and_rook_pawn_draws() {
+        assert_eq!(probe_fen("4k3/4P3/4K3/8/8/8/8/8 b - - 0 1"), Some(false));
+        assert_eq!(probe_fen("k7/8/8/P7/8/8/8/7K w - - 0 1"), Some(false));
+        assert_eq!(probe_fen("8/8/8/8/P7/8/8/k6K w - - 0 1"), Some(true));
+    }
+
+    #[test]
+    fn colours_and_sides_are_symmetric() {
+        // same positions as in opposition with black as the side with the pawn and mirrored
+        assert_eq!(probe_fen("8/8/3p4/3k4/8/3K4/8/8 b - - 0 1"), Some(false));
+        assert_eq!(probe_fen("8/8/3p4/3k4/8/3K4/8/8 w - - 0 1"), Some(true));
+    }
+}
@@ -0,0 +1,295 @@
+use std::{fmt, fs, str::FromStr};
+
+use crate::{
+    piece_type::{PieceType, PIECE_TYPE_COUNT},
+    psqt::{generate_psqt, PsqtTable, RawPsqtTables, DEFAULT_RAW_PSQT, PSQT},
+    score::Score,
+    side::Side,
+    square::Square,
+};
+
+use super::{
+    king_heuristics::PAWNS_IN_FRONT_OF_KING_MULTIPLIER, INNER_CENTER_CONTROL_MULTIPLER,
+    MOBILITY_MULTIPLIER, OUTER_CENTER_CONTROL_MULTIPLER,
+};
+
+pub const DEFAULT_PIECE_VALUES: [i32; PIECE_TYPE_COUNT] = [100, 300, 350, 500, 900, 10000];
+
+// every weight used by eval. the params file is a list of lines in the form of
+// `<name> <value> <value> ...`, lines starting with # are comments and any param left out of the
+// file keeps its compiled in default. psqt values are written as opening, middle, end triples
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvalParams {
+    piece_values: [i32; PIECE_TYPE_COUNT],
+    inner_center_control: i32,
+    outer_center_control: i32,
+    mobility: i32,
+    pawns_in_front_of_king: i32,
+    raw_psqt: RawPsqtTables,
+    psqt: [PsqtTable; 2],
+}
+
+const PIECE_VALUES: &str = "piece_values";
+const INNER_CENTER_CONTROL: &str = "inner_center_control";
+const OUTER_CENTER_CONTROL: &str = "outer_center_control";
+const MOBILITY: &str = "mobility";
+const PAWNS_IN_FRONT_OF_KING: &str = "pawns_in_front_of_king";
+const PAWN_PSQT: &str = "pawn_psqt";
+const KNIGHT_PSQT: &str = "knight_psqt";
+const BISHOP_PSQT: &str = "bishop_psqt";
+const ROOK_PSQT: &str = "rook_psqt";
+const QUEEN_PSQT: &str = "queen_psqt";
+const KING_PSQT: &str = "king_psqt";
+
+pub const PARAM_NAMES: [&str; 11] = [
+    PIECE_VALUES,
+    INNER_CENTER_CONTROL,
+    OUTER_CENTER_CONTROL,
+    MOBILITY,
+    PAWNS_IN_FRONT_OF_KING,
+    PAWN_PSQT,
+    KNIGHT_PSQT,
+    BISHOP_PSQT,
+    ROOK_PSQT,
+    QUEEN_PSQT,
+    KING_PSQT,
+];
+
+impl Default for EvalParams {
+    fn default() -> EvalParams {
+        EvalParams {
+            piece_values: DEFAULT_PIECE_VALUES,
+            inner_center_control: INNER_CENTER_CONTROL_MULTIPLER,
+            outer_center_control: OUTER_CENTER_CONTROL_MULTIPLER,
+            mobility: MOBILITY_MULTIPLIER,
+            pawns_in_front_of_king: PAWNS_IN_FRONT_OF_KING_MULTIPLIER,
+            raw_psqt: DEFAULT_RAW_PSQT,
+            psqt: PSQT,
+        }
+    }
+}
+
+impl EvalParams {
+    pub fn load(path: &str) -> Result<EvalParams, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("unable to read eval params from {}: {}", path, err))?;
+
+        contents.parse()
+    }
+
+    pub fn piece_value(&self, piece_type: PieceType) -> i32 {
+        self.piece_values[piece_type.to_usize()]
+    }
+
+    pub fn inner_center_control(&self) -> i32 {
+        self.inner_center_control
+    }
+
+    pub fn outer_center_control(&self) -> i32 {
+        self.outer_center_control
+    }
+
+    pub fn mobility(&self) -> i32 {
+        self.mobility
+    }
+
+    pub fn pawns_in_front_of_king(&self) -> i32 {
+        self.pawns_in_front_of_king
+    }
+
+    pub fn psqt(&self, side: Side, piece_type: PieceType, sq: Square) -> Score {
+        self.psqt[side.to_usize()][piece_type.to_usize()][sq.to_usize()]
+    }
+
+    pub fn values(&self, name: &str) -> Option<Vec<i32>> {
+        let values = match name {
+            PIECE_VALUES => self.piece_values.to_vec(),
+            INNER_CENTER_CONTROL => vec![self.inner_center_control],
+            OUTER_CENTER_CONTROL => vec![self.outer_center_control],
+            MOBILITY => vec![self.mobility],
+            PAWNS_IN_FRONT_OF_KING => vec![self.pawns_in_front_of_king],
+            PAWN_PSQT => scores_to_values(&self.raw_psqt.pawn),
+            KNIGHT_PSQT => scores_to_values(&self.raw_psqt.knight),
+            BISHOP_PSQT => scores_to_values(&self.raw_psqt.bishop),
+            ROOK_PSQT => scores_to_values(&self.raw_psqt.rook),
+            QUEEN_PSQT => scores_to_values(&self.raw_psqt.queen),
+            KING_PSQT => scores_to_values(&self.raw_psqt.king),
+            _ => return None,
+        };
+
+        Some(values)
+    }
+
+    pub fn set_values(&mut self, name: &str, values: &[i32]) -> Result<(), String> {
+        let expected_len = self
+            .values(name)
+            .ok_or(format!("{} is not an eval param", name))?
+            .len();
+        if values.len() != expected_len {
+            return Err(format!(
+                "{} expects {} values but was given {}",
+                name,
+                expected_len,
+                values.len()
+            ));
+        }
+
+        match name {
+            PIECE_VALUES => self.piece_values.copy_from_slice(values),
+            INNER_CENTER_CONTROL => self.inner_center_control = values[0],
+            OUTER_CENTER_CONTROL => self.outer_center_control = values[0],
+            MOBILITY => self.mobility = values[0],
+            PAWNS_IN_FRONT_OF_KING => self.pawns_in_front_of_king = values[0],
+            PAWN_PSQT => values_to_scores(values, &mut self.raw_psqt.pawn),
+            KNIGHT_PSQT => values_to_scores(values, &mut self.raw_psqt.knight),
+            BISHOP_PSQT => values_to_scores(values, &mut self.raw_psqt.bishop),
+            ROOK_PSQT => values_to_scores(values, &mut self.raw_psqt.rook),
+            QUEEN_PSQT => values_to_scores(values, &mut self.raw_psqt.queen),
+            KING_PSQT => values_to_scores(values, &mut self.raw_psqt.king),
+            _ => unreachable!(),
+        };
+        self.psqt = generate_psqt(&self.raw_psqt);
+
+        Ok(())
+    }
+}
+
+fn scores_to_values(scores: &[Score]) -> Vec<i32> {
+    scores
+        .iter()
+        .flat_map(|score| [score.0, score.1, score.2])
+        .collect()
+}
+
+fn values_to_scores(values: &[i32], scores: &mut [Score]) {
+    for (score, phase_values) in scores.iter_mut().zip(values.chunks(3)) {
+        *score = Score(phase_values[0], phase_values[1], phase_values[2]);
+    }
+}
+
+impl FromStr for EvalParams {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<EvalParams, String> {
+        let mut params = EvalParams::default();
+
+        for line in s.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut tokens = line.split_whitespace();
+            let name = tokens.next().unwrap();
+            let values = tokens
+                .map(|token| {
+                    token
+                        .parse::<i32>()
+                        .map_err(|_| format!("{} is not a number, param: {}", token, name))
+                })
+                .collect::<Result<Vec<i32>, String>>()?;
+
+            params.set_values(name, &values)?;
+        }
+
+        Ok(params)
+    }
+}
+
+impl fmt::Display for EvalParams {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for name in PARAM_NAMES {
+            let values = self.values(name).unwrap();
+            let values_str = values
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<String>>()
+                .join(" ");
+            writeln!(f, "{} {}", name, values_str)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::square::*;
+
+    #[test]
+    fn default_matches_compiled_in_psqt() {
+        let params = EvalParams::default();
+
+        assert_eq!(
+            params.psqt(Side::Black, PieceType::Knight, C6),
+            PSQT[Side::Black.to_usize()][PieceType::Knight.to_usize()][C6.to_usize()]
+        );
+        assert_eq!(
+            params.psqt(Side::White, PieceType::Pawn, E4),
+            PSQT[Side::White.to_usize()][PieceType::Pawn.to_usize()][E4.to_usize()]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let params = EvalParams::default();
+        let parsed = params.to_string().parse::<EvalParams>();
+
+        assert_eq!(parsed, Ok(params));
+    }
+
+    #[test]
+    fn partial_file_keeps_defaults() {
+        let parsed = "# only tune mobility\nmobility 4\n".parse::<EvalParams>();
+        assert!(parsed.is_ok());
+        let params = parsed.unwrap();
+
+        assert_eq!(params.mobility(), 4);
+        assert_eq!(
+            params.inner_center_control(),
+            INNER_CENTER_CONTROL_MULTIPLER
+        );
+        assert_eq!(params.piece_value(PieceType::Queen), 900);
+    }
+
+    #[test]
+    fn psqt_is_regenerated() {
+        let mut values = vec![0; 96];
+        // score for b1, which is mirrored onto g1 and flipped onto b8/g8 for black
+        values[3] = 7;
+        values[4] = 8;
+        values[5] = 9;
+        let mut params = EvalParams::default();
+        assert!(params.set_values("knight_psqt", &values).is_ok());
+
+        assert_eq!(
+            params.psqt(Side::White, PieceType::Knight, G1),
+            Score(7, 8, 9)
+        );
+        assert_eq!(
+            params.psqt(Side::Black, PieceType::Knight, B8),
+            Score(7, 8, 9)
+        );
+        assert_eq!(
+            params.psqt(Side::White, PieceType::Knight, A1),
+            Score(0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn wrong_number_of_values() {
+        assert!("mobility 1 2".parse::<EvalParams>().is_err());
+        assert!("knight_psqt 1 2 3".parse::<EvalParams>().is_err());
+    }
+
+    #[test]
+    fn unknown_param() {
+        assert!("bishop_pair 50".parse::<EvalParams>().is_err());
+    }
+
+    #[test]
+    fn invalid_number() {
+        assert!("mobility one".parse::<EvalParams>().is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{
+    bitboard,
+    side::Side,
+    square::Square,
+    state::{
+        position::{Position, POCKET_PIECE_TYPES},
+        State,
+    },
+    variant::CHECKS_TO_WIN,
+};
+
+use super::params::EvalParams;
+
+// by the number of king moves to the nearest center square, being on it has already won
+const HILL_DISTANCE_BONUS: [i32; 8] = [0, 150, 60, 25, 10, 0, 0, 0];
+// by the checks already given, the third has already won
+const CHECKS_GIVEN_BONUS: [i32; CHECKS_TO_WIN as usize] = [0, 150, 450];
+const ANTICHESS_PIECE_VALUE: i32 = 100;
+
+fn king_distance(a: Square, b: Square) -> usize {
+    a.rank().abs_diff(b.rank()).max(a.file().abs_diff(b.file()))
+}
+
+fn hill_bonus(position: &Position, side: Side) -> i32 {
+    let king_sq = position.king_sq(side);
+    let distance = bitboard::INNER_CENTER
+        .iter()
+        .map(|sq| king_distance(king_sq, sq))
+        .min()
+        .unwrap_or(0);
+    HILL_DISTANCE_BONUS[distance]
+}
+
+pub fn king_of_the_hill(position: &Position, side: Side) -> i32 {
+    hill_bonus(position, side) - hill_bonus(position, side.opposite())
+}
+
+pub fn three_check(state: &State, side: Side) -> i32 {
+    let bonus = |side| {
+        let checks = (state.checks_given(side) as usize).min(CHECKS_GIVEN_BONUS.len() - 1);
+        CHECKS_GIVEN_BONUS[checks]
+    };
+    bonus(side) - bonus(side.opposite())
+}
+
+// losing pieces is the goal, so the material counts for the side with less of it
+pub fn antichess(position: &Position, side: Side) -> i32 {
+    let pieces = |side| position.bb_side(side).count_ones() as i32;
+    (pieces(side.opposite()) - pieces(side)) * ANTICHESS_PIECE_VALUE
+}
+
+// the pieces in the pocket are still material, they are only off the board until they're dropped
+pub fn crazyhouse(position: &Position, side: Side, params: &EvalParams) -> i32 {
+    let pocket_score = |side| {
+        POCKET_PIECE_TYPES
+            .iter()
+            .map(|piece_type| {
+                position.pocket_count(side, *piece_type) as i32 * params.piece_value(*piece_type)
+            })
+            .sum::<i32>()
+    };
+    pocket_score(side) - pocket_score(side.opposite())
+}
+
+#[cfg(test)]
+pub mod test_variant_heuristics {
+    use super::*;
+    use crate::{game::Game, variant::Variant};
+
+    #[test]
+    fn closer_to_the_hill() {
+        let fen = "8/8/4k3/8/8/8/8/K7 w - - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::KingOfTheHill);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert!(king_of_the_hill(game.position(), Side::White) < 0);
+        assert_eq!(
+            king_of_the_hill(game.position(), Side::White),
+            -king_of_the_hill(game.position(), Side::Black)
+        );
+    }
+
+    #[test]
+    fn checks_given() {
+        let fen = "4k3/8/8/8/8/8/8/4K3 w - - 1+3 0 1";
+        let result = Game::from_variant_fen(fen, Variant::ThreeCheck);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(three_check(game.state(), Side::White), 450);
+        assert_eq!(three_check(game.state(), Side::Black), -450);
+    }
+
+    #[test]
+    fn fewer_pieces() {
+        let fen = "4k3/pppp4/8/8/8/8/PP6/8 w - - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Antichess);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(antichess(game.position(), Side::White), 300);
+    }
+
+    #[test]
+    fn pocket_material() {
+        let fen = "rnbqkb1r/pppppppp/8/8/8/8/PPPPPPP1/RNBQKBN1[Np] w Qkq - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Crazyhouse);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        let params = EvalParams::default();
+        assert_eq!(crazyhouse(game.position(), Side::White, &params), 200);
+        assert_eq!(crazyhouse(game.position(), Side::Black, &params), -200);
+    }
+}
@@ -1,28 +1,147 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 use crate::{
     bitboard::{self, BB, BOARD_LENGTH},
+    move_gen::{check_legal::kings_adjacent, is_sq_attacked},
     mv::castle::Castle,
     phase::Phase,
     piece::Piece,
     piece_type::{PieceType, PIECE_TYPE_COUNT},
-    side::Side,
+    side::{Side, SIDE_MAP},
     square::{self, Square},
-    state::position::Position,
+    state::position::{Pocket, Position, POCKET_PIECE_TYPES},
     state::{
-        castle_rights::{self, CastleRights},
+        castle_rights::{self, CastleFiles, CastleRights, STANDARD_FILES},
         zobrist::Zobrist,
     },
     state::{position::Board, State},
+    variant::{Variant, CHECKS_TO_WIN},
 };
 
 pub const STARTING_POSITION_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
 
-pub fn load_fen(fen: &str) -> Result<(Position, State), String> {
+// in crazyhouse every piece is either on the board or in a pocket
+const MAX_PIECES: u32 = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EnPassantFen {
+    // after every double pawn push, the same as the zobrist key
+    Always,
+    // only when a pawn can legally take en passant
+    Capturable,
+}
+
+// a fen that can't be read, or describes a position the engine can't play from
+#[derive(Clone, Debug, PartialEq)]
+pub enum FenError {
+    Syntax(String),
+    KingCount(Side, u32),
+    PawnOnBackRank(Square),
+    // castle rights without the king and rook on their start squares
+    CastleRights(Side, Castle),
+    EnPassant(Square),
+    // the side that just moved left its king in check
+    OpponentInCheck(Side),
+    // more pieces on the board and in the pockets than a crazyhouse game starts with
+    PieceCount(u32),
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FenError::Syntax(err) => write!(f, "{}", err),
+            FenError::KingCount(side, count) => {
+                write!(f, "{} has {} kings instead of one", side, count)
+            }
+            FenError::PawnOnBackRank(sq) => write!(f, "pawn on the back rank at {}", sq),
+            FenError::CastleRights(side, castle) => write!(
+                f,
+                "{} can't castle {} without the king and rook on their start squares",
+                side, castle
+            ),
+            FenError::EnPassant(sq) => write!(f, "{} is an impossible en passant square", sq),
+            FenError::OpponentInCheck(side) => {
+                write!(f, "{} is in check but it isn't their move", side)
+            }
+            FenError::PieceCount(count) => write!(
+                f,
+                "{} pieces are on the board and in the pockets, more than the {} a game starts with",
+                count, MAX_PIECES
+            ),
+        }
+    }
+}
+
+impl From<FenError> for String {
+    fn from(err: FenError) -> String {
+        err.to_string()
+    }
+}
+
+pub fn load_fen(fen: &str) -> Result<(Position, State), FenError> {
+    load_variant_fen(fen, Variant::Standard)
+}
+
+pub fn load_variant_fen(fen: &str, variant: Variant) -> Result<(Position, State), FenError> {
+    let (position, state) = parse_variant_fen(fen, variant)?;
+    validate(&position, &state)?;
+
+    Ok((position, state))
+}
+
+// reads the fen without checking the position could come up in a game
+pub fn parse_fen(fen: &str) -> Result<(Position, State), FenError> {
+    parse_variant_fen(fen, Variant::Standard)
+}
+
+pub fn parse_variant_fen(fen: &str, variant: Variant) -> Result<(Position, State), FenError> {
+    parse_fen_fields(fen, variant).map_err(FenError::Syntax)
+}
+
+// three-check fens have the checks each side has left to give, like `3+3`, after the en passant
+// square. the lichess form with the checks given after the move counters, like `+0+0`, is read
+// too
+fn parse_fen_checks(fen_checks: &str, checks_are_given: bool) -> Result<[u8; 2], String> {
+    let invalid = || format!("{} is an invalid check count", fen_checks);
+    let counts = fen_checks.strip_prefix('+').unwrap_or(fen_checks);
+    let (white, black) = counts.split_once('+').ok_or_else(invalid)?;
+
+    let mut checks = [0; 2];
+    for (side_checks, count) in checks.iter_mut().zip([white, black]) {
+        let count = count.parse::<u8>().map_err(|_| invalid())?;
+        if count > CHECKS_TO_WIN {
+            return Err(invalid());
+        }
+        *side_checks = if checks_are_given {
+            count
+        } else {
+            CHECKS_TO_WIN - count
+        };
+    }
+
+    Ok(checks)
+}
+
+fn parse_fen_fields(fen: &str, variant: Variant) -> Result<(Position, State), String> {
     let mut fen_state = fen.split(' ');
 
     let fen_board = fen_state.next().ok_or("fen string is empty")?;
-    let (bb_sides, bb_pieces, board) = parse_fen_board(fen_board)?;
+    let (fen_board, fen_pockets) = match fen_board.split_once('[') {
+        Some((fen_board, fen_pockets)) => (
+            fen_board,
+            Some(
+                fen_pockets
+                    .strip_suffix(']')
+                    .ok_or("fen pockets are missing the closing bracket")?,
+            ),
+        ),
+        None => (fen_board, None),
+    };
+    let (bb_sides, bb_pieces, board, promoted) = parse_fen_board(fen_board)?;
+    if variant != Variant::Crazyhouse && (fen_pockets.is_some() || promoted.not_empty()) {
+        return Err("only crazyhouse fens have pockets and promoted pieces".to_string());
+    }
+    let pockets = fen_pockets.map_or(Ok([[0; PIECE_TYPE_COUNT]; 2]), parse_fen_pockets)?;
 
     let fen_side_to_move = fen_state.next().ok_or("fen string is missing fields")?;
     let side_map: HashMap<&str, Side> = HashMap::from([("w", Side::White), ("b", Side::Black)]);
@@ -31,11 +150,21 @@ pub fn load_fen(fen: &str) -> Result<(Position, State), String> {
         .ok_or("side to move is an invalid color")?;
 
     let fen_castle = fen_state.next().ok_or("fen string is missing fields")?;
-    let castle_rights = parse_fen_castle(fen_castle)?;
+    let (castle_rights, castle_files) = match variant {
+        // antichess is played without castling
+        Variant::Antichess => (castle_rights::NONE, STANDARD_FILES),
+        _ => parse_fen_castle(fen_castle, &board)?,
+    };
 
     let fen_en_passant = fen_state.next().ok_or("fen string is missing fields")?;
     let en_passant = parse_fen_en_passant(fen_en_passant)?;
 
+    let mut checks = None;
+    let mut fen_state = fen_state.peekable();
+    if let Some(fen_checks) = fen_state.next_if(|field| field.contains('+')) {
+        checks = Some(parse_fen_checks(fen_checks, false)?);
+    }
+
     let fen_halfmoves = fen_state.next().ok_or("fen string is missing fields")?;
     let halfmoves = match fen_halfmoves.parse::<u16>() {
         Ok(num) => num,
@@ -48,26 +177,114 @@ pub fn load_fen(fen: &str) -> Result<(Position, State), String> {
         Err(_) => return Err("fullmoves is not a number".to_string()),
     };
 
+    if let Some(fen_checks) = fen_state.next_if(|field| field.starts_with('+')) {
+        checks = Some(parse_fen_checks(fen_checks, true)?);
+    }
+
     let phase = Phase::get(
         bb_sides[0] | bb_sides[1],
         bb_pieces[PieceType::Pawn.to_usize()],
         fullmoves,
     );
-    let position = Position::new(bb_sides, bb_pieces, board, phase);
-    Ok((
-        position,
-        State::new(
-            en_passant,
-            *side_to_move,
-            castle_rights,
-            halfmoves,
-            fullmoves,
-            Zobrist::new(&position, castle_rights, en_passant, *side_to_move),
-        ),
-    ))
+    let mut position = Position::new(bb_sides, bb_pieces, board, phase);
+    position.set_pockets(pockets);
+    let zobrist = Zobrist::new(&position, castle_rights, en_passant, *side_to_move);
+    let mut state = State::new(
+        en_passant,
+        *side_to_move,
+        castle_rights,
+        halfmoves,
+        fullmoves,
+        zobrist,
+    );
+    state.set_castle_files(castle_files);
+    state.set_variant(variant);
+    // the counts are kept in any variant so the fen reads back the same, only three-check ends
+    // the game on them
+    for (side, side_checks) in SIDE_MAP.into_iter().zip(checks.unwrap_or([0; 2])) {
+        state.set_checks_given(side, side_checks);
+    }
+    state.set_promoted(promoted);
+    Ok((position, state))
 }
 
-fn parse_fen_board(fen_board: &str) -> Result<([BB; 2], [BB; PIECE_TYPE_COUNT], Board), String> {
+// checks the rules a legal game can't break, on top of the fen being readable
+pub fn validate(position: &Position, state: &State) -> Result<(), FenError> {
+    let royal_king = state.variant().has_royal_king();
+    if royal_king {
+        for side in [Side::White, Side::Black] {
+            let count = position.king_sq_bb(side).count_ones();
+            if count != 1 {
+                return Err(FenError::KingCount(side, count));
+            }
+        }
+    }
+
+    if state.variant() == Variant::Crazyhouse {
+        let pocket_count: u32 = SIDE_MAP
+            .into_iter()
+            .flat_map(|side| position.pocket(side))
+            .map(u32::from)
+            .sum();
+        let count = position.bb_occupied().count_ones() + pocket_count;
+        if count > MAX_PIECES {
+            return Err(FenError::PieceCount(count));
+        }
+    }
+
+    let pawns_on_back_rank = position.bb_pieces()[PieceType::Pawn.to_usize()] & bitboard::END_ROWS;
+    if pawns_on_back_rank.not_empty() {
+        return Err(FenError::PawnOnBackRank(pawns_on_back_rank.bitscan()));
+    }
+
+    for side in [Side::White, Side::Black] {
+        for castle in [Castle::Kingside, Castle::Queenside] {
+            if !state.castle_rights().can(side, castle) {
+                continue;
+            }
+            let (king_sq, _) = state.castle_files().king_squares(side, castle);
+            let (rook_sq, _) = state.castle_files().rook_squares(side, castle);
+            if position.at(king_sq) != Some(Piece::new(side, PieceType::King))
+                || position.at(rook_sq) != Some(Piece::new(side, PieceType::Rook))
+            {
+                return Err(FenError::CastleRights(side, castle));
+            }
+        }
+    }
+
+    if let Some(sq) = state.en_passant() {
+        // the square a pawn of the side not to move just passed over
+        let side = state.side_to_move();
+        let (rank, start_rank, pawn_rank) = match side {
+            Side::White => (5, 6, 4),
+            Side::Black => (2, 1, 3),
+        };
+        let pushed_pawn = Piece::new(side.opposite(), PieceType::Pawn);
+        if sq.rank() != rank
+            || position.at(sq).is_some()
+            || position.at(Square::from(start_rank, sq.file())).is_some()
+            || position.at(Square::from(pawn_rank, sq.file())) != Some(pushed_pawn)
+        {
+            return Err(FenError::EnPassant(sq));
+        }
+    }
+
+    // touching kings can't take each other in atomic
+    let side = state.side_to_move().opposite();
+    if royal_king
+        && !(state.variant() == Variant::Atomic && kings_adjacent(position))
+        && is_sq_attacked(position, position.king_sq(side), side.opposite())
+    {
+        return Err(FenError::OpponentInCheck(side));
+    }
+
+    Ok(())
+}
+
+// a `~` after a piece marks it as promoted in crazyhouse
+fn parse_fen_board(
+    fen_board: &str,
+) -> Result<([BB; 2], [BB; PIECE_TYPE_COUNT], Board, BB), String> {
     let mut bb_pieces = [
         bitboard::EMPTY,
         bitboard::EMPTY,
@@ -79,11 +296,16 @@ fn parse_fen_board(fen_board: &str) -> Result<([BB; 2], [BB; PIECE_TYPE_COUNT],
 
     let mut bb_sides = [bitboard::EMPTY, bitboard::EMPTY];
     let mut board: [Option<Piece>; BOARD_LENGTH] = [None; BOARD_LENGTH];
+    let mut promoted = bitboard::EMPTY;
+    let mut last_sq = None;
 
     let mut rank = 7;
     let mut file = 0;
     for c in fen_board.chars() {
         if c == '/' {
+            if file != 8 {
+                return Err("fen board rank doesn't have 8 files".to_string());
+            }
             file = 0;
             rank -= 1;
             continue;
@@ -91,6 +313,17 @@ fn parse_fen_board(fen_board: &str) -> Result<([BB; 2], [BB; PIECE_TYPE_COUNT],
 
         if c.is_numeric() {
             file += c.to_digit(10).unwrap();
+            last_sq = None;
+            continue;
+        }
+
+        if c == '~' {
+            let sq: Square = last_sq.ok_or("~ has to follow a piece in the fen board")?;
+            let piece_type = board[sq.to_usize()].unwrap().piece_type();
+            if matches!(piece_type, PieceType::Pawn | PieceType::King) {
+                return Err(format!("a {} can't be promoted", piece_type));
+            }
+            promoted |= BB::new(sq);
             continue;
         }
 
@@ -100,7 +333,7 @@ fn parse_fen_board(fen_board: &str) -> Result<([BB; 2], [BB; PIECE_TYPE_COUNT],
             .ok_or("invalid character in fen board")?;
         let piece_type: PieceType = PieceType::try_from(piece_lowercase)?;
 
-        if !(0..=7).contains(&rank) {
+        if !(0..=7).contains(&rank) || file > 7 {
             return Err("fen board contains too many files or ranks".to_string());
         }
 
@@ -117,34 +350,104 @@ fn parse_fen_board(fen_board: &str) -> Result<([BB; 2], [BB; PIECE_TYPE_COUNT],
         bb_sides[side.to_usize()] |= sq_bb;
 
         board[sq.to_usize()] = Some(Piece::new(side, piece_type));
+        last_sq = Some(sq);
 
         file += 1;
     }
 
-    Ok((bb_sides, bb_pieces, board))
+    if rank != 0 || file != 8 {
+        return Err("fen board doesn't have 8 ranks of 8 files".to_string());
+    }
+
+    Ok((bb_sides, bb_pieces, board, promoted))
 }
 
-fn parse_fen_castle(fen_castle: &str) -> Result<CastleRights, String> {
+// the pieces in the pockets from between the brackets, white's uppercase and black's lowercase
+fn parse_fen_pockets(fen_pockets: &str) -> Result<[Pocket; 2], String> {
+    let mut pockets = [[0; PIECE_TYPE_COUNT]; 2];
+    for (i, c) in fen_pockets.chars().enumerate() {
+        // the kings are never in a pocket
+        if i >= MAX_PIECES as usize - 2 {
+            return Err("fen pockets have too many pieces".to_string());
+        }
+        let piece_type = PieceType::try_from(c.to_ascii_lowercase())?;
+        if piece_type == PieceType::King {
+            return Err("a king can't be in a pocket".to_string());
+        }
+        let side = if c.is_ascii_uppercase() {
+            Side::White
+        } else {
+            Side::Black
+        };
+        pockets[side.to_usize()][piece_type.to_usize()] += 1;
+    }
+
+    Ok(pockets)
+}
+
+fn back_rank(side: Side) -> usize {
+    match side {
+        Side::White => 0,
+        Side::Black => 7,
+    }
+}
+
+// reads standard, X-FEN and Shredder-FEN castle rights. K and Q castle with the outermost rook on
+// that side of the king, a file letter names the rook's file. rights without a king or rook where
+// they should be are left for `validate` to reject
+fn parse_fen_castle(
+    fen_castle: &str,
+    board: &Board,
+) -> Result<(CastleRights, CastleFiles), String> {
     if fen_castle == "-" {
-        return Ok(castle_rights::NONE);
+        return Ok((castle_rights::NONE, STANDARD_FILES));
     }
 
     let mut castle_rights = castle_rights::NONE;
+    let mut castle_files = STANDARD_FILES;
     for c in fen_castle.chars() {
-        if c == 'K' {
-            castle_rights = castle_rights.set(Side::White, Castle::Kingside);
-        } else if c == 'Q' {
-            castle_rights = castle_rights.set(Side::White, Castle::Queenside);
-        } else if c == 'k' {
-            castle_rights = castle_rights.set(Side::Black, Castle::Kingside);
-        } else if c == 'q' {
-            castle_rights = castle_rights.set(Side::Black, Castle::Queenside);
+        let side = if c.is_ascii_uppercase() {
+            Side::White
         } else {
-            return Err("invalid character in castle rights".to_string());
-        }
+            Side::Black
+        };
+        let rank = back_rank(side);
+        let is_at = |piece_type: PieceType, file: usize| {
+            board[Square::from(rank, file).to_usize()] == Some(Piece::new(side, piece_type))
+        };
+        let king_file = (0..8)
+            .find(|&file| is_at(PieceType::King, file))
+            .unwrap_or(square::E1.file());
+
+        let (castle, rook_file) = match c.to_ascii_lowercase() {
+            'k' => (
+                Castle::Kingside,
+                (king_file + 1..8)
+                    .rev()
+                    .find(|&file| is_at(PieceType::Rook, file))
+                    .unwrap_or(square::H1.file()),
+            ),
+            'q' => (
+                Castle::Queenside,
+                (0..king_file)
+                    .find(|&file| is_at(PieceType::Rook, file))
+                    .unwrap_or(square::A1.file()),
+            ),
+            file_char @ 'a'..='h' => {
+                let file = file_char as usize - 'a' as usize;
+                if file < king_file {
+                    (Castle::Queenside, file)
+                } else {
+                    (Castle::Kingside, file)
+                }
+            }
+            _ => return Err("invalid character in castle rights".to_string()),
+        };
+        castle_rights = castle_rights.set(side, castle);
+        castle_files = castle_files.set(side, king_file, castle, rook_file);
     }
 
-    Ok(castle_rights)
+    Ok((castle_rights, castle_files))
 }
 
 fn parse_fen_en_passant(fen_en_passant: &str) -> Result<Option<Square>, String> {
@@ -176,3 +479,111 @@ fn parse_fen_en_passant(fen_en_passant: &str) -> Result<Option<Square>, String>
 
     Ok(Some(en_passant_sq))
 }
+
+// the inverse of `load_fen`. the en passant square is given separately since callers choose when
+// it is written
+pub fn write_fen(position: &Position, state: &State, en_passant: Option<Square>) -> String {
+    let mut board = String::new();
+    for rank in (0..8).rev() {
+        let mut empty = 0;
+        for file in 0..8 {
+            let Some(piece) = position.at(Square::from(rank, file)) else {
+                empty += 1;
+                continue;
+            };
+            if empty > 0 {
+                board.push_str(&empty.to_string());
+                empty = 0;
+            }
+            let c = piece.piece_type().to_char();
+            board.push(match piece.side() {
+                Side::White => c.to_ascii_uppercase(),
+                Side::Black => c,
+            });
+            if state.promoted().is_set(Square::from(rank, file)) {
+                board.push('~');
+            }
+        }
+        if empty > 0 {
+            board.push_str(&empty.to_string());
+        }
+        if rank > 0 {
+            board.push('/');
+        }
+    }
+    if state.variant() == Variant::Crazyhouse {
+        board.push('[');
+        for side in SIDE_MAP {
+            for piece_type in POCKET_PIECE_TYPES {
+                let c = piece_type.to_char();
+                let c = match side {
+                    Side::White => c.to_ascii_uppercase(),
+                    Side::Black => c,
+                };
+                for _ in 0..position.pocket_count(side, piece_type) {
+                    board.push(c);
+                }
+            }
+        }
+        board.push(']');
+    }
+
+    let side_to_move = match state.side_to_move() {
+        Side::White => "w",
+        Side::Black => "b",
+    };
+
+    // X-FEN, a file letter is only written when another rook is further out than the castling rook
+    let mut castle = String::new();
+    for (side, castle_type) in [
+        (Side::White, Castle::Kingside),
+        (Side::White, Castle::Queenside),
+        (Side::Black, Castle::Kingside),
+        (Side::Black, Castle::Queenside),
+    ] {
+        if !state.castle_rights().can(side, castle_type) {
+            continue;
+        }
+        let rook_file = state.castle_files().rook_file(side, castle_type);
+        let mut outer_files = match castle_type {
+            Castle::Kingside => rook_file + 1..8,
+            Castle::Queenside => 0..rook_file,
+        };
+        let rook = Some(Piece::new(side, PieceType::Rook));
+        let is_outermost =
+            !outer_files.any(|file| position.at(Square::from(back_rank(side), file)) == rook);
+        let c = match (is_outermost, castle_type) {
+            (true, Castle::Kingside) => 'k',
+            (true, Castle::Queenside) => 'q',
+            (false, _) => (b'a' + rook_file as u8) as char,
+        };
+        castle.push(match side {
+            Side::White => c.to_ascii_uppercase(),
+            Side::Black => c,
+        });
+    }
+    if castle.is_empty() {
+        castle.push('-');
+    }
+
+    let mut en_passant = en_passant.map_or("-".to_string(), |sq| sq.to_string());
+    if state.variant() == Variant::ThreeCheck {
+        let checks_left = |side| CHECKS_TO_WIN.saturating_sub(state.checks_given(side));
+        en_passant = format!(
+            "{} {}+{}",
+            en_passant,
+            checks_left(Side::White),
+            checks_left(Side::Black)
+        );
+    }
+
+    format!(
+        "{} {} {} {} {} {}",
+        board,
+        side_to_move,
+        castle,
+        en_passant,
+        state.halfmoves(),
+        state.fullmoves()
+    )
+}
@@ -1,42 +1,174 @@
 use core::fmt;
 
 use crate::{
-    bitboard::squares_between::bb_squares_between,
-    fen,
+    bitboard::{BB, KING_MOVES},
+    fen::{self, EnPassantFen, FenError},
     move_gen::{
         check_legal::{
             is_legal_castle, is_legal_en_passant_move, is_legal_king_move, is_legal_regular_move,
             LegalCheckPreprocessing,
         },
-        escape_check,
+        escape_check, is_sq_attacked,
+        legal::{self, MoveGen},
+        magic::bb_squares_between,
+        pseudo_legal,
     },
     move_list::MoveList,
-    mv::{castle::Castle, Decode, EncodedMove, Move, PromotionMove},
+    mv::{castle::Castle, Decode, DropMove, EncodedMove, Move, PromotionMove},
     piece::Piece,
     piece_type::{PieceType, PIECE_TYPE_MAP},
+    san,
     side::Side,
     square::Square,
     state::position::Position,
     state::{EncodedState, State},
+    variant::Variant,
 };
 
+// fide ends the game on its own after fivefold repetition or 75 moves without a capture or pawn
+// move, while threefold repetition and 50 moves are draws either player can claim
+const FIVEFOLD_REPETITION: u8 = 5;
+const THREEFOLD_REPETITION: u8 = 3;
+const SEVENTY_FIVE_MOVE_HALFMOVES: u16 = 150;
+const FIFTY_MOVE_HALFMOVES: u16 = 100;
+
+// whether the game is over and why
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameStatus {
+    InProgress,
+    // the winner
+    Checkmate(Side),
+    Stalemate,
+    // the winner reached the goal of the variant, like a king on the hill, the third check or
+    // running out of pieces in antichess
+    VariantWin(Side),
+    ThreefoldRepetition,
+    FivefoldRepetition,
+    FiftyMoveRule,
+    SeventyFiveMoveRule,
+    InsufficientMaterial,
+}
+
+impl GameStatus {
+    pub fn is_over(self) -> bool {
+        self != GameStatus::InProgress
+    }
+
+    pub fn winner(self) -> Option<Side> {
+        match self {
+            GameStatus::Checkmate(side) | GameStatus::VariantWin(side) => Some(side),
+            _ => None,
+        }
+    }
+
+    // a name for why the game ended that doesn't change with the wording of the display
+    pub fn reason(self) -> &'static str {
+        match self {
+            GameStatus::InProgress => "in_progress",
+            GameStatus::Checkmate(_) => "checkmate",
+            GameStatus::Stalemate => "stalemate",
+            GameStatus::VariantWin(_) => "variant_win",
+            GameStatus::ThreefoldRepetition => "threefold_repetition",
+            GameStatus::FivefoldRepetition => "fivefold_repetition",
+            GameStatus::FiftyMoveRule => "fifty_move_rule",
+            GameStatus::SeventyFiveMoveRule => "seventy_five_move_rule",
+            GameStatus::InsufficientMaterial => "insufficient_material",
+        }
+    }
+
+    // the result as it is written in a pgn
+    pub fn result(self) -> &'static str {
+        match (self.is_over(), self.winner()) {
+            (false, _) => "*",
+            (true, Some(Side::White)) => "1-0",
+            (true, Some(Side::Black)) => "0-1",
+            (true, None) => "1/2-1/2",
+        }
+    }
+}
+
+impl fmt::Display for GameStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameStatus::InProgress => write!(f, "in progress"),
+            GameStatus::Checkmate(side) => write!(f, "{} wins by checkmate", side),
+            GameStatus::Stalemate => write!(f, "draw by stalemate"),
+            GameStatus::VariantWin(side) => write!(f, "{} wins by the rules of the variant", side),
+            GameStatus::ThreefoldRepetition => write!(f, "draw by threefold repetition"),
+            GameStatus::FivefoldRepetition => write!(f, "draw by fivefold repetition"),
+            GameStatus::FiftyMoveRule => write!(f, "draw by the fifty move rule"),
+            GameStatus::SeventyFiveMoveRule => write!(f, "draw by the seventy-five move rule"),
+            GameStatus::InsufficientMaterial => write!(f, "draw by insufficient material"),
+        }
+    }
+}
+
+// the pieces an atomic capture blew up, the capturing piece included, so unmaking it can put them
+// back. the capture square and the eight around it hold at most nine pieces
+type Explosion = [Option<(Square, Piece)>; 9];
+
 #[derive(Clone)]
 pub struct Game {
     position: Position,
     state: State,
+    // one for each atomic capture on the board, the last one is taken off when it is unmade
+    explosions: Vec<Explosion>,
 }
 
 impl Game {
-    pub fn from_fen(fen: &str) -> Result<Game, String> {
+    // the position the game starts from counts towards repetitions like the ones after each move,
+    // which make_move adds. a fen can't say how often it was on the board before, so once
+    fn new(position: Position, mut state: State) -> Game {
+        state.push_to_zobrist_table(state.zobrist().to_u64());
+
+        Game {
+            position,
+            state,
+            explosions: vec![],
+        }
+    }
+
+    pub fn from_fen(fen: &str) -> Result<Game, FenError> {
         let (position, state) = fen::load_fen(fen)?;
 
-        Ok(Game { position, state })
+        Ok(Game::new(position, state))
+    }
+
+    pub fn from_variant_fen(fen: &str, variant: Variant) -> Result<Game, FenError> {
+        let (position, state) = fen::load_variant_fen(fen, variant)?;
+
+        Ok(Game::new(position, state))
+    }
+
+    // for positions that break the rules checked by `fen::validate`, eg. missing kings
+    pub fn from_fen_unchecked(fen: &str) -> Result<Game, FenError> {
+        let (position, state) = fen::parse_fen(fen)?;
+
+        Ok(Game::new(position, state))
     }
 
     pub fn state(&self) -> &State {
         &self.state
     }
 
+    pub fn to_fen(&self) -> String {
+        self.to_fen_with(EnPassantFen::Always)
+    }
+
+    pub fn to_fen_with(&self, en_passant: EnPassantFen) -> String {
+        let en_passant_sq = match en_passant {
+            EnPassantFen::Always => self.state.en_passant(),
+            EnPassantFen::Capturable => self.state.en_passant().filter(|_| {
+                self.clone()
+                    .legal_moves()
+                    .iter()
+                    .any(|mv| matches!(mv, Move::EnPassant(_)))
+            }),
+        };
+
+        fen::write_fen(&self.position, &self.state, en_passant_sq)
+    }
+
     pub fn mut_position(&mut self) -> &mut Position {
         &mut self.position
     }
@@ -80,6 +212,8 @@ impl Game {
         if castle_rights.can(side, Castle::Kingside) {
             mv_list.push_move(Move::Castle(Castle::Kingside))
         }
+        let empty = !self.position.bb_occupied();
+        pseudo_legal::push_drops(&self.position, side, empty, &mut mv_list);
 
         mv_list
     }
@@ -138,6 +272,11 @@ impl Game {
                     );
                 }
             }
+
+            // a drop can block the check but never take the checker
+            let checker_sq = legal_check_preprocessing.checkers().bitscan();
+            let blocking_sqs = bb_squares_between(self.position.king_sq(side), checker_sq);
+            pseudo_legal::push_drops(&self.position, side, blocking_sqs, &mut mv_list);
         }
 
         mv_list
@@ -279,9 +418,20 @@ impl Game {
                 &self.position,
                 castle,
                 self.state.side_to_move(),
+                self.state.castle_files(),
                 legal_check_preprocessing.controlled_squares_with_king_gone_bb(),
                 legal_check_preprocessing.checkers(),
             ),
+            // adding a piece never leaves the king in check, but in check it has to block
+            Move::Drop(drop_mv) => match legal_check_preprocessing.num_of_checkers() {
+                0 => true,
+                1 => {
+                    let king_sq = self.position.king_sq(self.state.side_to_move());
+                    let checker_sq = legal_check_preprocessing.checkers().bitscan();
+                    bb_squares_between(king_sq, checker_sq).is_set(drop_mv.to())
+                }
+                _ => false,
+            },
         }
     }
 
@@ -311,9 +461,20 @@ impl Game {
     }
 
     fn make_castle_move(&mut self, castle: Castle, side: Side) {
-        let (king_from, king_to) = castle.king_squares(side);
-        let (rook_from, rook_to) = castle.rook_squares(side);
+        let castle_files = self.state.castle_files();
+        let (king_from, king_to) = castle_files.king_squares(side, castle);
+        let (rook_from, rook_to) = castle_files.rook_squares(side, castle);
+        self.move_castle_pieces((king_from, king_to), (rook_from, rook_to), side);
+    }
 
+    fn move_castle_pieces(
+        &mut self,
+        (king_from, king_to): (Square, Square),
+        (rook_from, rook_to): (Square, Square),
+        side: Side,
+    ) {
+        // in chess960 the king and rook can land on each other's start squares,
+        // so both are taken off the board before either is placed
         self.state
             .mut_zobrist()
             .hash_piece(side, PieceType::King, king_from);
@@ -328,10 +489,10 @@ impl Game {
             .mut_zobrist()
             .hash_piece(side, PieceType::Rook, rook_to);
 
-        self.position
-            .move_piece(PieceType::King, king_from, king_to, side);
-        self.position
-            .move_piece(PieceType::Rook, rook_from, rook_to, side);
+        self.position.remove_piece(PieceType::King, king_from, side);
+        self.position.remove_piece(PieceType::Rook, rook_from, side);
+        self.position.place_piece(PieceType::King, king_to, side);
+        self.position.place_piece(PieceType::Rook, rook_to, side);
     }
 
     fn make_promotion_move(&mut self, mv: PromotionMove, side: Side) -> Option<Piece> {
@@ -359,6 +520,124 @@ impl Game {
         capture
     }
 
+    fn make_drop_move(&mut self, mv: DropMove, side: Side) {
+        let piece_type = mv.piece_type();
+        let count = self.position.pocket_count(side, piece_type);
+        self.set_pocket_count(side, piece_type, count - 1);
+
+        self.position.place_piece(piece_type, mv.to(), side);
+        self.state
+            .mut_zobrist()
+            .hash_piece(side, piece_type, mv.to());
+    }
+
+    fn unmake_drop_move(&mut self, mv: DropMove, side: Side) {
+        let piece_type = mv.piece_type();
+        self.position.remove_piece(piece_type, mv.to(), side);
+        self.state
+            .mut_zobrist()
+            .hash_piece(side, piece_type, mv.to());
+
+        let count = self.position.pocket_count(side, piece_type);
+        self.set_pocket_count(side, piece_type, count + 1);
+    }
+
+    fn set_pocket_count(&mut self, side: Side, piece_type: PieceType, count: u8) {
+        let zobrist = self.state.mut_zobrist();
+        zobrist.hash_pocket(
+            side,
+            piece_type,
+            self.position.pocket_count(side, piece_type),
+        );
+        zobrist.hash_pocket(side, piece_type, count);
+        self.position.set_pocket_count(side, piece_type, count);
+    }
+
+    // after the pieces have moved in crazyhouse, the promoted pieces follow their moves and the
+    // capture goes to the pocket of the side that took it, as a pawn if it had been promoted
+    fn make_crazyhouse_move(&mut self, mv: Move, capture: Option<Piece>, side: Side) {
+        let promoted = self.state.promoted();
+        let mut new_promoted = promoted;
+        if let Some((from, to)) = moved_squares(mv) {
+            new_promoted &= !(BB::new(from) | BB::new(to));
+            if promoted.is_set(from) || matches!(mv, Move::Promotion(_)) {
+                new_promoted |= BB::new(to);
+            }
+
+            if let Some(capture) = capture {
+                let piece_type = if promoted.is_set(to) {
+                    PieceType::Pawn
+                } else {
+                    capture.piece_type()
+                };
+                let count = self.position.pocket_count(side, piece_type);
+                self.set_pocket_count(side, piece_type, count + 1);
+            }
+        }
+
+        self.state.set_promoted(new_promoted);
+    }
+
+    fn unmake_crazyhouse_move(&mut self, mv: Move, capture: Option<Piece>, side: Side) {
+        // decoding the previous state already put back the promoted pieces from before the move
+        let promoted = self.state.promoted();
+        if let (Some((_, to)), Some(capture)) = (moved_squares(mv), capture) {
+            let piece_type = if promoted.is_set(to) {
+                PieceType::Pawn
+            } else {
+                capture.piece_type()
+            };
+            let count = self.position.pocket_count(side, piece_type);
+            self.set_pocket_count(side, piece_type, count - 1);
+        }
+    }
+
+    // an atomic capture blows up the capturing piece on the capture square along with every piece
+    // but pawns next to it, and blown up kings and rooks take their castle rights with them
+    fn make_atomic_explosion(&mut self, mv: Move) {
+        let (_, to) = moved_squares(mv).expect("only moves of a single piece capture");
+        let pawns = self.position.bb_pieces()[PieceType::Pawn.to_usize()];
+        let blast =
+            BB::new(to) | (KING_MOVES[to.to_usize()] & self.position.bb_occupied() & !pawns);
+
+        let mut explosion: Explosion = [None; 9];
+        for (i, sq) in blast.iter().enumerate() {
+            let piece = self
+                .position
+                .remove_at(sq)
+                .expect("blew up an empty square");
+            let (side, piece_type) = piece.decode();
+            self.state.mut_zobrist().hash_piece(side, piece_type, sq);
+            explosion[i] = Some((sq, piece));
+
+            match piece_type {
+                PieceType::King => self.state.remove_castle_rights_for_color(side),
+                PieceType::Rook => {
+                    let (rook_queenside_sq, rook_kingside_sq) = self.rook_start_squares(side);
+                    if sq == rook_queenside_sq {
+                        self.state.remove_castle_rights(side, Castle::Queenside);
+                    } else if sq == rook_kingside_sq {
+                        self.state.remove_castle_rights(side, Castle::Kingside);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.explosions.push(explosion);
+    }
+
+    fn unmake_atomic_explosion(&mut self) {
+        let explosion = self
+            .explosions
+            .pop()
+            .expect("unmade an atomic capture that wasn't made");
+        for (sq, piece) in explosion.into_iter().flatten() {
+            let (side, piece_type) = piece.decode();
+            self.position.place_piece(piece_type, sq, side);
+            self.state.mut_zobrist().hash_piece(side, piece_type, sq);
+        }
+    }
+
     pub fn adjust_castle_rights_on_capture(&mut self, mv: impl Decode, capture: Option<Piece>) {
         let pc = capture.expect("adjusting castle rights on capture but no piece was given");
         if pc.piece_type() == PieceType::Rook {
@@ -367,7 +646,7 @@ impl Game {
     }
 
     pub fn adjust_castle_rights_on_capturing_rook(&mut self, mv: impl Decode, side: Side) {
-        let (rook_queenside_sq, rook_kingside_sq) = side.rook_start_squares();
+        let (rook_queenside_sq, rook_kingside_sq) = self.rook_start_squares(side);
         let (_, to) = mv.decode_into_squares();
 
         if to == rook_kingside_sq {
@@ -378,7 +657,7 @@ impl Game {
     }
 
     pub fn adjust_castle_rights_on_rook_move(&mut self, mv: impl Decode, side: Side) {
-        let (rook_queenside_sq, rook_kingside_sq) = side.rook_start_squares();
+        let (rook_queenside_sq, rook_kingside_sq) = self.rook_start_squares(side);
         let (from, _) = mv.decode_into_squares();
         if from == rook_kingside_sq {
             self.state.remove_castle_rights(side, Castle::Kingside);
@@ -387,6 +666,14 @@ impl Game {
         }
     }
 
+    fn rook_start_squares(&self, side: Side) -> (Square, Square) {
+        let castle_files = self.state.castle_files();
+        (
+            castle_files.rook_squares(side, Castle::Queenside).0,
+            castle_files.rook_squares(side, Castle::Kingside).0,
+        )
+    }
+
     pub fn adjust_castle_rights_on_king_move(&mut self, side: Side) {
         self.state.remove_castle_rights_for_color(side);
     }
@@ -493,7 +780,22 @@ impl Game {
 
                 capture
             }
+            Move::Drop(drop_mv) => {
+                self.make_drop_move(drop_mv, side);
+                if drop_mv.piece_type() == PieceType::Pawn {
+                    self.state.reset_halfmoves();
+                } else {
+                    self.state.increase_halfmoves();
+                }
+
+                None
+            }
         };
+        match self.state.variant() {
+            Variant::Crazyhouse => self.make_crazyhouse_move(mv, capture, side),
+            Variant::Atomic if capture.is_some() => self.make_atomic_explosion(mv),
+            _ => {}
+        }
 
         if !matches!(mv, Move::DoublePawnPush(_)) {
             self.state.remove_en_passant();
@@ -502,6 +804,11 @@ impl Game {
         if self.state.side_to_move() == Side::Black {
             self.state.increase_fullmoves();
         };
+        if self.state.variant() == Variant::ThreeCheck
+            && is_sq_attacked(&self.position, self.position.king_sq(side.opposite()), side)
+        {
+            self.state.add_check(side);
+        }
         self.state.update_side_to_move();
         self.state
             .push_to_zobrist_table(self.state.zobrist().to_u64());
@@ -531,26 +838,10 @@ impl Game {
     }
 
     fn unmake_castle_move(&mut self, castle: Castle, side: Side) {
-        let (king_from, king_to) = castle.king_squares(side);
-        let (rook_from, rook_to) = castle.rook_squares(side);
-        self.state
-            .mut_zobrist()
-            .hash_piece(side, PieceType::King, king_from);
-        self.state
-            .mut_zobrist()
-            .hash_piece(side, PieceType::King, king_to);
-
-        self.state
-            .mut_zobrist()
-            .hash_piece(side, PieceType::Rook, rook_from);
-        self.state
-            .mut_zobrist()
-            .hash_piece(side, PieceType::Rook, rook_to);
-
-        self.position
-            .move_piece(PieceType::King, king_to, king_from, side);
-        self.position
-            .move_piece(PieceType::Rook, rook_to, rook_from, side);
+        let castle_files = self.state.castle_files();
+        let (king_from, king_to) = castle_files.king_squares(side, castle);
+        let (rook_from, rook_to) = castle_files.rook_squares(side, castle);
+        self.move_castle_pieces((king_to, king_from), (rook_to, rook_from), side);
     }
 
     fn unmake_promotion_move(&mut self, mv: PromotionMove, capture: Option<Piece>, side: Side) {
@@ -594,6 +885,11 @@ impl Game {
         self.state.decode_from(prev_state);
         let side = self.state.side_to_move();
 
+        // the capturing piece has to be back on the capture square before it can be unmade
+        if self.state.variant() == Variant::Atomic && capture.is_some() {
+            self.unmake_atomic_explosion();
+        }
+
         match mv {
             Move::King(mv)
             | Move::Piece(mv)
@@ -611,14 +907,106 @@ impl Game {
             Move::EnPassant(en_passant_mv) => {
                 self.unmake_en_passant_move(en_passant_mv, side);
             }
+            Move::Drop(drop_mv) => {
+                self.unmake_drop_move(drop_mv, side);
+            }
         };
+        if self.state.variant() == Variant::Crazyhouse {
+            self.unmake_crazyhouse_move(mv, capture, side);
+        }
+    }
+
+    // generates only legal moves, so there is no need to filter them with is_legal
+    pub fn legal_move_list(
+        &self,
+        move_gen: MoveGen,
+        legal_check_preprocessing: &LegalCheckPreprocessing,
+    ) -> MoveList {
+        legal::legal_moves(self, move_gen, legal_check_preprocessing)
+    }
+
+    pub fn legal_moves(&mut self) -> Vec<Move> {
+        self.legal_moves_of(MoveGen::All)
+    }
+
+    // captures and promotions
+    pub fn legal_captures(&mut self) -> Vec<Move> {
+        self.legal_moves_of(MoveGen::Captures)
     }
 
+    // every move that isn't a capture or a promotion, castles included
+    pub fn legal_quiets(&mut self) -> Vec<Move> {
+        self.legal_moves_of(MoveGen::Quiets)
+    }
+
+    fn legal_moves_of(&mut self, move_gen: MoveGen) -> Vec<Move> {
+        let side = self.state.side_to_move();
+        let legal_check_preprocessing = LegalCheckPreprocessing::from(self, side);
+        self.legal_move_list(move_gen, &legal_check_preprocessing)
+            .into_list()
+    }
+
+    // standard algebraic notation for a legal move, like `Nbd7`, `exd5`, `e8=Q+` or `O-O`
+    pub fn move_to_san(&mut self, mv: Move) -> String {
+        san::move_to_san(self, mv)
+    }
+
+    pub fn parse_san(&mut self, san: &str) -> Result<Move, String> {
+        san::parse_san(self, san)
+    }
+
+    // a king alone can still walk to the hill or be given checks, and losing material is the
+    // goal of antichess, so insufficient material is only a draw in standard chess
     pub fn is_draw(&self) -> bool {
         let last_zobrist = self.state.zobrist();
         self.state.is_draw_by_repetition(*last_zobrist)
             || self.state.is_draw_by_halfmoves()
-            || self.position.insufficient_material()
+            || (self.state.variant() == Variant::Standard && self.position.insufficient_material())
+    }
+
+    // whether the game is over and why. the draws either player could claim are reported as well
+    // as the ones the rules enforce, but a mate on the last move stands over both
+    pub fn status(&mut self) -> GameStatus {
+        let side = self.state.side_to_move();
+        if self.is_variant_loss() {
+            return GameStatus::VariantWin(side.opposite());
+        }
+
+        let legal_check_preprocessing = LegalCheckPreprocessing::from(self, side);
+        if self
+            .legal_move_list(MoveGen::All, &legal_check_preprocessing)
+            .list()
+            .is_empty()
+        {
+            return match (self.state.variant(), legal_check_preprocessing.in_check()) {
+                (Variant::Antichess, _) => GameStatus::VariantWin(side),
+                (_, true) => GameStatus::Checkmate(side.opposite()),
+                (_, false) => GameStatus::Stalemate,
+            };
+        }
+
+        let repetitions = self.state.repetitions(*self.state.zobrist());
+        let halfmoves = self.state.halfmoves();
+        if repetitions >= FIVEFOLD_REPETITION {
+            GameStatus::FivefoldRepetition
+        } else if halfmoves >= SEVENTY_FIVE_MOVE_HALFMOVES {
+            GameStatus::SeventyFiveMoveRule
+        } else if self.state.variant() == Variant::Standard && self.position.insufficient_material()
+        {
+            GameStatus::InsufficientMaterial
+        } else if repetitions >= THREEFOLD_REPETITION {
+            GameStatus::ThreefoldRepetition
+        } else if halfmoves >= FIFTY_MOVE_HALFMOVES {
+            GameStatus::FiftyMoveRule
+        } else {
+            GameStatus::InProgress
+        }
+    }
+
+    // the side to move has lost to the goal of the variant, like the opponent's king reaching
+    // the hill or giving a third check
+    pub fn is_variant_loss(&self) -> bool {
+        self.state.variant().is_lost(self)
     }
 
     pub fn is_checkmate(&mut self, legal_check_preprocessing: &LegalCheckPreprocessing) -> bool {
@@ -626,15 +1014,9 @@ impl Game {
             return false;
         }
 
-        let side = self.state.side_to_move();
-
-        let escape_moves = self.pseudo_legal_escape_moves(side, legal_check_preprocessing);
-        let escape_moves_iter = escape_moves
+        self.legal_move_list(MoveGen::All, legal_check_preprocessing)
             .list()
-            .iter()
-            .filter(|mv| self.is_legal(**mv, legal_check_preprocessing));
-
-        escape_moves_iter.count() == 0
+            .is_empty()
     }
 
     pub fn is_stalemate(&mut self, legal_check_preprocessing: &LegalCheckPreprocessing) -> bool {
@@ -642,8 +1024,29 @@ impl Game {
             return false;
         }
 
+        // a pinned piece can still blow up the opponent's king in atomic, so the moves are
+        // generated in full
+        if self.state.variant() == Variant::Atomic {
+            return self
+                .legal_move_list(MoveGen::All, legal_check_preprocessing)
+                .list()
+                .is_empty();
+        }
+
         let side = self.state.side_to_move();
 
+        // there is no check so a drop on any empty square is legal
+        let mut drops = MoveList::new();
+        pseudo_legal::push_drops(
+            &self.position,
+            side,
+            !self.position.bb_occupied(),
+            &mut drops,
+        );
+        if !drops.list().is_empty() {
+            return false;
+        }
+
         let friendly_occupied = self.position().bb_side(side);
         let enemy_occupied = self.position().bb_side(side.opposite());
 
@@ -674,6 +1077,20 @@ impl Game {
     }
 }
 
+// the from and to squares of the moves that move a single piece
+fn moved_squares(mv: Move) -> Option<(Square, Square)> {
+    match mv {
+        Move::King(mv)
+        | Move::Rook(mv)
+        | Move::Pawn(mv)
+        | Move::DoublePawnPush(mv)
+        | Move::Piece(mv)
+        | Move::EnPassant(mv) => Some(mv.decode_into_squares()),
+        Move::Promotion(mv) => Some(mv.decode_into_squares()),
+        Move::Castle(_) | Move::Drop(_) => None,
+    }
+}
+
 impl fmt::Display for Game {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let position_str = self.position.to_string();
@@ -725,6 +1142,76 @@ pub mod test_fen {
         let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq f1 0 a";
         assert_eq!(true, Game::from_fen(fen).is_err());
     }
+    #[test]
+    fn invalid_board_size() {
+        let fen = "rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert!(matches!(Game::from_fen(fen), Err(FenError::Syntax(_))));
+        let fen = "rnbqkbnr/pppppppp/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert!(matches!(Game::from_fen(fen), Err(FenError::Syntax(_))));
+    }
+    #[test]
+    fn king_count() {
+        let fen = "4k3/8/8/8/8/8/8/8 w - - 0 1";
+        assert_eq!(
+            Game::from_fen(fen).err(),
+            Some(FenError::KingCount(Side::White, 0))
+        );
+        let fen = "4k3/8/8/8/8/8/8/3kK3 w - - 0 1";
+        assert_eq!(
+            Game::from_fen(fen).err(),
+            Some(FenError::KingCount(Side::Black, 2))
+        );
+    }
+    #[test]
+    fn pawn_on_back_rank() {
+        let fen = "4k2P/8/8/8/8/8/8/4K3 b - - 0 1";
+        assert_eq!(
+            Game::from_fen(fen).err(),
+            Some(FenError::PawnOnBackRank(H8))
+        );
+    }
+    #[test]
+    fn castle_rights_without_rook() {
+        let fen = "r3k3/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+        assert_eq!(
+            Game::from_fen(fen).err(),
+            Some(FenError::CastleRights(Side::Black, Castle::Kingside))
+        );
+        // no rook between the king and the corner
+        let fen = "r3k2r/8/8/8/8/8/8/R6K w KQkq - 0 1";
+        assert_eq!(
+            Game::from_fen(fen).err(),
+            Some(FenError::CastleRights(Side::White, Castle::Kingside))
+        );
+        // a shredder file with no rook on it
+        let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkqG - 0 1";
+        assert_eq!(
+            Game::from_fen(fen).err(),
+            Some(FenError::CastleRights(Side::White, Castle::Kingside))
+        );
+    }
+    #[test]
+    fn impossible_en_passant() {
+        // no pawn that just moved
+        let fen = "4k3/8/8/8/8/8/8/4K3 w - e6 0 1";
+        assert_eq!(Game::from_fen(fen).err(), Some(FenError::EnPassant(E6)));
+        // the wrong side moved
+        let fen = "4k3/8/8/8/4P3/8/8/4K3 w - e3 0 1";
+        assert_eq!(Game::from_fen(fen).err(), Some(FenError::EnPassant(E3)));
+        let fen = "4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1";
+        assert!(Game::from_fen(fen).is_ok());
+    }
+    #[test]
+    fn opponent_in_check() {
+        let fen = "4k3/8/8/8/8/8/8/4KR2 w - - 0 1";
+        assert!(Game::from_fen(fen).is_ok());
+        let fen = "4k3/8/8/8/8/8/8/4R1K1 w - - 0 1";
+        assert_eq!(
+            Game::from_fen(fen).err(),
+            Some(FenError::OpponentInCheck(Side::Black))
+        );
+        assert!(Game::from_fen_unchecked(fen).is_ok());
+    }
 
     #[test]
     fn phase() {
@@ -876,7 +1363,7 @@ pub mod test_fen {
     #[test]
     fn parse_with_ep_square_2() {
         let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq c3 0 0";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         match result {
             Ok(game) => {
                 assert_eq!(game.state().en_passant(), Some(C3));
@@ -891,7 +1378,7 @@ pub mod test_fen {
     #[test]
     fn parse_with_half_move_clock_1() {
         let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq c3 0 0";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         match result {
             Ok(game) => {
                 assert_eq!(game.state().halfmoves(), 0);
@@ -906,7 +1393,7 @@ pub mod test_fen {
     #[test]
     fn parse_with_half_move_clock_2() {
         let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq c3 23 0";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         match result {
             Ok(game) => {
                 assert_eq!(game.state().halfmoves(), 23);
@@ -921,7 +1408,7 @@ pub mod test_fen {
     #[test]
     fn parse_with_full_move_number_1() {
         let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq c3 0 0";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         match result {
             Ok(game) => {
                 assert_eq!(game.state().fullmoves(), 0);
@@ -936,7 +1423,7 @@ pub mod test_fen {
     #[test]
     fn parse_with_full_move_number_2() {
         let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq c3 0 45";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         match result {
             Ok(game) => {
                 assert_eq!(game.state().fullmoves(), 45);
@@ -951,7 +1438,7 @@ pub mod test_fen {
     #[test]
     fn parse_with_castling_rights_1() {
         let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b - c3 0 45";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         match result {
             Ok(game) => {
                 assert_eq!(game.state().castle_rights(), castle_rights::NONE);
@@ -966,7 +1453,7 @@ pub mod test_fen {
     #[test]
     fn parse_with_castling_rights_2() {
         let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b Kq c3 0 45";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         match result {
             Ok(game) => {
                 let mut expected = castle_rights::NONE.set(Side::White, Castle::Kingside);
@@ -983,7 +1470,7 @@ pub mod test_fen {
     #[test]
     fn parse_with_castling_rights_3() {
         let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq c3 0 45";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         match result {
             Ok(game) => {
                 assert_eq!(
@@ -997,6 +1484,188 @@ pub mod test_fen {
             }
         }
     }
+
+    #[test]
+    fn parse_shredder_castling() {
+        let fen = "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(game.state().castle_rights(), castle_rights::ALL);
+
+        let castle_files = game.state().castle_files();
+        for side in [Side::White, Side::Black] {
+            assert_eq!(castle_files.rook_file(side, Castle::Kingside), H1.file());
+            assert_eq!(castle_files.rook_file(side, Castle::Queenside), E1.file());
+        }
+        assert_eq!(
+            castle_files.king_squares(Side::White, Castle::Kingside),
+            (G1, G1)
+        );
+        assert_eq!(
+            castle_files.rook_squares(Side::Black, Castle::Queenside),
+            (E8, D8)
+        );
+    }
+
+    #[test]
+    fn parse_x_fen_castling() {
+        // K and Q pick the outermost rook, a file letter picks an inner one
+        let fen = "1k2r1r1/8/8/8/8/8/8/1K2R1R1 w Ke - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        let castle_files = game.state().castle_files();
+        assert_eq!(
+            castle_files.rook_squares(Side::White, Castle::Kingside),
+            (G1, F1)
+        );
+        assert_eq!(
+            castle_files.rook_squares(Side::Black, Castle::Kingside),
+            (E8, F8)
+        );
+        assert_eq!(
+            castle_files.king_squares(Side::Black, Castle::Kingside),
+            (B8, G8)
+        );
+        assert!(!castle_files.is_standard());
+    }
+
+    #[test]
+    fn standard_castling_files() {
+        let result = Game::from_fen(fen::STARTING_POSITION_FEN);
+        assert!(result.is_ok());
+        assert!(result.unwrap().state().castle_files().is_standard());
+    }
+}
+
+#[cfg(test)]
+pub mod test_to_fen {
+    use super::*;
+    use crate::{fen::STARTING_POSITION_FEN, square::ALL_SQUARES};
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    fn assert_round_trip(game: &Game) {
+        let fen = game.to_fen();
+        let result = Game::from_fen(&fen);
+        assert!(result.is_ok(), "{} is invalid fen", fen);
+        let loaded = result.unwrap();
+
+        for sq in ALL_SQUARES {
+            assert_eq!(loaded.position().at(sq), game.position().at(sq), "{}", fen);
+        }
+        assert_eq!(loaded.state().side_to_move(), game.state().side_to_move());
+        assert_eq!(loaded.state().castle_rights(), game.state().castle_rights());
+        for side in [Side::White, Side::Black] {
+            for castle in [Castle::Kingside, Castle::Queenside] {
+                if game.state().castle_rights().can(side, castle) {
+                    assert_eq!(
+                        loaded.state().castle_files().rook_squares(side, castle),
+                        game.state().castle_files().rook_squares(side, castle),
+                        "{}",
+                        fen
+                    );
+                }
+            }
+        }
+        assert_eq!(loaded.state().en_passant(), game.state().en_passant());
+        assert_eq!(loaded.state().halfmoves(), game.state().halfmoves());
+        assert_eq!(loaded.state().fullmoves(), game.state().fullmoves());
+        assert_eq!(loaded.state().zobrist(), game.state().zobrist(), "{}", fen);
+        assert_eq!(loaded.to_fen(), fen);
+    }
+
+    #[test]
+    fn fen_fields() {
+        let fens = [
+            STARTING_POSITION_FEN,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "4k3/8/8/8/8/8/8/4K2R b K - 17 52",
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9",
+            "1k2r1r1/8/8/8/8/8/8/1K2R1R1 w Ee - 0 1",
+        ];
+        for fen in fens {
+            let result = Game::from_fen(fen);
+            assert!(result.is_ok());
+            assert_eq!(result.unwrap().to_fen(), fen);
+        }
+    }
+
+    #[test]
+    fn shredder_fen_writes_x_fen() {
+        let fen = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().to_fen(),
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9"
+        );
+    }
+
+    #[test]
+    fn en_passant_choice() {
+        let result = Game::from_fen(STARTING_POSITION_FEN);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let result = game.parse_san("e4");
+        assert!(result.is_ok());
+        game.make_move(result.unwrap());
+
+        assert_eq!(
+            game.to_fen(),
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+        );
+        assert_eq!(
+            game.to_fen_with(EnPassantFen::Capturable),
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+        );
+
+        let fen = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().to_fen_with(EnPassantFen::Capturable), fen);
+
+        // taking en passant would leave the king in check along the rank
+        let result = Game::from_fen("8/8/8/KPp4r/8/8/8/4k3 w - c6 0 2");
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().to_fen_with(EnPassantFen::Capturable),
+            "8/8/8/KPp4r/8/8/8/4k3 w - - 0 2"
+        );
+    }
+
+    #[test]
+    fn round_trip_random_games() {
+        let mut rng = StdRng::seed_from_u64(36);
+        let fens = [
+            STARTING_POSITION_FEN,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+            "1k2r1r1/8/8/8/8/8/8/1K2R1R1 w Ee - 0 1",
+        ];
+
+        for fen in fens {
+            for _ in 0..10 {
+                let result = Game::from_fen(fen);
+                assert!(result.is_ok());
+                let mut game = result.unwrap();
+
+                for _ in 0..80 {
+                    assert_round_trip(&game);
+                    let moves = game.legal_moves();
+                    let Some(mv) = moves.choose(&mut rng) else {
+                        break;
+                    };
+                    game.make_move(*mv);
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1034,6 +1703,7 @@ pub mod test_pseudo_legal {
                 Move::Castle(_) => "castle",
                 Move::Promotion(_) => "promotion",
                 Move::EnPassant(_) => "en passant",
+                Move::Drop(_) => "drop",
             };
 
             if let Some(x) = mv_counter.get_mut(key_to_update) {
@@ -1093,7 +1763,7 @@ pub mod test_is_legal {
     #[test]
     fn have_to_deal_with_check() {
         let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q2/PPPBBPpP/R4K1R w KQkq - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let mut game = result.unwrap();
 
@@ -1131,6 +1801,7 @@ pub mod test_is_legal {
 pub mod test_make_move {
     use super::*;
     use crate::{
+        eval::{params::EvalParams, piece_score},
         fen::STARTING_POSITION_FEN,
         piece_type::PromoteType,
         square::{self, *},
@@ -1193,7 +1864,7 @@ pub mod test_make_move {
     #[test]
     fn removes_en_passant_1() {
         let fen = "5k2/8/8/8/8/8/4Q3/4K3 w - e6 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let mut game = result.unwrap();
         assert_eq!(game.state.en_passant().unwrap_or(square::NULL), E6);
@@ -1264,7 +1935,8 @@ pub mod test_make_move {
         let to = A4;
         let mv = Move::Piece(EncodedMove::new(from, to, PieceType::Queen, true));
         let side = game.state.side_to_move();
-        let score = game.position.piece_score(side.opposite());
+        let params = EvalParams::default();
+        let score = piece_score(&game.position, side.opposite(), &params);
 
         let capture = game.make_move(mv);
 
@@ -1280,12 +1952,13 @@ pub mod test_make_move {
         assert!(!game.position.bb_side(side).is_set(from));
         assert!(!game.position.bb_side(side.opposite()).is_set(to));
         assert_eq!(
-            game.position.piece_score(side.opposite()),
+            piece_score(&game.position, side.opposite(), &params),
             score
-                - capture
-                    .expect("capture made but no piece given")
-                    .piece_type()
-                    .score() as i32
+                - params.piece_value(
+                    capture
+                        .expect("capture made but no piece given")
+                        .piece_type()
+                )
         )
     }
 
@@ -1299,7 +1972,8 @@ pub mod test_make_move {
         let to = H8;
         let mv = Move::Promotion(PromotionMove::new(from, to, &PromoteType::Queen, false));
         let side = game.state.side_to_move();
-        let score = game.position.piece_score(side);
+        let params = EvalParams::default();
+        let score = piece_score(&game.position, side, &params);
         game.make_move(mv);
 
         assert!(game.position.at(to).is_some());
@@ -1309,8 +1983,8 @@ pub mod test_make_move {
         assert!(game.position.bb_side(side).is_set(to));
         assert!(!game.position.bb_side(side).is_set(from));
         assert_eq!(
-            game.position.piece_score(side),
-            score - PieceType::Pawn.score() as i32 + PieceType::Queen.score() as i32
+            piece_score(&game.position, side, &params),
+            score - params.piece_value(PieceType::Pawn) + params.piece_value(PieceType::Queen)
         )
     }
 
@@ -1744,6 +2418,7 @@ pub mod test_make_move {
         assert!(game.state().en_passant().is_none());
 
         let mut expected_zobrist = initial_zobrist.clone();
+        expected_zobrist.hash_side(side);
         expected_zobrist.hash_side(game.state().side_to_move());
         expected_zobrist.hash_en_passant(en_passant_option);
 
@@ -1862,6 +2537,7 @@ pub mod test_zobrist {
         game.make_move(mv);
 
         let mut expected = start_zobrist.clone();
+        expected.hash_side(side);
         expected.hash_side(game.state().side_to_move());
         expected.hash_piece(side, PieceType::Rook, A8);
         expected.hash_piece(side, PieceType::Rook, D8);
@@ -1885,7 +2561,7 @@ pub mod test_zobrist {
     #[test]
     fn zobrist_promotion_move() {
         let fen = "r3k3/pppppppP/8/8/4P3/8/PPPP1PP1/R3K2R w KQkq - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let mut game = result.unwrap();
         let start_zobrist = game.state().zobrist().clone();
@@ -1915,7 +2591,7 @@ pub mod test_zobrist {
     #[test]
     fn zobrist_en_passant() {
         let fen = "r3k3/ppppp1pP/8/8/4Pp2/8/PPPP1PP1/R3K2R b KQkq e3 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let mut game = result.unwrap();
         let start_zobrist = game.state().zobrist().clone();
@@ -1929,6 +2605,7 @@ pub mod test_zobrist {
         game.make_move(mv);
 
         let mut expected = start_zobrist;
+        expected.hash_side(side);
         expected.hash_side(game.state().side_to_move());
         expected.hash_en_passant(en_passant);
         expected.hash_piece(side, PieceType::Pawn, from);
@@ -2474,6 +3151,59 @@ pub mod test_unmake_move {
         assert!(game.position.bb_side(side).is_set(king_before));
         assert!(game.position.bb_side(side).is_set(rook_before));
     }
+
+    #[test]
+    fn castle_chess960_swap() {
+        // the king and rook land on each other's start squares
+        let fen = "k7/8/8/8/8/8/8/4RKR1 w K - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let start_zobrist = *game.state().zobrist();
+        let side = game.state.side_to_move();
+        let mv = Move::Castle(Castle::Kingside);
+        assert!(game.legal_moves().contains(&mv));
+
+        let prev_state = game.state.encode();
+        let capture = game.make_move(mv);
+        assert_eq!(
+            game.position.at(G1),
+            Some(Piece::new(side, PieceType::King))
+        );
+        assert_eq!(
+            game.position.at(F1),
+            Some(Piece::new(side, PieceType::Rook))
+        );
+        assert_eq!(
+            game.position.at(E1),
+            Some(Piece::new(side, PieceType::Rook))
+        );
+        assert_eq!(game.to_fen(), "k7/8/8/8/8/8/8/4RRK1 b - - 1 1");
+
+        game.unmake_move(mv, capture, prev_state);
+        assert_eq!(game.to_fen(), fen);
+        assert_eq!(game.state().zobrist(), &start_zobrist);
+    }
+
+    #[test]
+    fn castle_chess960_queenside() {
+        let fen = "1r2k3/8/8/8/8/8/8/1R2K3 w Qq - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let start_zobrist = *game.state().zobrist();
+        let mv = Move::Castle(Castle::Queenside);
+
+        let prev_state = game.state.encode();
+        let capture = game.make_move(mv);
+        assert_eq!(game.to_fen(), "1r2k3/8/8/8/8/8/8/2KR4 b q - 1 1");
+        let loaded = Game::from_fen(&game.to_fen()).unwrap();
+        assert_eq!(game.state().zobrist(), loaded.state().zobrist());
+
+        game.unmake_move(mv, capture, prev_state);
+        assert_eq!(game.to_fen(), fen);
+        assert_eq!(game.state().zobrist(), &start_zobrist);
+    }
 }
 
 #[cfg(test)]
@@ -2634,7 +3364,7 @@ pub mod test_is_checkmate {
     #[test]
     fn pos_1() {
         let fen = "rnb1kbnr/ppppppp1/8/8/8/8/PPPPPPP1/RNBQ2Kq w KQkq - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let mut game = result.unwrap();
         let side = game.state().side_to_move();
@@ -2655,7 +3385,7 @@ pub mod test_is_checkmate {
     #[test]
     fn pos_2() {
         let fen = "rnb1kbn1/ppppppp1/8/8/8/8/PPPPPPP1/RNBQ2Kq w KQkq - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let mut game = result.unwrap();
         let side = game.state().side_to_move();
@@ -2676,7 +3406,7 @@ pub mod test_is_checkmate {
     #[test]
     fn pos_3() {
         let fen = "rnb1k1n1/ppppppp1/3b4/3q4/8/8/PPPPP3/RNBQ2NK w KQkq - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let mut game = result.unwrap();
         let side = game.state().side_to_move();
@@ -2697,7 +3427,7 @@ pub mod test_is_checkmate {
     #[test]
     fn pos_4() {
         let fen = "rnb1k1n1/ppppppp1/3b4/3q4/8/4P3/PPPP4/RNBQ2BK w KQkq - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let mut game = result.unwrap();
         let side = game.state().side_to_move();
@@ -2718,7 +3448,7 @@ pub mod test_is_checkmate {
     #[test]
     fn pos_5() {
         let fen = "rnb1k1n1/ppppppp1/3b4/3q4/8/4P3/PPPP4/RNBB2BK w KQkq - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let mut game = result.unwrap();
         let side = game.state().side_to_move();
@@ -2739,7 +3469,7 @@ pub mod test_is_checkmate {
     #[test]
     fn pos_6() {
         let fen = "rnb1k1n1/ppppppp1/3b4/3q4/8/4P3/PPPP4/RNBB2RK w KQkq - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let mut game = result.unwrap();
         let side = game.state().side_to_move();
@@ -2760,7 +3490,7 @@ pub mod test_is_checkmate {
 
 #[cfg(test)]
 pub mod test_escape_moves {
-    use crate::bitboard;
+    use crate::{bitboard, square::*};
 
     use super::*;
 
@@ -2809,7 +3539,7 @@ pub mod test_escape_moves {
                     let (_, to_bb) = piece_mv.decode_into_bb();
                     moves_bb_arr[piece_type.to_usize()] |= to_bb;
                 }
-                Move::Castle(_) => {}
+                Move::Castle(_) | Move::Drop(_) => {}
                 Move::Promotion(pawn_mv) => {
                     let (_, to_bb) = pawn_mv.decode_into_bb();
                     moves_bb_arr[PieceType::Pawn.to_usize()] |= to_bb;
@@ -2917,6 +3647,18 @@ pub mod test_escape_moves {
             "failed knight test"
         );
     }
+
+    #[test]
+    fn en_passant_captures_checker() {
+        // the pawn that just moved to d5 gives check and can be taken on d6
+        let fen = "8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+
+        let en_passant = Move::EnPassant(EncodedMove::new(E4, D3, PieceType::Pawn, true));
+        assert!(game.legal_moves().contains(&en_passant));
+    }
 }
 
 #[cfg(test)]
@@ -2969,7 +3711,7 @@ pub mod test_loud_moves {
                     let (_, to_bb) = piece_mv.decode_into_bb();
                     moves_bb_arr[piece_type.to_usize()] |= to_bb;
                 }
-                Move::Castle(_) => {}
+                Move::Castle(_) | Move::Drop(_) => {}
                 Move::Promotion(pawn_mv) => {
                     let (_, to_bb) = pawn_mv.decode_into_bb();
                     moves_bb_arr[PieceType::Pawn.to_usize()] |= to_bb;
@@ -3158,7 +3900,7 @@ pub mod test_is_stalemate {
     #[test]
     fn stalemate_pos_7() {
         let fen = "3bb3/k7/8/1Np5/KN5r/7r/8/8 w - - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let mut game = result.unwrap();
         let side = game.state.side_to_move();
@@ -3170,7 +3912,7 @@ pub mod test_is_stalemate {
     #[test]
     fn stalemate_pos_8() {
         let fen = "3bb3/k7/8/1Np5/KN5r/7r/8/4R3 w - - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let mut game = result.unwrap();
         let side = game.state.side_to_move();
@@ -3182,7 +3924,7 @@ pub mod test_is_stalemate {
     #[test]
     fn stalemate_pos_9() {
         let fen = "3bb3/k7/8/1Np5/KN5r/7r/8/4Q3 w - - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let mut game = result.unwrap();
         let side = game.state.side_to_move();
@@ -3191,3 +3933,172 @@ pub mod test_is_stalemate {
         assert!(!game.is_stalemate(legal_check_preprocessing))
     }
 }
+
+#[cfg(test)]
+pub mod test_status {
+    use super::*;
+    use crate::{fen::STARTING_POSITION_FEN, square::*};
+
+    fn shuffle_knights(game: &mut Game) {
+        game.make_move(Move::Piece(EncodedMove::new(
+            G1,
+            F3,
+            PieceType::Knight,
+            false,
+        )));
+        game.make_move(Move::Piece(EncodedMove::new(
+            G8,
+            F6,
+            PieceType::Knight,
+            false,
+        )));
+        game.make_move(Move::Piece(EncodedMove::new(
+            F3,
+            G1,
+            PieceType::Knight,
+            false,
+        )));
+        game.make_move(Move::Piece(EncodedMove::new(
+            F6,
+            G8,
+            PieceType::Knight,
+            false,
+        )));
+    }
+
+    #[test]
+    fn in_progress() {
+        let result = Game::from_fen(STARTING_POSITION_FEN);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let status = game.status();
+
+        assert_eq!(status, GameStatus::InProgress);
+        assert!(!status.is_over());
+        assert_eq!(status.winner(), None);
+        assert_eq!(status.result(), "*");
+    }
+
+    #[test]
+    fn checkmate() {
+        // fool's mate
+        let fen = "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let status = game.status();
+
+        assert_eq!(status, GameStatus::Checkmate(Side::Black));
+        assert_eq!(status.reason(), "checkmate");
+        assert_eq!(status.winner(), Some(Side::Black));
+        assert_eq!(status.result(), "0-1");
+        assert_eq!(status.to_string(), "black wins by checkmate");
+    }
+
+    #[test]
+    fn stalemate() {
+        let fen = "4k3/4Pn2/4K3/7B/8/8/8/8 b - - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let status = game.status();
+
+        assert_eq!(status, GameStatus::Stalemate);
+        assert_eq!(status.result(), "1/2-1/2");
+    }
+
+    #[test]
+    fn threefold_counts_the_starting_position() {
+        let result = Game::from_fen(STARTING_POSITION_FEN);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+
+        shuffle_knights(&mut game);
+        assert_eq!(game.status(), GameStatus::InProgress);
+
+        shuffle_knights(&mut game);
+        let status = game.status();
+        assert_eq!(status, GameStatus::ThreefoldRepetition);
+        assert_eq!(status.to_string(), "draw by threefold repetition");
+    }
+
+    #[test]
+    fn only_the_game_counts_the_fen_position() {
+        let result = fen::load_fen(STARTING_POSITION_FEN);
+        assert!(result.is_ok());
+        let (_, state) = result.unwrap();
+        assert_eq!(state.repetitions(*state.zobrist()), 0);
+
+        let result = Game::from_fen(STARTING_POSITION_FEN);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(game.state().repetitions(*game.state().zobrist()), 1);
+    }
+
+    #[test]
+    fn fivefold() {
+        let result = Game::from_fen(STARTING_POSITION_FEN);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+
+        for _ in 0..3 {
+            shuffle_knights(&mut game);
+        }
+        assert_eq!(game.status(), GameStatus::ThreefoldRepetition);
+
+        shuffle_knights(&mut game);
+        assert_eq!(game.status(), GameStatus::FivefoldRepetition);
+    }
+
+    #[test]
+    fn fifty_and_seventy_five_moves() {
+        let result = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 99 80");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().status(), GameStatus::InProgress);
+
+        let result = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 100 80");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().status(), GameStatus::FiftyMoveRule);
+
+        let result = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 150 105");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().status(), GameStatus::SeventyFiveMoveRule);
+    }
+
+    #[test]
+    fn mate_stands_over_fifty_moves() {
+        let fen = "R5k1/5ppp/8/8/8/8/8/6K1 b - - 120 90";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+
+        assert_eq!(game.status(), GameStatus::Checkmate(Side::White));
+    }
+
+    #[test]
+    fn insufficient_material() {
+        let fen = "4k3/8/8/8/2B5/8/8/4K3 w - - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+
+        assert_eq!(game.status(), GameStatus::InsufficientMaterial);
+
+        // a lone king can still reach the hill
+        let result = Game::from_variant_fen(fen, Variant::KingOfTheHill);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().status(), GameStatus::InProgress);
+    }
+
+    #[test]
+    fn variant_win() {
+        let fen = "4k3/8/8/3K4/8/8/8/8 b - - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::KingOfTheHill);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let status = game.status();
+
+        assert_eq!(status, GameStatus::VariantWin(Side::White));
+        assert_eq!(status.result(), "1-0");
+    }
+}
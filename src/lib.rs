@@ -1,22 +1,33 @@
 mod bitboard;
+mod book;
+pub mod book_builder;
+pub mod epd;
 mod eval;
 mod fen;
 mod game;
 mod move_gen;
 mod move_list;
 mod mv;
+mod nnue;
 mod perft;
+mod pgn;
 mod phase;
 mod piece;
 mod piece_type;
 mod psqt;
+mod san;
 mod score;
 mod search;
 mod side;
 mod square;
 mod state;
+mod syzygy;
+pub mod tune;
 pub mod uci;
 mod util;
+mod variant;
+// the browser client, built natively only to run its tests
+#[cfg(any(target_arch = "wasm32", test))]
 mod wasm;
 
 #[cfg(test)]
@@ -2,24 +2,33 @@
 extern crate unindent;
 
 mod bitboard;
+mod book;
+// only the perft suite reads epd in the engine binary
+#[allow(dead_code)]
+mod epd;
 mod eval;
 mod fen;
 mod game;
 mod move_gen;
 mod move_list;
 mod mv;
+mod nnue;
 mod perft;
+mod pgn;
 mod phase;
 mod piece;
 mod piece_type;
 mod psqt;
+mod san;
 mod score;
 mod search;
 mod side;
 mod square;
 mod state;
+mod syzygy;
 pub mod uci;
 mod util;
+mod variant;
 
 fn main() {
     uci::main();
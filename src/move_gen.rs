@@ -1,13 +1,17 @@
 use crate::{
-    bitboard::{self, squares_between::bb_squares_between, BB, KING_MOVES, PAWN_CAPTURES},
+    bitboard::{self, BB, KING_MOVES, PAWN_CAPTURES},
     piece_type::PieceType,
     side::Side,
     square::Square,
     state::position::Position,
 };
 
+use magic::bb_squares_between;
+
 pub mod check_legal;
 pub mod escape_check;
+pub mod legal;
+pub mod magic;
 mod parallel;
 mod pawn;
 pub mod pseudo_legal;
@@ -272,7 +276,7 @@ pub mod test_attacks {
     #[test]
     fn no_1() {
         let fen = "4k3/1n6/5N2/8/8/2B5/6B1/4K3 w - - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let game = result.unwrap();
         let position = game.position();
@@ -1,13 +1,19 @@
 use crate::{
-    bitboard::{self, squares_between::bb_squares_between, BB},
+    bitboard::{self, BB, KING_MOVES, PAWN_CAPTURES},
     game::Game,
     mv::{castle::Castle, Decode},
+    piece_type::PieceType,
     side::Side,
     square::Square,
-    state::position::Position,
+    state::{castle_rights::CastleFiles, position::Position},
+    variant::Variant,
 };
 
-use super::{checkers_pinners_pinned, controlled_squares_with_king_gone};
+use super::{
+    checkers_pinners_pinned, controlled_squares_with_king_gone,
+    magic::{bb_line, bb_squares_between},
+    pseudo_legal::{bishop_attacks, rook_attacks},
+};
 
 pub struct LegalCheckPreprocessing {
     checkers: BB,
@@ -34,7 +40,34 @@ impl LegalCheckPreprocessing {
     }
 
     pub fn from(game: &mut Game, side: Side) -> LegalCheckPreprocessing {
-        let (checkers, pinners, pinned) = checkers_pinners_pinned(game.position(), side.opposite());
+        // nothing is pinned or gives check without a royal king, and there may be no king at all
+        if !game.state().variant().has_royal_king() {
+            return LegalCheckPreprocessing::new(
+                bitboard::EMPTY,
+                bitboard::EMPTY,
+                bitboard::EMPTY,
+                bitboard::EMPTY,
+            );
+        }
+        // the game is over once a king has blown up in atomic
+        let atomic = game.state().variant() == Variant::Atomic;
+        if atomic
+            && (game.position().king_sq_bb(Side::White).empty()
+                || game.position().king_sq_bb(Side::Black).empty())
+        {
+            return LegalCheckPreprocessing::new(
+                bitboard::EMPTY,
+                bitboard::EMPTY,
+                bitboard::EMPTY,
+                bitboard::EMPTY,
+            );
+        }
+
+        let (mut checkers, pinners, pinned) =
+            checkers_pinners_pinned(game.position(), side.opposite());
+        if atomic && kings_adjacent(game.position()) {
+            checkers = bitboard::EMPTY;
+        }
         let controlled_squares_with_king_gone_bb =
             controlled_squares_with_king_gone(game.mut_position(), side.opposite());
         LegalCheckPreprocessing {
@@ -127,29 +160,8 @@ pub fn is_en_passant_pinned_on_rank(
 }
 
 fn is_pinned_move_legal(from_sq: Square, to_sq: Square, king_sq: Square) -> bool {
-    // piece is assumed to be pinned
-
-    let pinned_on_diagonal = from_sq.diagonal_mask().is_set(king_sq);
-    if pinned_on_diagonal {
-        return from_sq.diagonal_mask().is_set(to_sq);
-    }
-
-    let pinned_on_anti_diagonal = from_sq.anti_diagonal_mask().is_set(king_sq);
-    if pinned_on_anti_diagonal {
-        return from_sq.anti_diagonal_mask().is_set(to_sq);
-    }
-
-    let pinned_on_file = from_sq.file_mask().is_set(king_sq);
-    if pinned_on_file {
-        return from_sq.file_mask().is_set(to_sq);
-    }
-
-    let pinned_on_rank = from_sq.rank_mask().is_set(king_sq);
-    if pinned_on_rank {
-        return from_sq.rank_mask().is_set(to_sq);
-    }
-
-    false
+    // piece is assumed to be pinned, so it can only move along the line through the king
+    bb_line(from_sq, king_sq).is_set(to_sq)
 }
 
 pub fn is_legal_king_move(
@@ -201,6 +213,7 @@ pub fn is_legal_castle(
     position: &Position,
     castle: Castle,
     side: Side,
+    castle_files: CastleFiles,
     attacked_squares_bb: BB,
     checkers: BB,
 ) -> bool {
@@ -209,12 +222,23 @@ pub fn is_legal_castle(
         return false;
     }
     let occupied = position.bb_occupied();
-    let must_clear_squares = castle.must_clear_squares(side);
+    let must_clear_squares = castle_files.must_clear_squares(side, castle);
     if (occupied & must_clear_squares).not_empty() {
         return false;
     }
 
-    (castle.pass_through_squares(side) & attacked_squares_bb).empty()
+    if (castle_files.pass_through_squares(side, castle) & attacked_squares_bb).not_empty() {
+        return false;
+    }
+
+    // in chess960 the castling rook can be the only thing shielding the king's destination
+    // from a rook or queen on the back rank, eg. king c1, rook b1 and an enemy queen on a1
+    let (king_from, king_to) = castle_files.king_squares(side, castle);
+    let (rook_from, _) = castle_files.rook_squares(side, castle);
+    let occupied_after = occupied ^ BB::new(king_from) ^ BB::new(rook_from);
+    let (_, enemy_rank_sliders) = position.bb_sliders(side.opposite());
+
+    (rook_attacks(king_to, bitboard::EMPTY, occupied_after) & enemy_rank_sliders).empty()
 }
 
 pub fn is_legal_en_passant_move(
@@ -225,7 +249,22 @@ pub fn is_legal_en_passant_move(
     side: Side,
     legal_check_preprocessing: &LegalCheckPreprocessing,
 ) -> bool {
-    is_legal_regular_move(position, from, to, side, legal_check_preprocessing)
+    // taking the checking pawn en passant doesn't land on the checker's square, so it is checked
+    // by looking for sliders that reach the king once both pawns are gone
+    let checkers = legal_check_preprocessing.checkers();
+    if checkers.count_ones() == 1 && checkers.is_set(en_passant_capture_sq) {
+        let king_sq = position.king_sq(side);
+        let occupied_after =
+            (position.bb_occupied() ^ BB::new(from) ^ BB::new(en_passant_capture_sq)) | BB::new(to);
+        let (diag_attackers, non_diag_attackers) = position.bb_sliders(side.opposite());
+
+        return (bishop_attacks(king_sq, bitboard::EMPTY, occupied_after) & diag_attackers).empty()
+            && (rook_attacks(king_sq, bitboard::EMPTY, occupied_after) & non_diag_attackers)
+                .empty();
+    }
+
+    let is_legal = is_legal_regular_move(position, from, to, side, legal_check_preprocessing);
+    is_legal
         && !is_en_passant_pinned_on_rank(
             position,
             side,
@@ -235,6 +274,99 @@ pub fn is_legal_en_passant_move(
         )
 }
 
+// kings may touch in atomic, and neither is in check then since taking one would blow up both
+pub fn kings_adjacent(position: &Position) -> bool {
+    let white_king = position.king_sq_bb(Side::White);
+    let black_king = position.king_sq_bb(Side::Black);
+    white_king.not_empty() && (KING_MOVES[white_king.bitscan().to_usize()] & black_king).not_empty()
+}
+
+// the pieces of attack_side that can take on sq with only the occupied squares left on the board.
+// kings can't capture in atomic
+fn atomic_attackers(position: &Position, sq: Square, occupied: BB, attack_side: Side) -> BB {
+    let (diag_attackers, non_diag_attackers) = position.bb_sliders(attack_side);
+    let pawn_squares = PAWN_CAPTURES[attack_side.opposite().to_usize()][sq.to_usize()];
+    let attackers = (sq.knight_jumps() & position.bb_pc(PieceType::Knight, attack_side))
+        | (pawn_squares & position.bb_pc(PieceType::Pawn, attack_side))
+        | (bishop_attacks(sq, bitboard::EMPTY, occupied) & diag_attackers)
+        | (rook_attacks(sq, bitboard::EMPTY, occupied) & non_diag_attackers);
+
+    attackers & occupied
+}
+
+fn is_atomic_king_attacked(position: &Position, king_sq: Square, occupied: BB, side: Side) -> bool {
+    let enemy_king = position.king_sq_bb(side.opposite()) & occupied;
+    (KING_MOVES[king_sq.to_usize()] & enemy_king).empty()
+        && atomic_attackers(position, king_sq, occupied, side.opposite()).not_empty()
+}
+
+// a move in atomic is legal when it doesn't blow up the mover's king or leave it in check. blowing
+// up the opponent's king wins straight away, even from check. capture_sq is where the captured
+// piece stands, which differs from the to square for en passant
+pub fn is_legal_atomic_move(
+    position: &Position,
+    from: Square,
+    to: Square,
+    capture_sq: Option<Square>,
+    side: Side,
+) -> bool {
+    let occupied = position.bb_occupied();
+    let king_bb = position.king_sq_bb(side);
+    let king_sq = if king_bb.is_set(from) {
+        to
+    } else {
+        king_bb.bitscan()
+    };
+
+    let occupied_after = match capture_sq {
+        Some(capture_sq) => {
+            let pawns = position.bb_pieces()[PieceType::Pawn.to_usize()];
+            let blast = BB::new(to) | (KING_MOVES[to.to_usize()] & occupied & !pawns);
+            if (blast & king_bb).not_empty() {
+                return false;
+            }
+            if (blast & position.king_sq_bb(side.opposite())).not_empty() {
+                return true;
+            }
+            occupied & !blast & !BB::new(from) & !BB::new(capture_sq)
+        }
+        None => (occupied ^ BB::new(from)) | BB::new(to),
+    };
+
+    !is_atomic_king_attacked(position, king_sq, occupied_after, side)
+}
+
+pub fn is_legal_atomic_castle(
+    position: &Position,
+    castle: Castle,
+    side: Side,
+    castle_files: CastleFiles,
+    checkers: BB,
+) -> bool {
+    // assumes castle rights are set and king and rook are on home squares
+    let occupied = position.bb_occupied();
+    if checkers.not_empty()
+        || (occupied & castle_files.must_clear_squares(side, castle)).not_empty()
+    {
+        return false;
+    }
+
+    let (king_from, king_to) = castle_files.king_squares(side, castle);
+    let (rook_from, rook_to) = castle_files.rook_squares(side, castle);
+    let occupied_without_king = occupied ^ BB::new(king_from);
+    if castle_files
+        .pass_through_squares(side, castle)
+        .iter()
+        .any(|sq| is_atomic_king_attacked(position, sq, occupied_without_king, side))
+    {
+        return false;
+    }
+
+    let occupied_after =
+        (occupied_without_king ^ BB::new(rook_from)) | BB::new(king_to) | BB::new(rook_to);
+    !is_atomic_king_attacked(position, king_to, occupied_after, side)
+}
+
 #[cfg(test)]
 pub mod test_is_pinned_move_legal {
     use core::panic;
@@ -257,7 +389,7 @@ pub mod test_is_pinned_move_legal {
     #[test]
     fn can_capture_pinner() {
         let fen = "4k3/8/8/7b/8/8/4Q3/3K4 w - - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
 
         let game = result.unwrap();
@@ -327,7 +459,7 @@ pub mod test_is_pinned_move_legal {
     #[test]
     fn pinned_on_diagonal() {
         let fen = "4k3/8/8/7b/8/8/4Q3/3K4 w - - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
 
         let game = result.unwrap();
@@ -395,7 +527,7 @@ pub mod test_is_legal_regular_or_capture_move {
     #[test]
     fn can_capture_checker() {
         let fen = "4k3/8/8/7b/8/8/4Q3/3K4 w - - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
 
         let game = result.unwrap();
@@ -684,6 +816,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Queenside,
                 Side::White,
+                game.state().castle_files(),
                 attacked_squares_bb,
                 bitboard::EMPTY
             ),
@@ -694,6 +827,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Kingside,
                 Side::White,
+                game.state().castle_files(),
                 attacked_squares_bb,
                 bitboard::EMPTY
             ),
@@ -714,6 +848,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Queenside,
                 Side::Black,
+                game.state().castle_files(),
                 attacked_squares_bb_w,
                 bitboard::EMPTY
             ),
@@ -724,6 +859,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Kingside,
                 Side::Black,
+                game.state().castle_files(),
                 attacked_squares_bb_w,
                 bitboard::EMPTY
             ),
@@ -734,6 +870,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Queenside,
                 Side::White,
+                game.state().castle_files(),
                 attacked_squares_bb_b,
                 bitboard::EMPTY
             ),
@@ -744,6 +881,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Kingside,
                 Side::White,
+                game.state().castle_files(),
                 attacked_squares_bb_b,
                 bitboard::EMPTY
             ),
@@ -764,6 +902,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Queenside,
                 Side::Black,
+                game.state().castle_files(),
                 attacked_squares_bb_w,
                 bitboard::EMPTY
             ),
@@ -774,6 +913,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Kingside,
                 Side::Black,
+                game.state().castle_files(),
                 attacked_squares_bb_w,
                 bitboard::EMPTY
             ),
@@ -784,6 +924,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Queenside,
                 Side::White,
+                game.state().castle_files(),
                 attacked_squares_bb_b,
                 bitboard::EMPTY
             ),
@@ -794,6 +935,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Kingside,
                 Side::White,
+                game.state().castle_files(),
                 attacked_squares_bb_b,
                 bitboard::EMPTY
             ),
@@ -814,6 +956,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Queenside,
                 Side::Black,
+                game.state().castle_files(),
                 attacked_squares_bb_w,
                 bitboard::EMPTY
             ),
@@ -824,6 +967,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Kingside,
                 Side::Black,
+                game.state().castle_files(),
                 attacked_squares_bb_w,
                 bitboard::EMPTY
             ),
@@ -834,6 +978,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Queenside,
                 Side::White,
+                game.state().castle_files(),
                 attacked_squares_bb_b,
                 bitboard::EMPTY
             ),
@@ -844,6 +989,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Kingside,
                 Side::White,
+                game.state().castle_files(),
                 attacked_squares_bb_b,
                 bitboard::EMPTY
             ),
@@ -863,6 +1009,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Queenside,
                 Side::White,
+                game.state().castle_files(),
                 attacked_squares_bb,
                 bitboard::EMPTY
             ),
@@ -873,6 +1020,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Kingside,
                 Side::White,
+                game.state().castle_files(),
                 attacked_squares_bb,
                 bitboard::EMPTY
             ),
@@ -892,6 +1040,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Queenside,
                 Side::White,
+                game.state().castle_files(),
                 attacked_squares_bb,
                 bitboard::EMPTY
             ),
@@ -902,6 +1051,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Kingside,
                 Side::White,
+                game.state().castle_files(),
                 attacked_squares_bb,
                 bitboard::EMPTY
             ),
@@ -921,6 +1071,7 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Queenside,
                 Side::White,
+                game.state().castle_files(),
                 attacked_squares_bb,
                 bitboard::EMPTY
             ),
@@ -931,12 +1082,71 @@ pub mod test_is_legal_castle {
                 game.position(),
                 Castle::Kingside,
                 Side::White,
+                game.state().castle_files(),
                 attacked_squares_bb,
                 bitboard::EMPTY
             ),
             true
         );
     }
+
+    #[test]
+    fn cant_castle_when_rook_shields_king_destination() {
+        // the king stays on c1, but the rook leaving b1 opens the queen's line to it
+        let fen = "4k3/8/8/8/8/8/8/qRK5 w Q - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        let attacked_squares_bb = controlled_squares(game.position(), Side::Black);
+        assert!(!is_legal_castle(
+            game.position(),
+            Castle::Queenside,
+            Side::White,
+            game.state().castle_files(),
+            attacked_squares_bb,
+            bitboard::EMPTY
+        ));
+
+        let fen = "4k3/8/8/8/8/8/8/nRK5 w Q - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        let attacked_squares_bb = controlled_squares(game.position(), Side::Black);
+        assert!(is_legal_castle(
+            game.position(),
+            Castle::Queenside,
+            Side::White,
+            game.state().castle_files(),
+            attacked_squares_bb,
+            bitboard::EMPTY
+        ));
+    }
+
+    #[test]
+    fn chess960_must_clear_squares() {
+        // the knight on d1 is in the way of the queenside castle, kingside only needs f1 clear
+        let fen = "4k3/8/8/8/8/8/8/1R1NK1R1 w KQ - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        let attacked_squares_bb = controlled_squares(game.position(), Side::Black);
+        assert!(!is_legal_castle(
+            game.position(),
+            Castle::Queenside,
+            Side::White,
+            game.state().castle_files(),
+            attacked_squares_bb,
+            bitboard::EMPTY
+        ));
+        assert!(is_legal_castle(
+            game.position(),
+            Castle::Kingside,
+            Side::White,
+            game.state().castle_files(),
+            attacked_squares_bb,
+            bitboard::EMPTY
+        ));
+    }
 }
 
 #[cfg(test)]
@@ -991,7 +1201,7 @@ pub mod test_is_legal_en_passant {
     #[test]
     fn pinned_on_file_2() {
         let fen = "4k3/5r2/8/4Pp2/8/8/5K2/8 w - f6 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let mut game = result.unwrap();
         let side = game.state().side_to_move();
@@ -1097,4 +1307,24 @@ pub mod test_is_legal_en_passant {
             &legal_check_preprocessing
         ));
     }
+
+    #[test]
+    fn captures_checker() {
+        let fen = "8/8/3p4/1Pp4r/1K5k/5p2/4P1P1/1R6 w - c6 0 3";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let side = game.state().side_to_move();
+
+        let legal_check_preprocessing = LegalCheckPreprocessing::from(&mut game, side);
+
+        assert!(is_legal_en_passant_move(
+            game.position(),
+            B5,
+            C6,
+            game.state().en_passant_capture_sq().unwrap(),
+            side,
+            &legal_check_preprocessing
+        ));
+    }
 }
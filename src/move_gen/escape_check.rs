@@ -38,6 +38,18 @@ pub fn pawn(
     side: Side,
     check_ray: BB,
 ) -> BB {
-    // check_ray includes the square of the checker
-    pseudo_legal::pawn(from, friendly_occupied, enemy_occupied, en_passant, side) & check_ray
+    // check_ray includes the square of the checker. a pawn that gives check with a double push
+    // can also be taken en passant, which lands behind it instead of on it
+    let mut targets = check_ray;
+    if let Some(en_passant_sq) = en_passant {
+        let pushed_pawn_sq = match side {
+            Side::White => en_passant_sq.rank_down(),
+            Side::Black => en_passant_sq.rank_up(),
+        };
+        if check_ray.is_set(pushed_pawn_sq) {
+            targets |= BB::new(en_passant_sq);
+        }
+    }
+
+    pseudo_legal::pawn(from, friendly_occupied, enemy_occupied, en_passant, side) & targets
 }
@@ -0,0 +1,390 @@
+use crate::{
+    bitboard::{self, BB, PAWN_CAPTURES},
+    game::Game,
+    move_list::MoveList,
+    mv::{castle::Castle, Decode, Move, PromotionMove},
+    piece_type::{PieceType, PromoteType, PIECE_TYPE_MAP},
+    variant::Variant,
+};
+
+use super::{
+    check_legal::{
+        is_legal_atomic_castle, is_legal_atomic_move, is_legal_castle, is_legal_en_passant_move,
+        LegalCheckPreprocessing,
+    },
+    magic::{bb_line, bb_squares_between},
+    pseudo_legal,
+};
+
+// which legal moves to generate. promotions count as captures like the loud moves quiescence
+// search looks at, so captures and quiets never overlap and together make up all the moves
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveGen {
+    All,
+    Captures,
+    Quiets,
+}
+
+// generates only legal moves, in the same order as the pseudo legal generators. moves of
+// pinned pieces are masked to the line through the king and, when in check, moves of other
+// pieces are masked to the squares that capture or block the checker
+pub fn legal_moves(
+    game: &Game,
+    move_gen: MoveGen,
+    legal_check_preprocessing: &LegalCheckPreprocessing,
+) -> MoveList {
+    match game.state().variant() {
+        Variant::Antichess => return antichess_moves(game, move_gen),
+        // there are no moves once the game is over
+        variant if variant.is_lost(game) => return MoveList::new(),
+        Variant::Atomic => return atomic_moves(game, move_gen, legal_check_preprocessing),
+        _ => {}
+    }
+
+    let position = game.position();
+    let state = game.state();
+    let side = state.side_to_move();
+    let friendly_occupied = position.bb_side(side);
+    let enemy_occupied = position.bb_side(side.opposite());
+    let empty = !(friendly_occupied | enemy_occupied);
+    let king_sq = position.king_sq(side);
+
+    let targets = match move_gen {
+        MoveGen::All => !friendly_occupied,
+        MoveGen::Captures => enemy_occupied,
+        MoveGen::Quiets => empty,
+    };
+    let promote_rank_bb = bitboard::ROW_1 | bitboard::ROW_8;
+    let pawn_targets = match move_gen {
+        MoveGen::All => !friendly_occupied,
+        MoveGen::Captures => enemy_occupied | promote_rank_bb,
+        MoveGen::Quiets => empty & !promote_rank_bb,
+    };
+
+    let checkers = legal_check_preprocessing.checkers();
+    let check_mask = match checkers.count_ones() {
+        0 => !bitboard::EMPTY,
+        1 => checkers | bb_squares_between(king_sq, checkers.bitscan()),
+        _ => bitboard::EMPTY,
+    };
+    let pinned = legal_check_preprocessing.pinned();
+
+    let en_passant = match move_gen {
+        MoveGen::Quiets => None,
+        _ => state.en_passant(),
+    };
+
+    let mut mv_list = MoveList::new();
+    for (i, piece_bb) in position.bb_pieces().iter().enumerate() {
+        let piece_type = PIECE_TYPE_MAP[i];
+        let piece_bb_iter = (*piece_bb & friendly_occupied).iter();
+
+        for from in piece_bb_iter {
+            let moves_bb = piece_type.pseudo_legal_moves_bb(
+                from,
+                friendly_occupied,
+                enemy_occupied,
+                side,
+                None,
+            );
+
+            let mut legal_moves_bb = match piece_type {
+                PieceType::King => {
+                    moves_bb
+                        & targets
+                        & !legal_check_preprocessing.controlled_squares_with_king_gone_bb()
+                }
+                PieceType::Pawn => moves_bb & pawn_targets & check_mask,
+                _ => moves_bb & targets & check_mask,
+            };
+            if pinned.is_set(from) {
+                legal_moves_bb &= bb_line(from, king_sq);
+            }
+
+            // en passant is checked on its own, the captured pawn isn't on the target square
+            if let Some(en_passant_sq) = en_passant {
+                if piece_type == PieceType::Pawn
+                    && PAWN_CAPTURES[side.to_usize()][from.to_usize()].is_set(en_passant_sq)
+                    && is_legal_en_passant_move(
+                        position,
+                        from,
+                        en_passant_sq,
+                        state
+                            .en_passant_capture_sq()
+                            .expect("en passant square without a capture square"),
+                        side,
+                        legal_check_preprocessing,
+                    )
+                {
+                    legal_moves_bb |= BB::new(en_passant_sq);
+                }
+            }
+
+            piece_type.push_bb_to_move_list(
+                &mut mv_list,
+                legal_moves_bb,
+                from,
+                side,
+                enemy_occupied,
+                en_passant,
+            );
+        }
+    }
+
+    if move_gen != MoveGen::Captures && checkers.empty() {
+        let castle_rights = state.castle_rights();
+        for castle in [Castle::Queenside, Castle::Kingside] {
+            if castle_rights.can(side, castle)
+                && is_legal_castle(
+                    position,
+                    castle,
+                    side,
+                    state.castle_files(),
+                    legal_check_preprocessing.controlled_squares_with_king_gone_bb(),
+                    checkers,
+                )
+            {
+                mv_list.push_move(Move::Castle(castle));
+            }
+        }
+    }
+
+    // drops are quiet and can't leave the king in check, so in check they only block
+    if move_gen != MoveGen::Captures {
+        pseudo_legal::push_drops(position, side, empty & check_mask, &mut mv_list);
+    }
+
+    mv_list
+}
+
+fn is_capture(mv: &Move) -> bool {
+    match mv {
+        Move::King(mv)
+        | Move::Piece(mv)
+        | Move::Rook(mv)
+        | Move::Pawn(mv)
+        | Move::DoublePawnPush(mv) => mv.is_capture(),
+        Move::Promotion(mv) => mv.is_capture(),
+        Move::EnPassant(_) => true,
+        Move::Castle(_) | Move::Drop(_) => false,
+    }
+}
+
+// the pseudo legal moves are all legal since there's no check, and pawns can promote to a king
+// too. taking is compulsory so only the captures are legal when there are any
+fn antichess_moves(game: &Game, move_gen: MoveGen) -> MoveList {
+    let mut moves = vec![];
+    for mv in game
+        .pseudo_legal_moves(game.state().side_to_move())
+        .into_list()
+    {
+        moves.push(mv);
+        if let Move::Promotion(promotion_mv) = mv {
+            if promotion_mv.promote_piece_type() == PieceType::Queen {
+                let (from, to) = promotion_mv.decode_into_squares();
+                moves.push(Move::Promotion(PromotionMove::new(
+                    from,
+                    to,
+                    &PromoteType::King,
+                    promotion_mv.is_capture(),
+                )));
+            }
+        }
+    }
+
+    let must_capture = moves.iter().any(is_capture);
+    let mut mv_list = MoveList::new();
+    for mv in moves {
+        let is_promotion = matches!(mv, Move::Promotion(_));
+        let legal = match move_gen {
+            MoveGen::All => !must_capture || is_capture(&mv),
+            MoveGen::Captures => is_capture(&mv) || (!must_capture && is_promotion),
+            MoveGen::Quiets => !must_capture && !is_promotion,
+        };
+        if legal {
+            mv_list.push_move(mv);
+        }
+    }
+
+    mv_list
+}
+
+// the pseudo legal moves that don't blow up the mover's own king or leave it in check. kings can't
+// capture, since they would blow up with the piece they take
+fn atomic_moves(
+    game: &Game,
+    move_gen: MoveGen,
+    legal_check_preprocessing: &LegalCheckPreprocessing,
+) -> MoveList {
+    let position = game.position();
+    let state = game.state();
+    let side = state.side_to_move();
+
+    let mut mv_list = MoveList::new();
+    for mv in game.pseudo_legal_moves(side).into_list() {
+        let is_promotion = matches!(mv, Move::Promotion(_));
+        let generated = match move_gen {
+            MoveGen::All => true,
+            MoveGen::Captures => is_capture(&mv) || is_promotion,
+            MoveGen::Quiets => !is_capture(&mv) && !is_promotion,
+        };
+        if !generated {
+            continue;
+        }
+
+        let legal = match mv {
+            Move::Castle(castle) => is_legal_atomic_castle(
+                position,
+                castle,
+                side,
+                state.castle_files(),
+                legal_check_preprocessing.checkers(),
+            ),
+            Move::King(king_mv) if king_mv.is_capture() => false,
+            Move::EnPassant(en_passant_mv) => {
+                let (from, to) = en_passant_mv.decode_into_squares();
+                is_legal_atomic_move(position, from, to, state.en_passant_capture_sq(), side)
+            }
+            Move::Promotion(promotion_mv) => {
+                let (from, to) = promotion_mv.decode_into_squares();
+                let capture_sq = promotion_mv.is_capture().then_some(to);
+                is_legal_atomic_move(position, from, to, capture_sq, side)
+            }
+            Move::King(mv)
+            | Move::Rook(mv)
+            | Move::Pawn(mv)
+            | Move::DoublePawnPush(mv)
+            | Move::Piece(mv) => {
+                let (from, to) = mv.decode_into_squares();
+                is_legal_atomic_move(position, from, to, mv.is_capture().then_some(to), side)
+            }
+            Move::Drop(_) => unreachable!("atomic is played without drops"),
+        };
+        if legal {
+            mv_list.push_move(mv);
+        }
+    }
+
+    mv_list
+}
+
+#[cfg(test)]
+pub mod test_legal_moves {
+    use crate::{epd::read_epd, mv::Decode, square::*};
+    use std::{fs, path::PathBuf};
+
+    use super::*;
+
+    // compares the moves against filtering the pseudo legal moves, then does the same after each
+    // of them down to `depth` plies
+    fn check_against_pseudo_legal_filter(game: &mut Game, depth: u8, fen: &str) {
+        let side = game.state().side_to_move();
+        let legal_check_preprocessing = LegalCheckPreprocessing::from(game, side);
+        let pseudo_legal_mv_list = if legal_check_preprocessing.in_check() {
+            game.pseudo_legal_escape_moves(side, &legal_check_preprocessing)
+        } else {
+            game.pseudo_legal_moves(side)
+        };
+        let expected: Vec<Move> = pseudo_legal_mv_list
+            .list()
+            .iter()
+            .copied()
+            .filter(|mv| game.is_legal(*mv, &legal_check_preprocessing))
+            .collect();
+
+        let all = legal_moves(game, MoveGen::All, &legal_check_preprocessing);
+        assert_eq!(all.list(), &expected, "{} {}", fen, game.to_fen());
+
+        if depth == 0 {
+            return;
+        }
+        for mv in expected {
+            let prev_state = game.state().encode();
+            let capture = game.make_move(mv);
+            check_against_pseudo_legal_filter(game, depth - 1, fen);
+            game.unmake_move(mv, capture, prev_state);
+        }
+    }
+
+    // every position of the perft suite and the positions up to three plies from them
+    #[test]
+    fn matches_pseudo_legal_filter() {
+        let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        d.push("resources/test/perftsuite.epd");
+
+        let contents = fs::read_to_string(d).expect("should have been able to read from file");
+        for result in read_epd(&contents) {
+            assert!(result.is_ok());
+            let epd = result.unwrap();
+
+            let result = epd.game();
+            assert!(result.is_ok());
+            let mut game = result.unwrap();
+
+            check_against_pseudo_legal_filter(&mut game, 3, &epd.fen);
+        }
+    }
+
+    #[test]
+    fn captures_and_quiets_split_all_moves() {
+        let fens = [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        ];
+        for fen in fens {
+            let result = Game::from_fen(fen);
+            assert!(result.is_ok());
+            let mut game = result.unwrap();
+
+            let all = game.legal_moves();
+            let captures = game.legal_captures();
+            let quiets = game.legal_quiets();
+            assert_eq!(captures.len() + quiets.len(), all.len());
+            assert!(captures
+                .iter()
+                .all(|mv| all.contains(mv) && !quiets.contains(mv)));
+            assert!(quiets.iter().all(|mv| all.contains(mv)));
+            assert!(captures.iter().all(|mv| match mv {
+                Move::Promotion(_) | Move::EnPassant(_) => true,
+                Move::King(mv)
+                | Move::Piece(mv)
+                | Move::Rook(mv)
+                | Move::Pawn(mv)
+                | Move::DoublePawnPush(mv) => mv.is_capture(),
+                Move::Castle(_) | Move::Drop(_) => false,
+            }));
+        }
+    }
+
+    #[test]
+    fn double_check_only_king_moves() {
+        let fen = "4k3/8/8/8/4r3/5n2/8/R3K2R w KQ - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+
+        let legal_moves = game.legal_moves();
+        assert!(!legal_moves.is_empty());
+        assert!(legal_moves.iter().all(|mv| matches!(mv, Move::King(_))));
+    }
+
+    #[test]
+    fn pinned_piece_moves_along_pin() {
+        let fen = "4k3/4r3/8/8/8/8/4Q3/4K3 w - - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+
+        let queen_moves: Vec<Square> = game
+            .legal_moves()
+            .into_iter()
+            .filter_map(|mv| match mv {
+                Move::Piece(mv) => Some(mv.decode_into_squares().1),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(queen_moves, vec![E3, E4, E5, E6, E7]);
+    }
+}
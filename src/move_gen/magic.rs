@@ -0,0 +1,325 @@
+use std::sync::OnceLock;
+
+use crate::{
+    bitboard::{self, BB, BOARD_LENGTH, FILE_A, FILE_H, ROW_1, ROW_8},
+    square::Square,
+};
+
+use super::slider::*;
+
+// sliding attacks are looked up in tables that are built the first time they're needed. the
+// relevant blockers of a square are turned into an index by multiplying them with a magic number,
+// or by gathering them with pext when the engine is compiled with bmi2
+// (RUSTFLAGS="-C target-cpu=native"). pext is picked at compile time rather than at runtime like
+// the nnue simd, a runtime check would stop the lookups from being inlined
+
+#[derive(Clone, Copy)]
+struct Magic {
+    mask: BB,
+    magic: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl Magic {
+    #[cfg(all(target_arch = "x86_64", target_feature = "bmi2"))]
+    #[inline(always)]
+    fn index(&self, occupied: BB) -> usize {
+        // safe because bmi2 is enabled at compile time
+        #[allow(unused_unsafe)]
+        let index = unsafe { std::arch::x86_64::_pext_u64(occupied.0, self.mask.0) };
+        self.offset + index as usize
+    }
+
+    #[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+    #[inline(always)]
+    fn index(&self, occupied: BB) -> usize {
+        self.offset + ((occupied & self.mask).0.wrapping_mul(self.magic) >> self.shift) as usize
+    }
+}
+
+struct SliderTables {
+    rook: [Magic; BOARD_LENGTH],
+    bishop: [Magic; BOARD_LENGTH],
+    attacks: Vec<BB>,
+    between: Vec<BB>,
+    line: Vec<BB>,
+}
+
+static TABLES: OnceLock<SliderTables> = OnceLock::new();
+
+fn tables() -> &'static SliderTables {
+    TABLES.get_or_init(SliderTables::new)
+}
+
+pub fn rook_moves_from_sq(from: Square, occupied: BB) -> BB {
+    tables().rook_moves(from, occupied)
+}
+
+pub fn bishop_moves_from_sq(from: Square, occupied: BB) -> BB {
+    tables().bishop_moves(from, occupied)
+}
+
+// squares strictly between a and b, empty when they don't share a rank, file or diagonal
+pub fn bb_squares_between(a: Square, b: Square) -> BB {
+    tables().between[a.to_usize() * BOARD_LENGTH + b.to_usize()]
+}
+
+// the whole rank, file or diagonal through a and b, empty when they don't share one
+pub fn bb_line(a: Square, b: Square) -> BB {
+    tables().line[a.to_usize() * BOARD_LENGTH + b.to_usize()]
+}
+
+fn rook_reference(from: Square, occupied: BB) -> BB {
+    horizontal_moves_from_sq(from, occupied) | vertical_moves_from_sq(from, occupied)
+}
+
+fn bishop_reference(from: Square, occupied: BB) -> BB {
+    diagonal_moves_from_sq(from, occupied) | anti_diagonal_moves_from_sq(from, occupied)
+}
+
+// seeds that find magics quickly with the generator below, one per rank
+const SEEDS: [u64; 8] = [728, 10316, 55013, 32803, 12281, 15100, 16645, 255];
+
+// xorshift64*
+struct Prng(u64);
+
+impl Prng {
+    fn rand(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(2685821657736338717)
+    }
+
+    // magics with few bits set are found a lot faster
+    fn sparse_rand(&mut self) -> u64 {
+        self.rand() & self.rand() & self.rand()
+    }
+}
+
+impl SliderTables {
+    fn new() -> SliderTables {
+        let mut attacks = Vec::new();
+        let rook = init_magics(&mut attacks, rook_reference);
+        let bishop = init_magics(&mut attacks, bishop_reference);
+
+        let mut tables = SliderTables {
+            rook,
+            bishop,
+            attacks,
+            between: vec![bitboard::EMPTY; BOARD_LENGTH * BOARD_LENGTH],
+            line: vec![bitboard::EMPTY; BOARD_LENGTH * BOARD_LENGTH],
+        };
+
+        for a in 0..BOARD_LENGTH {
+            for b in 0..BOARD_LENGTH {
+                let (sq_a, sq_b) = (Square::new(a), Square::new(b));
+                let (bb_a, bb_b) = (BB::new(sq_a), BB::new(sq_b));
+
+                let lookups: [fn(&SliderTables, Square, BB) -> BB; 2] =
+                    [SliderTables::rook_moves, SliderTables::bishop_moves];
+                for lookup in lookups {
+                    if a == b || (lookup(&tables, sq_a, bitboard::EMPTY) & bb_b).empty() {
+                        continue;
+                    }
+
+                    tables.between[a * BOARD_LENGTH + b] =
+                        lookup(&tables, sq_a, bb_b) & lookup(&tables, sq_b, bb_a);
+                    tables.line[a * BOARD_LENGTH + b] = (lookup(&tables, sq_a, bitboard::EMPTY)
+                        & lookup(&tables, sq_b, bitboard::EMPTY))
+                        | bb_a
+                        | bb_b;
+                }
+            }
+        }
+
+        tables
+    }
+
+    #[inline(always)]
+    fn rook_moves(&self, from: Square, occupied: BB) -> BB {
+        self.attacks[self.rook[from.to_usize()].index(occupied)]
+    }
+
+    #[inline(always)]
+    fn bishop_moves(&self, from: Square, occupied: BB) -> BB {
+        self.attacks[self.bishop[from.to_usize()].index(occupied)]
+    }
+}
+
+// appends the attack tables of one piece type for every square to attacks
+fn init_magics(attacks: &mut Vec<BB>, reference: fn(Square, BB) -> BB) -> [Magic; BOARD_LENGTH] {
+    let mut magics = [Magic {
+        mask: bitboard::EMPTY,
+        magic: 0,
+        shift: 0,
+        offset: 0,
+    }; BOARD_LENGTH];
+    let mut occupancies = Vec::new();
+    let mut references = Vec::new();
+    let mut epoch = Vec::new();
+    let mut attempt = 0;
+
+    for (sq_index, magic) in magics.iter_mut().enumerate() {
+        let sq = Square::new(sq_index);
+
+        // blockers on the edge don't change the attacks, unless the piece is on that edge
+        let edges = ((ROW_1 | ROW_8) & !sq.rank_mask()) | ((FILE_A | FILE_H) & !sq.file_mask());
+        magic.mask = reference(sq, bitboard::EMPTY) & !edges;
+        magic.shift = 64 - magic.mask.count_ones();
+        magic.offset = attacks.len();
+
+        // every subset of the mask with the carry rippler trick
+        occupancies.clear();
+        references.clear();
+        let mut subset = 0u64;
+        loop {
+            occupancies.push(BB(subset));
+            references.push(reference(sq, BB(subset)));
+            subset = subset.wrapping_sub(magic.mask.0) & magic.mask.0;
+            if subset == 0 {
+                break;
+            }
+        }
+
+        let size = occupancies.len();
+        attacks.resize(magic.offset + size, bitboard::EMPTY);
+
+        if cfg!(all(target_arch = "x86_64", target_feature = "bmi2")) {
+            for (occupied, reference) in occupancies.iter().zip(&references) {
+                attacks[magic.index(*occupied)] = *reference;
+            }
+            continue;
+        }
+
+        // try magics until one maps every subset either to its own entry or to an entry with the
+        // same attacks. epoch saves clearing the entries between attempts
+        epoch.clear();
+        epoch.resize(size, 0);
+        let mut prng = Prng(SEEDS[sq.rank()]);
+        loop {
+            magic.magic = prng.sparse_rand();
+            if (magic.mask.0.wrapping_mul(magic.magic) >> 56).count_ones() < 6 {
+                continue;
+            }
+
+            attempt += 1;
+            let mut found = true;
+            for (occupied, reference) in occupancies.iter().zip(&references) {
+                let index = magic.index(*occupied);
+                let entry = index - magic.offset;
+                if epoch[entry] < attempt {
+                    epoch[entry] = attempt;
+                    attacks[index] = *reference;
+                } else if attacks[index] != *reference {
+                    found = false;
+                    break;
+                }
+            }
+
+            if found {
+                break;
+            }
+        }
+    }
+
+    magics
+}
+
+#[cfg(test)]
+pub mod test_magic {
+    use std::{hint::black_box, time::Instant};
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::square::*;
+
+    #[test]
+    fn matches_reference() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..2000 {
+            // sparse boards like real positions as well as crowded ones
+            let occupied = BB(rng.gen::<u64>() & rng.gen::<u64>() & rng.gen::<u64>());
+            let crowded = BB(rng.gen::<u64>());
+            for sq_index in 0..BOARD_LENGTH {
+                let sq = Square::new(sq_index);
+                for occupied in [occupied, crowded] {
+                    assert_eq!(
+                        rook_moves_from_sq(sq, occupied),
+                        rook_reference(sq, occupied)
+                    );
+                    assert_eq!(
+                        bishop_moves_from_sq(sq, occupied),
+                        bishop_reference(sq, occupied)
+                    );
+                }
+            }
+        }
+    }
+
+    // cargo test --release bench_against_reference -- --ignored --nocapture
+    #[test]
+    #[ignore = "only needed to compare with hyperbola quintessence"]
+    fn bench_against_reference() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let boards: Vec<BB> = (0..20_000)
+            .map(|_| BB(rng.gen::<u64>() & rng.gen::<u64>()))
+            .collect();
+        // the tables are built the first time they're used, which shouldn't be timed
+        tables();
+
+        let time = |moves_from_sq: fn(Square, BB) -> BB| {
+            let start = Instant::now();
+            let mut moves = 0;
+            for occupied in &boards {
+                for sq_index in 0..BOARD_LENGTH {
+                    moves ^= moves_from_sq(Square::new(sq_index), black_box(*occupied)).0;
+                }
+            }
+            black_box(moves);
+            start.elapsed()
+        };
+        let magic = time(|sq, occupied| {
+            rook_moves_from_sq(sq, occupied) | bishop_moves_from_sq(sq, occupied)
+        });
+        let reference =
+            time(|sq, occupied| rook_reference(sq, occupied) | bishop_reference(sq, occupied));
+
+        println!(
+            "{} rook and bishop lookups, magic: {:?}, hyperbola quintessence: {:?}",
+            boards.len() * BOARD_LENGTH,
+            magic,
+            reference
+        );
+        assert!(magic < reference);
+    }
+
+    #[test]
+    fn table_size() {
+        let tables = tables();
+        assert_eq!(tables.attacks.len(), 102_400 + 5_248);
+    }
+
+    #[test]
+    fn squares_between() {
+        assert_eq!(bb_squares_between(A1, A3), BB::new(A2));
+        assert_eq!(bb_squares_between(A1, H8), BB(0x0040201008040200));
+        assert_eq!(bb_squares_between(H1, A8), BB(0x0002040810204000));
+        assert_eq!(bb_squares_between(E1, H1), BB::from_arr(&[F1, G1]));
+        assert_eq!(bb_squares_between(E4, E5), bitboard::EMPTY);
+        assert_eq!(bb_squares_between(A1, B3), bitboard::EMPTY);
+        assert_eq!(bb_squares_between(D4, D4), bitboard::EMPTY);
+        assert_eq!(bb_squares_between(H8, A1), bb_squares_between(A1, H8));
+    }
+
+    #[test]
+    fn line() {
+        assert_eq!(bb_line(A1, C3), BB(0x8040201008040201));
+        assert_eq!(bb_line(B1, G1), bitboard::ROW_1);
+        assert_eq!(bb_line(E2, E7), E1.file_mask());
+        assert_eq!(bb_line(A1, B3), bitboard::EMPTY);
+        assert_eq!(bb_line(D4, D4), bitboard::EMPTY);
+    }
+}
@@ -619,7 +619,7 @@ pub mod test_slider_attacks {
     #[test]
     fn north_west_slider_attacks_on_edge_of_board() {
         let fen = "4k3/8/8/8/B7/8/8/4K3 w - - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let game = result.unwrap();
         let (diag_attackers, _) = game.position().bb_sliders(Side::White);
@@ -705,7 +705,7 @@ pub mod test_slider_attacks {
     #[test]
     fn north_east_slider_attacks_on_edge_of_board() {
         let fen = "4k3/8/8/7B/8/8/8/4K3 w - - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let game = result.unwrap();
         let (diag_attackers, _) = game.position().bb_sliders(Side::White);
@@ -2,31 +2,27 @@ use std::ops::{Shl, Shr};
 
 use crate::{
     bitboard::{self, BB, KING_MOVES, KNIGHT_JUMPS, PAWN_CAPTURES, PAWN_PUSHES},
+    move_list::MoveList,
+    mv::{DropMove, Move},
+    piece_type::PieceType,
     side::Side,
     square::Square,
+    state::position::{Position, POCKET_PIECE_TYPES},
 };
 
-use super::slider::*;
+use super::magic::{bishop_moves_from_sq, rook_moves_from_sq};
 
 pub fn bishop_attacks(from: Square, friendly_occupied: BB, enemy_occupied: BB) -> BB {
-    let occupied = friendly_occupied | enemy_occupied;
-
-    (diagonal_moves_from_sq(from, occupied) | anti_diagonal_moves_from_sq(from, occupied))
-        & !friendly_occupied
+    bishop_moves_from_sq(from, friendly_occupied | enemy_occupied) & !friendly_occupied
 }
 
 pub fn rook_attacks(from: Square, friendly_occupied: BB, enemy_occupied: BB) -> BB {
-    let occupied = friendly_occupied | enemy_occupied;
-
-    (vertical_moves_from_sq(from, occupied) | horizontal_moves_from_sq(from, occupied))
-        & !friendly_occupied
+    rook_moves_from_sq(from, friendly_occupied | enemy_occupied) & !friendly_occupied
 }
 
 pub fn queen_attacks(from: Square, friendly_occupied: BB, enemy_occupied: BB) -> BB {
     let occupied = friendly_occupied | enemy_occupied;
-    ((vertical_moves_from_sq(from, occupied) | horizontal_moves_from_sq(from, occupied))
-        | (diagonal_moves_from_sq(from, occupied) | anti_diagonal_moves_from_sq(from, occupied)))
-        & !friendly_occupied
+    (rook_moves_from_sq(from, occupied) | bishop_moves_from_sq(from, occupied)) & !friendly_occupied
 }
 
 pub fn knight_attacks(from: Square, friendly_occupied: BB) -> BB {
@@ -109,24 +105,16 @@ pub fn is_double_pawn_push(from: Square, to: Square, side: Side) -> bool {
 }
 
 pub fn bishop_captures(from: Square, friendly_occupied: BB, enemy_occupied: BB) -> BB {
-    let occupied = friendly_occupied | enemy_occupied;
-
-    (diagonal_moves_from_sq(from, occupied) | anti_diagonal_moves_from_sq(from, occupied))
-        & enemy_occupied
+    bishop_moves_from_sq(from, friendly_occupied | enemy_occupied) & enemy_occupied
 }
 
 pub fn rook_captures(from: Square, friendly_occupied: BB, enemy_occupied: BB) -> BB {
-    let occupied = friendly_occupied | enemy_occupied;
-
-    (vertical_moves_from_sq(from, occupied) | horizontal_moves_from_sq(from, occupied))
-        & enemy_occupied
+    rook_moves_from_sq(from, friendly_occupied | enemy_occupied) & enemy_occupied
 }
 
 pub fn queen_captures(from: Square, friendly_occupied: BB, enemy_occupied: BB) -> BB {
     let occupied = friendly_occupied | enemy_occupied;
-    ((vertical_moves_from_sq(from, occupied) | horizontal_moves_from_sq(from, occupied))
-        | (diagonal_moves_from_sq(from, occupied) | anti_diagonal_moves_from_sq(from, occupied)))
-        & enemy_occupied
+    (rook_moves_from_sq(from, occupied) | bishop_moves_from_sq(from, occupied)) & enemy_occupied
 }
 
 pub fn knight_captures(from: Square, enemy_occupied: BB) -> BB {
@@ -137,6 +125,23 @@ pub fn king_captures(from: Square, enemy_occupied: BB) -> BB {
     KING_MOVES[from.to_usize()] & enemy_occupied
 }
 
+// crazyhouse drops of the pieces in the side's pocket on the target squares, which should be
+// empty. pawns can't be dropped on the first or last rank
+pub fn push_drops(position: &Position, side: Side, targets: BB, mv_list: &mut MoveList) {
+    for piece_type in POCKET_PIECE_TYPES {
+        if position.pocket_count(side, piece_type) == 0 {
+            continue;
+        }
+        let targets = match piece_type {
+            PieceType::Pawn => targets & !bitboard::END_ROWS,
+            _ => targets,
+        };
+        for to in targets.iter() {
+            mv_list.push_move(Move::Drop(DropMove::new(to, piece_type)));
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod queen_tests {
     use super::*;
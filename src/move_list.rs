@@ -15,6 +15,10 @@ impl MoveList {
         &self.0
     }
 
+    pub fn into_list(self) -> Vec<Move> {
+        self.0
+    }
+
     pub fn insert_moves<F: Fn(Square, Square) -> Move>(
         &mut self,
         from: Square,
@@ -25,6 +25,7 @@ pub enum Move {
     Castle(Castle),
     Promotion(PromotionMove),
     EnPassant(EncodedMove),
+    Drop(DropMove),
 }
 
 impl fmt::Display for Move {
@@ -38,6 +39,7 @@ impl fmt::Display for Move {
             | Move::EnPassant(mv) => write!(f, "{}", mv),
             Move::Castle(castle_mv) => write!(f, "{}", castle_mv),
             Move::Promotion(promote_mv) => write!(f, "{}", promote_mv),
+            Move::Drop(drop_mv) => write!(f, "{}", drop_mv),
         }
     }
 }
@@ -53,6 +55,7 @@ impl Move {
             | Move::EnPassant(mv) => mv.to_algebra(),
             Move::Castle(castle_mv) => castle_mv.to_string(),
             Move::Promotion(promote_mv) => promote_mv.to_algebra(),
+            Move::Drop(drop_mv) => drop_mv.to_string(),
         }
     }
 }
@@ -177,6 +180,33 @@ impl fmt::Display for PromotionMove {
     }
 }
 
+// a piece taken from the pocket and put on an empty square, only in crazyhouse. there is no from
+// square so only the piece type and the to square are encoded
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct DropMove(u16);
+
+impl DropMove {
+    pub fn new(to: Square, piece_type: PieceType) -> DropMove {
+        DropMove(piece_type.to_u16() << 6 | to.to_u16())
+    }
+
+    pub fn piece_type(self) -> PieceType {
+        PIECE_TYPE_MAP[((self.0 >> 6) & 7) as usize]
+    }
+
+    pub fn to(self) -> Square {
+        Square::new((self.0 & 63) as square::Internal)
+    }
+}
+
+// the piece is always uppercase, like `N@f3` or `P@e4`, the same in uci and san
+impl fmt::Display for DropMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let piece = self.piece_type().to_char().to_ascii_uppercase();
+        write!(f, "{}@{}", piece, self.to())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -221,6 +251,10 @@ mod test {
                 "e8=Q",
             ),
             (Move::Castle(Castle::Queenside), "0-0-0"),
+            (
+                Move::Drop(DropMove::new(square::F3, PieceType::Knight)),
+                "N@f3",
+            ),
             (
                 Move::Pawn(EncodedMove::new(
                     square::E2,
@@ -0,0 +1,271 @@
+pub mod accumulator;
+mod simd;
+
+use std::{fs, sync::Arc};
+
+use crate::{piece_type::PieceType, side::Side, square::Square};
+
+// halfka features, every (king square, piece side, piece type, piece square) combination seen from
+// one side's perspective. black's perspective is flipped vertically so both sides share weights
+pub const FEATURE_COUNT: usize = 64 * 2 * 6 * 64;
+
+const MAGIC: &[u8; 4] = b"CRNN";
+const HEADER_SIZE: usize = 8;
+
+// quantisation of the feature transformer and output layer, and the scale that turns the output
+// into centipawns
+const QA: i16 = 255;
+const QB: i32 = 64;
+const SCALE: i32 = 400;
+
+// network file layout, all little endian
+// magic "CRNN", u32 hidden size
+// feature transformer weights [FEATURE_COUNT][hidden size] i16
+// feature transformer biases [hidden size] i16
+// output weights [2 * hidden size] i16, side to move's half first
+// output bias i32
+#[derive(Debug, PartialEq)]
+pub struct Network {
+    hidden_size: usize,
+    ft_weights: Vec<i16>,
+    ft_biases: Vec<i16>,
+    out_weights: Vec<i16>,
+    out_bias: i32,
+}
+
+impl Network {
+    pub fn new(
+        hidden_size: usize,
+        ft_weights: Vec<i16>,
+        ft_biases: Vec<i16>,
+        out_weights: Vec<i16>,
+        out_bias: i32,
+    ) -> Result<Network, String> {
+        if hidden_size == 0 {
+            return Err("network hidden size can't be zero".to_string());
+        }
+        if ft_weights.len() != FEATURE_COUNT * hidden_size
+            || ft_biases.len() != hidden_size
+            || out_weights.len() != 2 * hidden_size
+        {
+            return Err(format!(
+                "network weights don't match hidden size {}",
+                hidden_size
+            ));
+        }
+
+        Ok(Network {
+            hidden_size,
+            ft_weights,
+            ft_biases,
+            out_weights,
+            out_bias,
+        })
+    }
+
+    pub fn load(path: &str) -> Result<Arc<Network>, String> {
+        let bytes = fs::read(path)
+            .map_err(|err| format!("unable to read network from {}: {}", path, err))?;
+
+        Ok(Arc::new(Network::from_bytes(&bytes)?))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Network, String> {
+        if bytes.len() < HEADER_SIZE || &bytes[..4] != MAGIC {
+            return Err("not a network file".to_string());
+        }
+        let hidden_size = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+
+        let i16_count = (FEATURE_COUNT + 1 + 2) * hidden_size;
+        if bytes.len() != HEADER_SIZE + i16_count * 2 + 4 {
+            return Err(format!(
+                "network file has the wrong size for hidden size {}",
+                hidden_size
+            ));
+        }
+
+        let mut values = bytes[HEADER_SIZE..HEADER_SIZE + i16_count * 2]
+            .chunks_exact(2)
+            .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]));
+        let ft_weights = values.by_ref().take(FEATURE_COUNT * hidden_size).collect();
+        let ft_biases = values.by_ref().take(hidden_size).collect();
+        let out_weights = values.collect();
+        let out_bias = i32::from_le_bytes(bytes[bytes.len() - 4..].try_into().unwrap());
+
+        Network::new(hidden_size, ft_weights, ft_biases, out_weights, out_bias)
+    }
+
+    fn feature_weights(&self, feature: usize) -> &[i16] {
+        &self.ft_weights[feature * self.hidden_size..(feature + 1) * self.hidden_size]
+    }
+
+    fn ft_biases(&self) -> &[i16] {
+        &self.ft_biases
+    }
+
+    // eval from the side to move's perspective, given the accumulators of both sides
+    fn output(&self, us: &[i16], them: &[i16]) -> i32 {
+        let (us_weights, them_weights) = self.out_weights.split_at(self.hidden_size);
+        let sum = simd::clipped_relu_dot(us, us_weights, QA)
+            + simd::clipped_relu_dot(them, them_weights, QA)
+            + self.out_bias;
+
+        sum / QA as i32 * SCALE / QB
+    }
+}
+
+fn orient(perspective: Side, sq: Square) -> usize {
+    match perspective {
+        Side::White => sq.to_usize(),
+        Side::Black => sq.to_usize() ^ 56,
+    }
+}
+
+pub fn feature_index(
+    perspective: Side,
+    king_sq: Square,
+    side: Side,
+    piece_type: PieceType,
+    sq: Square,
+) -> usize {
+    let relative_side = (side != perspective) as usize;
+    ((orient(perspective, king_sq) * 2 + relative_side) * 6 + piece_type.to_usize()) * 64
+        + orient(perspective, sq)
+}
+
+#[cfg(test)]
+pub mod test_nnue {
+    use super::*;
+    use crate::{fen::STARTING_POSITION_FEN, game::Game, uci::algebra_to_move};
+
+    use self::accumulator::{Accumulator, AccumulatorStack};
+
+    // small network with deterministic pseudo random weights
+    pub fn test_network() -> Arc<Network> {
+        let hidden_size = 16;
+        let mut seed: u32 = 12345;
+        let mut next = |range: i32| -> i16 {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            ((seed >> 16) as i32 % (2 * range + 1) - range) as i16
+        };
+
+        let ft_weights = (0..FEATURE_COUNT * hidden_size).map(|_| next(40)).collect();
+        let ft_biases = (0..hidden_size).map(|_| next(40)).collect();
+        let out_weights = (0..2 * hidden_size).map(|_| next(60)).collect();
+        let network = Network::new(hidden_size, ft_weights, ft_biases, out_weights, 100);
+        assert!(network.is_ok());
+
+        Arc::new(network.unwrap())
+    }
+
+    // the engine only reads networks, writing one is only needed to test reading it
+    fn to_bytes(network: &Network) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend((network.hidden_size as u32).to_le_bytes());
+        for value in network
+            .ft_weights
+            .iter()
+            .chain(&network.ft_biases)
+            .chain(&network.out_weights)
+        {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes.extend(network.out_bias.to_le_bytes());
+
+        bytes
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let network = test_network();
+        let result = Network::from_bytes(&to_bytes(&network));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), *network);
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        let network = test_network();
+        let mut bytes = to_bytes(&network);
+        bytes.pop();
+        assert!(Network::from_bytes(&bytes).is_err());
+        assert!(Network::from_bytes(b"NNUE").is_err());
+    }
+
+    #[test]
+    fn incremental_matches_refresh() {
+        let network = test_network();
+        let result = Game::from_fen(STARTING_POSITION_FEN);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let mut accumulators = AccumulatorStack::new(network.clone(), game.position());
+
+        // covers quiet moves, captures, castling, en passant, king moves and a promotion
+        let moves = [
+            "e2e4", "d7d5", "e4d5", "g8f6", "g1f3", "f6d5", "f1c4", "c7c5", "e1g1", "c5c4", "b2b4",
+            "c4b3", "a2b3", "e7e5", "g1h1", "e8e7", "d2d4", "e5d4", "c2c4", "d4c3", "b1c3", "c8g4",
+            "c3d5", "e7d6", "d5b6", "a7b6", "h2h3", "a8a1", "h3g4", "a1c1",
+        ];
+        for move_notation in moves {
+            let mv = algebra_to_move(move_notation, &game);
+            assert!(mv.is_ok(), "{}", move_notation);
+            game.make_move(mv.unwrap());
+            accumulators.push(game.position());
+
+            assert!(
+                *accumulators.top() == Accumulator::new(network.clone(), game.position()),
+                "accumulator differs after {}",
+                move_notation
+            );
+        }
+    }
+
+    #[test]
+    fn pop_restores_accumulator() {
+        let network = test_network();
+        let fen = "r3k2r/pPppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let mut accumulators = AccumulatorStack::new(network.clone(), game.position());
+        let before = accumulators.top().clone();
+
+        // each push after the first reuses the entry the last one left behind
+        for move_notation in ["e1g1", "e1c1", "b7a8q", "b7b8n", "e5f7", "d5e6", "e1d1"] {
+            let mv = algebra_to_move(move_notation, &game);
+            assert!(mv.is_ok(), "{}", move_notation);
+            let mv = mv.unwrap();
+            let prev_state = game.state().encode();
+            let capture = game.make_move(mv);
+            accumulators.push(game.position());
+            assert!(
+                *accumulators.top() == Accumulator::new(network.clone(), game.position()),
+                "{}",
+                move_notation
+            );
+
+            game.unmake_move(mv, capture, prev_state);
+            accumulators.pop();
+            assert!(*accumulators.top() == before, "{}", move_notation);
+        }
+    }
+
+    #[test]
+    fn mirrored_positions_evaluate_the_same() {
+        let network = test_network();
+        let fens = [
+            "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3",
+            "rnbqkb1r/pppp1ppp/5n2/4p3/4P3/2N5/PPPP1PPP/R1BQKBNR b KQkq - 2 3",
+        ];
+        let evals: Vec<i32> = fens
+            .iter()
+            .map(|fen| {
+                let game = Game::from_fen(fen).unwrap();
+                let side = game.state().side_to_move();
+                Accumulator::new(network.clone(), game.position()).evaluate(side)
+            })
+            .collect();
+
+        assert_eq!(evals[0], evals[1]);
+    }
+}
@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use crate::{
+    side::{Side, SIDE_MAP},
+    square::Square,
+    state::position::{Board, Position},
+};
+
+use super::{feature_index, simd, Network};
+
+// first layer outputs for both perspectives
+#[derive(Clone)]
+pub struct Accumulator {
+    network: Arc<Network>,
+    values: [Vec<i16>; 2],
+    // king squares the values were built with, a perspective is refreshed when its king moves
+    king_sqs: [Square; 2],
+}
+
+impl PartialEq for Accumulator {
+    fn eq(&self, other: &Accumulator) -> bool {
+        Arc::ptr_eq(&self.network, &other.network)
+            && self.values == other.values
+            && self.king_sqs == other.king_sqs
+    }
+}
+
+impl Accumulator {
+    pub fn new(network: Arc<Network>, position: &Position) -> Accumulator {
+        let mut accumulator = Accumulator {
+            values: [network.ft_biases().to_vec(), network.ft_biases().to_vec()],
+            network,
+            king_sqs: [position.king_sq(Side::White), position.king_sq(Side::Black)],
+        };
+        accumulator.refresh(Side::White, position.board());
+        accumulator.refresh(Side::Black, position.board());
+
+        accumulator
+    }
+
+    fn refresh(&mut self, perspective: Side, board: &Board) {
+        let values = &mut self.values[perspective.to_usize()];
+        values.copy_from_slice(self.network.ft_biases());
+
+        let king_sq = self.king_sqs[perspective.to_usize()];
+        for (i, piece) in board.iter().enumerate() {
+            if let Some(piece) = piece {
+                let (side, piece_type) = piece.decode();
+                let feature = feature_index(perspective, king_sq, side, piece_type, Square(i));
+                simd::add_assign(values, self.network.feature_weights(feature));
+            }
+        }
+    }
+
+    // takes on the values of another accumulator without allocating
+    fn copy_from(&mut self, other: &Accumulator) {
+        self.network = other.network.clone();
+        for side in SIDE_MAP {
+            self.values[side.to_usize()].copy_from_slice(&other.values[side.to_usize()]);
+        }
+        self.king_sqs = other.king_sqs;
+    }
+
+    // brings the values from the position on `prev_board` to the one on `position`, only the
+    // squares whose piece changed are updated unless the perspective's king moved
+    fn update(&mut self, prev_board: &Board, position: &Position) {
+        let board = position.board();
+        for perspective in SIDE_MAP {
+            let king_sq = position.king_sq(perspective);
+            if king_sq != self.king_sqs[perspective.to_usize()] {
+                self.king_sqs[perspective.to_usize()] = king_sq;
+                self.refresh(perspective, board);
+                continue;
+            }
+
+            let values = &mut self.values[perspective.to_usize()];
+            for (i, (prev_piece, piece)) in prev_board.iter().zip(board).enumerate() {
+                if prev_piece == piece {
+                    continue;
+                }
+                if let Some(prev_piece) = prev_piece {
+                    let (side, piece_type) = prev_piece.decode();
+                    let feature = feature_index(perspective, king_sq, side, piece_type, Square(i));
+                    simd::sub_assign(values, self.network.feature_weights(feature));
+                }
+                if let Some(piece) = piece {
+                    let (side, piece_type) = piece.decode();
+                    let feature = feature_index(perspective, king_sq, side, piece_type, Square(i));
+                    simd::add_assign(values, self.network.feature_weights(feature));
+                }
+            }
+        }
+    }
+
+    pub fn evaluate(&self, side_to_move: Side) -> i32 {
+        self.network.output(
+            &self.values[side_to_move.to_usize()],
+            &self.values[side_to_move.opposite().to_usize()],
+        )
+    }
+}
+
+// the accumulators of the positions along the line being searched, kept by the searcher so the
+// positions themselves don't carry any network state. the entries above the top are kept around
+// so pushing doesn't allocate once the search has been as deep before
+pub struct AccumulatorStack {
+    entries: Vec<(Accumulator, Board)>,
+    len: usize,
+}
+
+impl AccumulatorStack {
+    pub fn new(network: Arc<Network>, position: &Position) -> AccumulatorStack {
+        AccumulatorStack {
+            entries: vec![(Accumulator::new(network, position), *position.board())],
+            len: 1,
+        }
+    }
+
+    // adds the accumulator of the position a move was just made to
+    pub fn push(&mut self, position: &Position) {
+        if self.len == self.entries.len() {
+            let top = self.entries[self.len - 1].clone();
+            self.entries.push(top);
+        } else {
+            let (done, rest) = self.entries.split_at_mut(self.len);
+            let (top, top_board) = &done[self.len - 1];
+            rest[0].0.copy_from(top);
+            rest[0].1 = *top_board;
+        }
+
+        let (accumulator, board) = &mut self.entries[self.len];
+        accumulator.update(board, position);
+        *board = *position.board();
+        self.len += 1;
+    }
+
+    // goes back to the accumulator from before the last push, after its move is unmade
+    pub fn pop(&mut self) {
+        debug_assert!(self.len > 1, "popped the root accumulator");
+        self.len -= 1;
+    }
+
+    pub fn top(&self) -> &Accumulator {
+        &self.entries[self.len - 1].0
+    }
+}
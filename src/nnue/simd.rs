@@ -0,0 +1,172 @@
+// the accumulator updates and the output layer with avx2 on x86_64 when the cpu supports it,
+// picked at runtime. everywhere else, and for the values left over after the last full vector,
+// plain loops are used
+
+fn add_assign_scalar(values: &mut [i16], weights: &[i16]) {
+    for (value, weight) in values.iter_mut().zip(weights) {
+        *value = value.wrapping_add(*weight);
+    }
+}
+
+fn sub_assign_scalar(values: &mut [i16], weights: &[i16]) {
+    for (value, weight) in values.iter_mut().zip(weights) {
+        *value = value.wrapping_sub(*weight);
+    }
+}
+
+fn clipped_relu_dot_scalar(values: &[i16], weights: &[i16], max: i16) -> i32 {
+    values
+        .iter()
+        .zip(weights)
+        .map(|(value, weight)| (*value).clamp(0, max) as i32 * *weight as i32)
+        .sum()
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use std::arch::x86_64::*;
+
+    // i16 lanes in a 256 bit register
+    const LANES: usize = 16;
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn add_assign(values: &mut [i16], weights: &[i16]) {
+        let len = values.len().min(weights.len());
+        let vectorised = len - len % LANES;
+        for i in (0..vectorised).step_by(LANES) {
+            let value = _mm256_loadu_si256(values.as_ptr().add(i) as *const __m256i);
+            let weight = _mm256_loadu_si256(weights.as_ptr().add(i) as *const __m256i);
+            _mm256_storeu_si256(
+                values.as_mut_ptr().add(i) as *mut __m256i,
+                _mm256_add_epi16(value, weight),
+            );
+        }
+
+        super::add_assign_scalar(&mut values[vectorised..len], &weights[vectorised..len])
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sub_assign(values: &mut [i16], weights: &[i16]) {
+        let len = values.len().min(weights.len());
+        let vectorised = len - len % LANES;
+        for i in (0..vectorised).step_by(LANES) {
+            let value = _mm256_loadu_si256(values.as_ptr().add(i) as *const __m256i);
+            let weight = _mm256_loadu_si256(weights.as_ptr().add(i) as *const __m256i);
+            _mm256_storeu_si256(
+                values.as_mut_ptr().add(i) as *mut __m256i,
+                _mm256_sub_epi16(value, weight),
+            );
+        }
+
+        super::sub_assign_scalar(&mut values[vectorised..len], &weights[vectorised..len])
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn clipped_relu_dot(values: &[i16], weights: &[i16], max: i16) -> i32 {
+        let len = values.len().min(weights.len());
+        let vectorised = len - len % LANES;
+        let zero = _mm256_setzero_si256();
+        let max_vector = _mm256_set1_epi16(max);
+
+        // madd multiplies the i16 lanes into i32s and adds neighbouring pairs, so the sums are
+        // kept in eight i32 lanes
+        let mut sum = _mm256_setzero_si256();
+        for i in (0..vectorised).step_by(LANES) {
+            let value = _mm256_loadu_si256(values.as_ptr().add(i) as *const __m256i);
+            let weight = _mm256_loadu_si256(weights.as_ptr().add(i) as *const __m256i);
+            let clipped = _mm256_min_epi16(_mm256_max_epi16(value, zero), max_vector);
+            sum = _mm256_add_epi32(sum, _mm256_madd_epi16(clipped, weight));
+        }
+
+        let halves = _mm_add_epi32(
+            _mm256_castsi256_si128(sum),
+            _mm256_extracti128_si256(sum, 1),
+        );
+        let pairs = _mm_add_epi32(halves, _mm_shuffle_epi32(halves, 0b01_00_11_10));
+        let total = _mm_add_epi32(pairs, _mm_shuffle_epi32(pairs, 0b10_11_00_01));
+
+        _mm_cvtsi128_si32(total)
+            + super::clipped_relu_dot_scalar(
+                &values[vectorised..len],
+                &weights[vectorised..len],
+                max,
+            )
+    }
+}
+
+pub fn add_assign(values: &mut [i16], weights: &[i16]) {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") {
+        // safe because avx2 support was checked above
+        return unsafe { avx2::add_assign(values, weights) };
+    }
+
+    add_assign_scalar(values, weights)
+}
+
+pub fn sub_assign(values: &mut [i16], weights: &[i16]) {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") {
+        // safe because avx2 support was checked above
+        return unsafe { avx2::sub_assign(values, weights) };
+    }
+
+    sub_assign_scalar(values, weights)
+}
+
+// sum of clamp(value, 0, max) * weight
+pub fn clipped_relu_dot(values: &[i16], weights: &[i16], max: i16) -> i32 {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") {
+        // safe because avx2 support was checked above
+        return unsafe { avx2::clipped_relu_dot(values, weights, max) };
+    }
+
+    clipped_relu_dot_scalar(values, weights, max)
+}
+
+#[cfg(test)]
+pub mod test_simd {
+    use super::*;
+
+    #[test]
+    fn add_sub() {
+        let mut values = vec![1i16; 40];
+        let weights: Vec<i16> = (0..40).collect();
+        add_assign(&mut values, &weights);
+        assert_eq!(values[39], 40);
+        sub_assign(&mut values, &weights);
+        assert_eq!(values, vec![1i16; 40]);
+    }
+
+    #[test]
+    fn dot_is_clipped() {
+        let values = [-5, 3, 300];
+        let weights = [2, 2, 2];
+        assert_eq!(clipped_relu_dot(&values, &weights, 255), 6 + 510);
+    }
+
+    #[test]
+    fn matches_scalar() {
+        // long enough for two full vectors and a remainder
+        let values: Vec<i16> = (0..37).map(|i| i * 23 - 300).collect();
+        let weights: Vec<i16> = (0..37).map(|i| 500 - i * 31).collect();
+
+        let mut added = values.clone();
+        add_assign(&mut added, &weights);
+        let mut added_scalar = values.clone();
+        add_assign_scalar(&mut added_scalar, &weights);
+        assert_eq!(added, added_scalar);
+
+        let mut subtracted = values.clone();
+        sub_assign(&mut subtracted, &weights);
+        let mut subtracted_scalar = values.clone();
+        sub_assign_scalar(&mut subtracted_scalar, &weights);
+        assert_eq!(subtracted, subtracted_scalar);
+
+        assert_eq!(
+            clipped_relu_dot(&values, &weights, 255),
+            clipped_relu_dot_scalar(&values, &weights, 255)
+        );
+    }
+}
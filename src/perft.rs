@@ -1,139 +1,400 @@
-use std::collections::HashMap;
+use std::{
+    fs,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
 
 use crate::{
+    bitboard::BB,
+    epd::read_epd,
     game::Game,
-    move_gen::{
-        check_legal::LegalCheckPreprocessing, checkers_pinners_pinned,
-        controlled_squares_with_king_gone,
-    },
+    move_gen::check_legal::LegalCheckPreprocessing,
+    mv::{Decode, Move},
+    square::Square,
 };
 
-type ZobristMap = HashMap<u64, u32>;
+pub const DEFAULT_PERFT_HASH_MB: usize = 64;
 
-pub fn count_moves_debug(depth: u32, game: &mut Game) -> (u32, u32) {
-    let mut count: u32 = 0;
+// subtree node counts shared by the perft threads, keyed by zobrist and depth. each entry is the
+// key xored with the data next to the data, so an entry torn by two threads writing at the same
+// time fails the key check instead of giving a wrong count. the depth is in the low byte of the
+// data and the node count above it
+pub struct PerftTable {
+    entries: Vec<[AtomicU64; 2]>,
+}
 
-    let side = game.state().side_to_move();
-    let pseudo_legal_mv_list = game.pseudo_legal_moves(side);
-    let (checkers, pinners, pinned) = checkers_pinners_pinned(game.position(), side.opposite());
-    let controlled_squares_with_king_gone_bb =
-        controlled_squares_with_king_gone(game.mut_position(), side.opposite());
-    let legal_check_preprocessing = LegalCheckPreprocessing::new(
-        checkers,
-        pinners,
-        pinned,
-        controlled_squares_with_king_gone_bb,
-    );
+impl PerftTable {
+    pub fn new(hash_mb: usize) -> PerftTable {
+        let max_entries = (hash_mb.max(1) << 20) / std::mem::size_of::<[AtomicU64; 2]>();
+        // a power of two so the index is a mask of the zobrist
+        let len = 1 << max_entries.ilog2();
+        PerftTable {
+            entries: (0..len)
+                .map(|_| [AtomicU64::new(0), AtomicU64::new(0)])
+                .collect(),
+        }
+    }
 
-    let prev_state = game.state().encode();
+    // the counts depend on the castle files which aren't in the zobrist, so the table is cleared
+    // before each new position
+    pub fn clear(&self) {
+        for [key, data] in self.entries.iter() {
+            key.store(0, Ordering::Relaxed);
+            data.store(0, Ordering::Relaxed);
+        }
+    }
 
-    let mut zobrist_map: ZobristMap = HashMap::new();
+    fn entry(&self, zobrist: u64) -> &[AtomicU64; 2] {
+        &self.entries[zobrist as usize & (self.entries.len() - 1)]
+    }
 
-    for mv in pseudo_legal_mv_list.list().iter() {
-        if !game.is_legal(*mv, &legal_check_preprocessing) {
-            continue;
-        }
+    fn probe(&self, zobrist: u64, depth: u32) -> Option<u64> {
+        let [key, data] = self.entry(zobrist);
+        let data = data.load(Ordering::Relaxed);
+        let found = key.load(Ordering::Relaxed) ^ data == zobrist && data & 0xff == depth as u64;
+        found.then_some(data >> 8)
+    }
 
-        let zobrist_count_before_move = zobrist_map.len();
+    fn store(&self, zobrist: u64, depth: u32, nodes: u64) {
+        let [key, data] = self.entry(zobrist);
+        let new_data = nodes << 8 | depth as u64;
+        key.store(zobrist ^ new_data, Ordering::Relaxed);
+        data.store(new_data, Ordering::Relaxed);
+    }
+}
 
-        let capture = game.make_move(*mv);
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
 
-        let sub_nodes = count_moves(depth - 1, game, &mut zobrist_map);
-        count += sub_nodes;
+pub fn nps(nodes: u64, elapsed: Duration) -> u64 {
+    (nodes as f64 / elapsed.as_secs_f64().max(0.001)) as u64
+}
 
-        println!(
-            "{}: {} {}",
-            mv,
-            sub_nodes,
-            zobrist_map.len() - zobrist_count_before_move
-        );
+// the breakdown of the moves played at one depth, in the same columns as the published perft
+// results. a check is discovered when a piece other than the one that moved gives it, double
+// checks are only counted as double checks
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PerftStats {
+    pub nodes: u64,
+    pub captures: u64,
+    pub en_passants: u64,
+    pub castles: u64,
+    pub promotions: u64,
+    pub checks: u64,
+    pub discovered_checks: u64,
+    pub double_checks: u64,
+    pub checkmates: u64,
+}
 
-        game.unmake_move(*mv, capture, prev_state)
+pub fn perft(game: &mut Game, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
     }
 
-    let total_zobrist_collisions: u32 = zobrist_map.values().sum();
-    println!(
-        "\ntotal nodes: {}\ntotal zobrist hashes: {}\ntotal zobrist collisions: {}",
-        count,
-        zobrist_map.len(),
-        total_zobrist_collisions
-    );
+    let legal_moves = game.legal_moves();
+    if depth == 1 {
+        return legal_moves.len() as u64;
+    }
+
+    let prev_state = game.state().encode();
+    let mut count = 0;
+    for mv in legal_moves {
+        let capture = game.make_move(mv);
+        count += perft(game, depth - 1);
+        game.unmake_move(mv, capture, prev_state);
+    }
+
+    count
+}
+
+// the node count under each legal move on one thread without the table, to check the parallel
+// split against
+#[cfg(test)]
+pub fn divide(game: &mut Game, depth: u32) -> Vec<(Move, u64)> {
+    let prev_state = game.state().encode();
+    let mut counts = vec![];
+    for mv in game.legal_moves() {
+        let capture = game.make_move(mv);
+        counts.push((mv, perft(game, depth.saturating_sub(1))));
+        game.unmake_move(mv, capture, prev_state);
+    }
 
-    (count, zobrist_map.len() as u32 + total_zobrist_collisions)
+    counts
+}
+
+fn perft_hashed(game: &mut Game, depth: u32, table: &PerftTable) -> u64 {
+    if depth <= 1 {
+        return perft(game, depth);
+    }
+
+    let zobrist = game.state().zobrist().to_u64();
+    if let Some(nodes) = table.probe(zobrist, depth) {
+        return nodes;
+    }
+
+    let prev_state = game.state().encode();
+    let mut count = 0;
+    for mv in game.legal_moves() {
+        let capture = game.make_move(mv);
+        count += perft_hashed(game, depth - 1, table);
+        game.unmake_move(mv, capture, prev_state);
+    }
+
+    table.store(zobrist, depth, count);
+    count
 }
 
-fn count_moves(depth: u32, game: &mut Game, zobrist_map: &mut ZobristMap) -> u32 {
+// divide with the root moves handed out to `threads` threads, which all share the table. the
+// counts are in the same order as `divide`
+pub fn parallel_divide(
+    game: &Game,
+    depth: u32,
+    threads: usize,
+    table: &PerftTable,
+) -> Vec<(Move, u64)> {
+    let root_moves = game.clone().legal_moves();
+    let next_move = AtomicUsize::new(0);
+
+    let mut counts: Vec<(usize, u64)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads.clamp(1, root_moves.len().max(1)))
+            .map(|_| {
+                let (root_moves, next_move) = (&root_moves, &next_move);
+                scope.spawn(move || {
+                    let mut game = game.clone();
+                    let prev_state = game.state().encode();
+                    let mut counts = vec![];
+                    loop {
+                        let i = next_move.fetch_add(1, Ordering::Relaxed);
+                        let Some(mv) = root_moves.get(i) else {
+                            return counts;
+                        };
+                        let capture = game.make_move(*mv);
+                        counts.push((i, perft_hashed(&mut game, depth.saturating_sub(1), table)));
+                        game.unmake_move(*mv, capture, prev_state);
+                    }
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    counts.sort_unstable();
+    counts
+        .into_iter()
+        .map(|(i, nodes)| (root_moves[i], nodes))
+        .collect()
+}
+
+pub fn parallel_perft(game: &Game, depth: u32, threads: usize, table: &PerftTable) -> u64 {
     if depth == 0 {
-        zobrist_map
-            .entry(game.state().zobrist().to_u64())
-            .and_modify(|v| *v += 1)
-            .or_insert(0);
         return 1;
     }
 
-    let mut count: u32 = 0;
+    parallel_divide(game, depth, threads, table)
+        .iter()
+        .map(|(_, nodes)| nodes)
+        .sum()
+}
+
+// the stats for every depth up to `depth`, the first entry is depth 1
+pub fn perft_stats(game: &mut Game, depth: u32) -> Vec<PerftStats> {
+    let mut stats = vec![PerftStats::default(); depth as usize];
+    if depth > 0 {
+        collect_stats(game, 0, &mut stats);
+    }
 
+    stats
+}
+
+fn collect_stats(game: &mut Game, ply: usize, stats: &mut [PerftStats]) {
     let side = game.state().side_to_move();
-    let pseudo_legal_mv_list = game.pseudo_legal_moves(side);
-    let (checkers, pinners, pinned) = checkers_pinners_pinned(game.position(), side.opposite());
-    let attacked_squares_with_king_gone_bb =
-        controlled_squares_with_king_gone(game.mut_position(), side.opposite());
-    let legal_check_preprocessing = LegalCheckPreprocessing::new(
-        checkers,
-        pinners,
-        pinned,
-        attacked_squares_with_king_gone_bb,
-    );
     let prev_state = game.state().encode();
 
-    for mv in pseudo_legal_mv_list.list().iter() {
-        if !game.is_legal(*mv, &legal_check_preprocessing) {
-            continue;
+    for mv in game.legal_moves() {
+        let ply_stats = &mut stats[ply];
+        ply_stats.nodes += 1;
+
+        // the square of the piece that moved, which is the rook for a castle
+        let moved_to: Square = match mv {
+            Move::King(mv)
+            | Move::Rook(mv)
+            | Move::Pawn(mv)
+            | Move::DoublePawnPush(mv)
+            | Move::Piece(mv) => mv.decode_into_squares().1,
+            Move::EnPassant(mv) => {
+                ply_stats.en_passants += 1;
+                mv.decode_into_squares().1
+            }
+            Move::Castle(castle) => {
+                ply_stats.castles += 1;
+                game.state().castle_files().rook_squares(side, castle).1
+            }
+            Move::Promotion(mv) => {
+                ply_stats.promotions += 1;
+                mv.decode_into_squares().1
+            }
+            Move::Drop(mv) => mv.to(),
+        };
+
+        let capture = game.make_move(mv);
+        if capture.is_some() {
+            ply_stats.captures += 1;
         }
 
-        let capture = game.make_move(*mv);
-        count += count_moves(depth - 1, game, zobrist_map);
+        let opponent_check_preprocessing = LegalCheckPreprocessing::from(game, side.opposite());
+        let checkers = opponent_check_preprocessing.checkers();
+        if checkers.not_empty() {
+            ply_stats.checks += 1;
+            if checkers.count_ones() > 1 {
+                ply_stats.double_checks += 1;
+            } else if (checkers & !BB::new(moved_to)).not_empty() {
+                ply_stats.discovered_checks += 1;
+            }
+            if game.is_checkmate(&opponent_check_preprocessing) {
+                ply_stats.checkmates += 1;
+            }
+        }
 
-        game.unmake_move(*mv, capture, prev_state);
+        if ply + 1 < stats.len() {
+            collect_stats(game, ply + 1, stats);
+        }
+        game.unmake_move(mv, capture, prev_state);
     }
+}
 
-    count
+fn print_suite_usage() {
+    println!("usage: rust_engine perft <epd file> [--depth <n>] [--threads <n>] [--hash <mb>]");
+}
+
+// checks the D1, D2 ... node counts of every position in an epd file, up to `--depth` when it's
+// given
+pub fn run_suite(args: &[String]) {
+    let mut path = None;
+    let mut max_depth = u32::MAX;
+    let mut threads = default_threads();
+    let mut hash_mb = DEFAULT_PERFT_HASH_MB;
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--depth" => match args_iter.next().map(|n| n.parse()) {
+                Some(Ok(n)) => max_depth = n,
+                _ => return print_suite_usage(),
+            },
+            "--threads" => match args_iter.next().map(|n| n.parse()) {
+                Some(Ok(n)) if n > 0 => threads = n,
+                _ => return print_suite_usage(),
+            },
+            "--hash" => match args_iter.next().map(|n| n.parse()) {
+                Some(Ok(n)) => hash_mb = n,
+                _ => return print_suite_usage(),
+            },
+            path_arg if path.is_none() => path = Some(path_arg),
+            _ => return print_suite_usage(),
+        }
+    }
+
+    let Some(path) = path else {
+        return print_suite_usage();
+    };
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) => return println!("unable to read {}: {}", path, err),
+    };
+
+    let table = PerftTable::new(hash_mb);
+    let (mut passed, mut failed, mut total_nodes) = (0, 0, 0);
+    let start = Instant::now();
+    for (i, epd) in read_epd(&text).into_iter().enumerate() {
+        let (game, epd) = match epd.and_then(|epd| Ok((epd.game()?, epd))) {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                failed += 1;
+                println!("#{}: {}", i + 1, err);
+                continue;
+            }
+        };
+
+        println!("{}", epd.fen);
+        table.clear();
+        for depth in 1..=max_depth {
+            let Some(operand) = epd.operand(&format!("D{}", depth)) else {
+                break;
+            };
+            let Ok(expected) = operand.parse::<u64>() else {
+                failed += 1;
+                println!("  D{} {} is not a number", depth, operand);
+                break;
+            };
+
+            let depth_start = Instant::now();
+            let nodes = parallel_perft(&game, depth, threads, &table);
+            let nps = nps(nodes, depth_start.elapsed());
+            total_nodes += nodes;
+            if nodes == expected {
+                passed += 1;
+                println!("  D{} {} ok, {} nps", depth, nodes, nps);
+            } else {
+                failed += 1;
+                println!("  D{} {} FAILED, expected {}", depth, nodes, expected);
+            }
+        }
+    }
+
+    let elapsed = start.elapsed();
+    println!(
+        "\n{} passed, {} failed\n{} nodes in {} ms, {} nps",
+        passed,
+        failed,
+        total_nodes,
+        elapsed.as_millis(),
+        nps(total_nodes, elapsed)
+    );
+}
+
+// a small table and a few threads so the tests split the root moves even on one core
+#[cfg(test)]
+fn hashed_perft(game: &Game, depth: u32) -> u64 {
+    parallel_perft(game, depth, 4, &PerftTable::new(16))
 }
 
 #[cfg(test)]
 fn read_test_suite() {
     // reads perft results from file and run tests against those results
 
-    use std::{fs, path::PathBuf};
+    use std::path::PathBuf;
     let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     d.push("resources/test/perftsuite.epd");
 
     let contents = fs::read_to_string(d).expect("should have been able to read from file");
-    let lines = contents.split_terminator("\n");
-    for test in lines.into_iter() {
-        let mut results = test.split(";").into_iter();
-        let fen = results
-            .next()
-            .expect("something went wrong reading a line from the perft test suite");
-
-        let result = Game::from_fen(fen);
-        assert!(result.is_ok(), "{} is invalid fen", fen);
-        let mut game = result.unwrap();
-
-        for (i, ply_result) in results.enumerate() {
-            let depth = i + 1;
-            let expected_nodes = ply_result[3..].trim().to_owned().parse::<u32>();
-            assert!(
-                expected_nodes.is_ok(),
-                "{} is not a number",
-                ply_result[3..].trim().to_owned()
-            );
+    let table = PerftTable::new(DEFAULT_PERFT_HASH_MB);
+    for result in read_epd(&contents) {
+        assert!(result.is_ok(), "{}", result.unwrap_err());
+        let epd = result.unwrap();
+
+        let result = epd.game();
+        assert!(result.is_ok(), "{} is invalid fen", epd.fen);
+        let game = result.unwrap();
+        table.clear();
+
+        // the node counts are in the D1, D2 ... operations
+        for depth in 1.. {
+            let Some(ply_result) = epd.operand(&format!("D{}", depth)) else {
+                break;
+            };
+            let expected_nodes = ply_result.parse::<u64>();
+            assert!(expected_nodes.is_ok(), "{} is not a number", ply_result);
             let expected_nodes = expected_nodes.unwrap();
             assert_eq!(
-                count_moves_debug(depth as u32, &mut game),
-                (expected_nodes, expected_nodes),
+                parallel_perft(&game, depth, default_threads(), &table),
+                expected_nodes,
                 "fen: {} depth: {}",
-                fen,
+                epd.fen,
                 depth
             );
         }
@@ -151,6 +412,193 @@ pub mod test_suite {
     }
 }
 
+#[cfg(test)]
+pub mod test_perft_stats {
+    use super::*;
+
+    // the counts are in the same order as the columns of the published results
+    fn stats(nodes: u64, counts: [u64; 8]) -> PerftStats {
+        PerftStats {
+            nodes,
+            captures: counts[0],
+            en_passants: counts[1],
+            castles: counts[2],
+            promotions: counts[3],
+            checks: counts[4],
+            discovered_checks: counts[5],
+            double_checks: counts[6],
+            checkmates: counts[7],
+        }
+    }
+
+    #[test]
+    fn start_pos() {
+        let result = Game::from_fen(crate::fen::STARTING_POSITION_FEN);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        assert_eq!(
+            perft_stats(&mut game, 4),
+            vec![
+                stats(20, [0, 0, 0, 0, 0, 0, 0, 0]),
+                stats(400, [0, 0, 0, 0, 0, 0, 0, 0]),
+                stats(8902, [34, 0, 0, 0, 12, 0, 0, 0]),
+                stats(197_281, [1576, 0, 0, 0, 469, 0, 0, 8]),
+            ]
+        );
+    }
+
+    #[test]
+    fn castles_and_en_passant() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        assert_eq!(
+            perft_stats(&mut game, 3),
+            vec![
+                stats(48, [8, 0, 2, 0, 0, 0, 0, 0]),
+                stats(2039, [351, 1, 91, 0, 3, 0, 0, 0]),
+                stats(97_862, [17102, 45, 3162, 0, 993, 0, 0, 1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn discovered_checks() {
+        let fen = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        assert_eq!(
+            perft_stats(&mut game, 4),
+            vec![
+                stats(14, [1, 0, 0, 0, 2, 0, 0, 0]),
+                stats(191, [14, 0, 0, 0, 10, 0, 0, 0]),
+                stats(2812, [209, 2, 0, 0, 267, 3, 0, 0]),
+                stats(43_238, [3348, 123, 0, 0, 1680, 106, 0, 17]),
+            ]
+        );
+    }
+
+    #[test]
+    fn promotions() {
+        let fen = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        assert_eq!(
+            perft_stats(&mut game, 3),
+            vec![
+                stats(6, [0, 0, 0, 0, 0, 0, 0, 0]),
+                stats(264, [87, 0, 6, 48, 10, 0, 0, 0]),
+                stats(9467, [1021, 4, 0, 120, 38, 2, 0, 22]),
+            ]
+        );
+    }
+
+    #[ignore]
+    #[test]
+    fn double_checks() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        assert_eq!(
+            perft_stats(&mut game, 4)[3],
+            stats(4_085_603, [757163, 1929, 128013, 15172, 25523, 42, 6, 43])
+        );
+    }
+
+    #[ignore]
+    #[test]
+    fn discovered_and_double_checks() {
+        let fen = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        assert_eq!(
+            perft_stats(&mut game, 5)[4],
+            stats(674_624, [52051, 1165, 0, 0, 52950, 1292, 3, 0])
+        );
+    }
+
+    #[test]
+    fn divide_start_pos() {
+        let result = Game::from_fen(crate::fen::STARTING_POSITION_FEN);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let split = divide(&mut game, 3);
+        assert_eq!(split.len(), 20);
+        assert_eq!(split.iter().map(|(_, nodes)| nodes).sum::<u64>(), 8902);
+
+        let e2e4 = split
+            .iter()
+            .find(|(mv, _)| crate::uci::move_to_algebra(*mv, crate::side::Side::White) == "e2e4");
+        assert_eq!(e2e4.map(|(_, nodes)| *nodes), Some(600));
+        assert_eq!(perft(&mut game, 3), 8902);
+    }
+}
+
+#[cfg(test)]
+pub mod test_parallel_perft {
+    use super::*;
+
+    #[test]
+    fn table_probe() {
+        let table = PerftTable::new(1);
+        assert_eq!(table.probe(12345, 3), None);
+
+        table.store(12345, 3, 97_862);
+        assert_eq!(table.probe(12345, 3), Some(97_862));
+        assert_eq!(table.probe(12345, 4), None);
+        assert_eq!(table.probe(12345 + table.entries.len() as u64, 3), None);
+
+        table.clear();
+        assert_eq!(table.probe(12345, 3), None);
+    }
+
+    #[test]
+    fn torn_entry_misses() {
+        let table = PerftTable::new(1);
+        table.store(12345, 3, 97_862);
+        // the data of another position written without its key
+        table.entry(12345)[1].store(2039 << 8 | 3, Ordering::Relaxed);
+        assert_eq!(table.probe(12345, 3), None);
+    }
+
+    #[test]
+    fn matches_divide() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+
+        let table = PerftTable::new(16);
+        for threads in [1, 3] {
+            table.clear();
+            assert_eq!(
+                parallel_divide(&game, 3, threads, &table),
+                divide(&mut game, 3)
+            );
+        }
+        // the second run only reads the table
+        assert_eq!(parallel_perft(&game, 3, 3, &table), 97_862);
+    }
+
+    #[test]
+    fn no_moves() {
+        let fen = "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+
+        let table = PerftTable::new(1);
+        assert!(parallel_divide(&game, 2, 4, &table).is_empty());
+        assert_eq!(parallel_perft(&game, 2, 4, &table), 0);
+        assert_eq!(parallel_perft(&game, 0, 4, &table), 1);
+    }
+}
+
 #[cfg(test)]
 pub mod start_pos {
     use super::*;
@@ -161,8 +609,8 @@ pub mod start_pos {
         let fen = STARTING_POSITION_FEN;
         let result = Game::from_fen(fen);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(1, &mut game), (20, 20))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 1), 20)
     }
 
     #[test]
@@ -170,8 +618,8 @@ pub mod start_pos {
         let fen = STARTING_POSITION_FEN;
         let result = Game::from_fen(fen);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(2, &mut game), (400, 400))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 2), 400)
     }
 
     #[test]
@@ -179,8 +627,8 @@ pub mod start_pos {
         let fen = STARTING_POSITION_FEN;
         let result = Game::from_fen(fen);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(3, &mut game), (8902, 8902))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 3), 8902)
     }
 
     #[test]
@@ -188,8 +636,8 @@ pub mod start_pos {
         let fen = STARTING_POSITION_FEN;
         let result = Game::from_fen(fen);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(4, &mut game), (197_281, 197_281))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 4), 197_281)
     }
 
     #[ignore]
@@ -198,8 +646,8 @@ pub mod start_pos {
         let fen = STARTING_POSITION_FEN;
         let result = Game::from_fen(fen);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(5, &mut game), (4_865_609, 4_865_609))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 5), 4_865_609)
     }
 
     #[ignore = "only need to run when movegen logic changes"]
@@ -208,8 +656,8 @@ pub mod start_pos {
         let fen = STARTING_POSITION_FEN;
         let result = Game::from_fen(fen);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(6, &mut game), (119_060_324, 119_060_324))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 6), 119_060_324)
     }
 }
 
@@ -223,24 +671,24 @@ pub mod perft_fen_1 {
     fn one_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(1, &mut game), (48, 48))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 1), 48)
     }
 
     #[test]
     fn two_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(2, &mut game), (2039, 2039))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 2), 2039)
     }
 
     #[test]
     fn three_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(3, &mut game), (97862, 97862))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 3), 97862)
     }
 
     #[ignore]
@@ -248,8 +696,8 @@ pub mod perft_fen_1 {
     fn four_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(4, &mut game), (4_085_603, 4_085_603))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 4), 4_085_603)
     }
 
     #[ignore]
@@ -257,8 +705,8 @@ pub mod perft_fen_1 {
     fn five_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(5, &mut game), (193_690_690, 193_690_690))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 5), 193_690_690)
     }
 }
 
@@ -272,40 +720,40 @@ pub mod perft_fen_2 {
     fn one_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(1, &mut game), (15, 15))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 1), 15)
     }
 
     #[test]
     fn two_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(2, &mut game), (66, 66))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 2), 66)
     }
 
     #[test]
     fn three_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(3, &mut game), (1197, 1197))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 3), 1197)
     }
 
     #[test]
     fn four_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(4, &mut game), (7059, 7059))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 4), 7059)
     }
 
     #[test]
     fn five_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(5, &mut game), (133_987, 133_987))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 5), 133_987)
     }
 
     #[ignore]
@@ -313,14 +761,74 @@ pub mod perft_fen_2 {
     fn six_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(6, &mut game), (764_643, 764_643))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 6), 764_643)
+    }
+}
+
+#[cfg(test)]
+pub mod perft_chess960 {
+    use super::*;
+
+    const FEN: &str = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+
+    #[test]
+    fn one_ply() {
+        let result = Game::from_fen(FEN);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 1), 21)
+    }
+
+    #[test]
+    fn two_ply() {
+        let result = Game::from_fen(FEN);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 2), 528)
+    }
+
+    #[test]
+    fn three_ply() {
+        let result = Game::from_fen(FEN);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 3), 12189)
+    }
+
+    #[ignore]
+    #[test]
+    fn four_ply() {
+        let result = Game::from_fen(FEN);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 4), 326_672)
+    }
+
+    const SECOND_FEN: &str = "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9";
+
+    #[test]
+    fn second_position_four_ply() {
+        let result = Game::from_fen(SECOND_FEN);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 4), 667_366)
+    }
+
+    #[ignore]
+    #[test]
+    fn second_position_five_ply() {
+        let result = Game::from_fen(SECOND_FEN);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 5), 16_253_601)
     }
 }
 
 #[cfg(test)]
 pub mod perft_promotion {
     use crate::move_gen::check_legal::is_legal_king_move;
+    use crate::move_gen::{checkers_pinners_pinned, controlled_squares_with_king_gone};
     use crate::mv::{EncodedMove, Move};
     use crate::square::*;
     use crate::uci::input_position;
@@ -333,24 +841,24 @@ pub mod perft_promotion {
     fn promotion_pos_one_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(1, &mut game), (24, 24))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 1), 24)
     }
 
     #[test]
     fn promotion_pos_two_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(2, &mut game), (496, 496))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 2), 496)
     }
 
     #[test]
     fn promotion_pos_three_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(3, &mut game), (9483, 9483))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 3), 9483)
     }
 
     #[test]
@@ -385,8 +893,8 @@ pub mod perft_promotion {
     fn promotion_pos_four_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(4, &mut game), (182_838, 182_838))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 4), 182_838)
     }
 
     #[ignore]
@@ -394,8 +902,8 @@ pub mod perft_promotion {
     fn promotion_pos_five_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(5, &mut game), (3_605_103, 3_605_103))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 5), 3_605_103)
     }
 
     #[ignore]
@@ -403,8 +911,8 @@ pub mod perft_promotion {
     fn promotion_pos_six_ply() {
         let result = Game::from_fen(FEN);
         assert!(result.is_ok());
-        let mut game = result.unwrap();
-        assert_eq!(count_moves_debug(6, &mut game), (71_179_139, 71_179_139))
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 6), 71_179_139)
     }
 
     #[test]
@@ -435,3 +943,269 @@ pub mod perft_promotion {
         assert!(!is_legal_king_move(illegal_mv, &legal_check_preprocessing));
     }
 }
+
+#[cfg(test)]
+pub mod perft_antichess {
+    use super::*;
+    use crate::variant::Variant;
+
+    // every count here, down to the start position, was counted again with
+    // `resources/test/reference_perft.py antichess`
+
+    const KIWIPETE_FEN: &str =
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+    #[test]
+    fn start_pos() {
+        let result = Game::from_variant_fen(Variant::Antichess.start_fen(), Variant::Antichess);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 1), 20);
+        assert_eq!(hashed_perft(&game, 2), 400);
+        assert_eq!(hashed_perft(&game, 3), 8067);
+        assert_eq!(hashed_perft(&game, 4), 153_299)
+    }
+
+    #[ignore]
+    #[test]
+    fn start_pos_five_ply() {
+        let result = Game::from_variant_fen(Variant::Antichess.start_fen(), Variant::Antichess);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 5), 2_732_672)
+    }
+
+    #[test]
+    fn kiwipete() {
+        // the castle rights are ignored
+        let result = Game::from_variant_fen(KIWIPETE_FEN, Variant::Antichess);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 3), 487);
+        assert_eq!(hashed_perft(&game, 4), 3872)
+    }
+
+    #[test]
+    fn king_promotion() {
+        let fen = "8/1P6/8/8/8/8/6p1/8 w - - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Antichess);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 1), 5);
+        assert_eq!(hashed_perft(&game, 4), 2112)
+    }
+
+    #[test]
+    fn forced_en_passant() {
+        let fen = "rnbqkbnr/pp1ppppp/8/8/2pPP3/8/PPP2PPP/RNBQKBNR b - d3 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Antichess);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 1), 1);
+        assert_eq!(hashed_perft(&game, 4), 996)
+    }
+
+    #[test]
+    fn no_pieces_left() {
+        let fen = "8/8/8/8/8/8/p7/8 w - - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Antichess);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 1), 0)
+    }
+}
+
+#[cfg(test)]
+pub mod perft_king_of_the_hill {
+    use super::*;
+    use crate::variant::Variant;
+
+    // counted again with `resources/test/reference_perft.py kingofthehill`
+
+    #[test]
+    fn kiwipete() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::KingOfTheHill);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 3), 97_862)
+    }
+
+    #[test]
+    fn kings_racing_to_the_hill() {
+        let fen = "8/8/8/2k5/8/5K2/8/8 b - - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::KingOfTheHill);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 5), 16_036)
+    }
+}
+
+#[cfg(test)]
+pub mod perft_three_check {
+    use super::*;
+    use crate::variant::Variant;
+
+    // counted again with `resources/test/reference_perft.py 3check`, which reads the checks left
+    // the same way
+
+    #[test]
+    fn kiwipete_one_check_left() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 1+1 0 1";
+        let result = Game::from_variant_fen(fen, Variant::ThreeCheck);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 1), 48);
+        assert_eq!(hashed_perft(&game, 2), 2039);
+        assert_eq!(hashed_perft(&game, 3), 97_848)
+    }
+
+    #[test]
+    fn italian_checks_left() {
+        let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5Q2/PPPP1PPP/RNB1K1NR w KQkq - 1+2 0 1";
+        let result = Game::from_variant_fen(fen, Variant::ThreeCheck);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 3), 49_106)
+    }
+
+    #[test]
+    fn rooks_one_check_left() {
+        let fen = "4k3/8/8/8/8/8/8/R3K2R w KQ - 1+3 0 1";
+        let result = Game::from_variant_fen(fen, Variant::ThreeCheck);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 4), 15_274)
+    }
+}
+
+#[cfg(test)]
+pub mod perft_crazyhouse {
+    use super::*;
+    use crate::variant::Variant;
+
+    // counted again with `resources/test/reference_perft.py crazyhouse`
+
+    #[test]
+    fn start_pos() {
+        let result = Game::from_variant_fen(Variant::Crazyhouse.start_fen(), Variant::Crazyhouse);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 3), 8902);
+        assert_eq!(hashed_perft(&game, 4), 197_281)
+    }
+
+    #[test]
+    fn kiwipete_pawns_in_pockets() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/4P3/2N2Q2/PPPBBPPP/R3K2R[Pp] w KQkq - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Crazyhouse);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 2), 4958)
+    }
+
+    #[test]
+    fn promoted_rook_and_en_passant() {
+        let fen = "r3k2r/8/8/2pP4/8/8/8/R3K2R~[QNbp] w KQkq c6 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Crazyhouse);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 3), 1_356_487)
+    }
+
+    #[test]
+    fn drops_in_check() {
+        // the queen on b4 checks along the diagonal, so white can only block with a drop or move
+        // the king, and not castle
+        let fen = "4k3/1P6/8/8/1q6/8/8/R3K3[Np] w Q - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Crazyhouse);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 1), 6);
+        assert_eq!(hashed_perft(&game, 3), 22_502);
+
+        let fen = "2r1k3/8/8/8/8/8/4B~3/4K3[Rr] b - - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Crazyhouse);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 3), 147_773)
+    }
+}
+
+#[cfg(test)]
+pub mod perft_atomic {
+    use super::*;
+    use crate::variant::Variant;
+
+    // counted again with `resources/test/reference_perft.py atomic`
+
+    #[test]
+    fn start_pos() {
+        let result = Game::from_variant_fen(Variant::Atomic.start_fen(), Variant::Atomic);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 1), 20);
+        assert_eq!(hashed_perft(&game, 2), 400);
+        assert_eq!(hashed_perft(&game, 3), 8902);
+        assert_eq!(hashed_perft(&game, 4), 197_326)
+    }
+
+    #[ignore]
+    #[test]
+    fn start_pos_five_ply() {
+        let result = Game::from_variant_fen(Variant::Atomic.start_fen(), Variant::Atomic);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 5), 4_864_979)
+    }
+
+    #[test]
+    fn kiwipete() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Atomic);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 3), 88_298)
+    }
+
+    #[test]
+    fn queens_gambit_declined() {
+        let fen = "rn2kb1r/1pp1p2p/p2q1pp1/3P4/2P3b1/4PN2/PP3PPP/R2QKB1R b KQkq - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Atomic);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 1), 40);
+        assert_eq!(hashed_perft(&game, 2), 1238);
+        assert_eq!(hashed_perft(&game, 3), 45_237)
+    }
+
+    #[test]
+    fn chess960_castling() {
+        let fen = "8/8/8/8/8/8/2k5/rR4KR w KQ - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Atomic);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 1), 18);
+        assert_eq!(hashed_perft(&game, 2), 180);
+        assert_eq!(hashed_perft(&game, 3), 4364);
+        assert_eq!(hashed_perft(&game, 4), 61_401)
+    }
+
+    #[test]
+    fn en_passant_explosion() {
+        let fen = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3";
+        let result = Game::from_variant_fen(fen, Variant::Atomic);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 3), 21_511)
+    }
+
+    #[test]
+    fn touching_kings() {
+        let fen = "8/8/8/3kK3/8/8/8/3r3R w - - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Atomic);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(hashed_perft(&game, 4), 93_271)
+    }
+}
@@ -0,0 +1,591 @@
+use std::{iter::Peekable, slice::Iter};
+
+use crate::{fen::STARTING_POSITION_FEN, game::Game, mv::Move, side::Side};
+
+// written first and in this order, with these values when the tag is missing
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+const MAX_LINE_LENGTH: usize = 80;
+// move suffixes in the order of the nags they stand for, so `!` is $1 and `?!` is $6
+const SUFFIX_ANNOTATIONS: [&str; 6] = ["!", "?", "!!", "??", "!?", "?!"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PgnMove {
+    pub mv: Move,
+    pub san: String,
+    pub nags: Vec<u8>,
+    // the comment written after the move
+    pub comment: Option<String>,
+    // alternatives to this move
+    pub variations: Vec<Line>,
+}
+
+impl PgnMove {
+    pub fn new(mv: Move, san: String) -> PgnMove {
+        PgnMove {
+            mv,
+            san,
+            nags: vec![],
+            comment: None,
+            variations: vec![],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Line {
+    // the comment written before the first move
+    pub comment: Option<String>,
+    pub moves: Vec<PgnMove>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRecord {
+    pub tags: Vec<(String, String)>,
+    pub mainline: Line,
+    // 1-0, 0-1, 1/2-1/2 or * for unfinished games
+    pub result: String,
+}
+
+impl Default for GameRecord {
+    fn default() -> GameRecord {
+        GameRecord {
+            tags: vec![],
+            mainline: Line::default(),
+            result: "*".to_string(),
+        }
+    }
+}
+
+impl GameRecord {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag_name, _)| tag_name == name) {
+            Some((_, tag_value)) => *tag_value = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn set_result(&mut self, result: &str) {
+        self.result = result.to_string();
+        self.set_tag("Result", result);
+    }
+
+    pub fn start_fen(&self) -> &str {
+        self.tag("FEN").unwrap_or(STARTING_POSITION_FEN)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Tag(String, String),
+    Comment(String),
+    Nag(u8),
+    San(String),
+    StartVariation,
+    EndVariation,
+    Result(String),
+}
+
+fn is_result(token: &str) -> bool {
+    matches!(token, "1-0" | "0-1" | "1/2-1/2" | "*")
+}
+
+fn parse_tag(tag: &str) -> Option<Token> {
+    let (name, value) = tag.trim().split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some(Token::Tag(
+        name.to_string(),
+        value.replace("\\\"", "\"").replace("\\\\", "\\"),
+    ))
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+    let mut line_start = true;
+
+    while let Some(c) = chars.next() {
+        let at_line_start = line_start;
+        line_start = c == '\n';
+
+        match c {
+            // escaped lines are for other software and are skipped
+            '%' if at_line_start => {
+                chars.by_ref().take_while(|c| *c != '\n').for_each(drop);
+                line_start = true;
+            }
+            '[' => {
+                let mut tag = String::new();
+                let mut in_quotes = false;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => in_quotes = !in_quotes,
+                        '\\' if in_quotes => {
+                            tag.push(c);
+                            if let Some(escaped) = chars.next() {
+                                tag.push(escaped);
+                            }
+                            continue;
+                        }
+                        ']' if !in_quotes => break,
+                        _ => (),
+                    }
+                    tag.push(c);
+                }
+                tokens.extend(parse_tag(&tag));
+            }
+            '{' => {
+                let comment: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                tokens.push(Token::Comment(
+                    comment.split_whitespace().collect::<Vec<&str>>().join(" "),
+                ));
+            }
+            ';' => {
+                let comment: String = chars.by_ref().take_while(|c| *c != '\n').collect();
+                tokens.push(Token::Comment(comment.trim().to_string()));
+                line_start = true;
+            }
+            '(' => tokens.push(Token::StartVariation),
+            ')' => tokens.push(Token::EndVariation),
+            '$' => {
+                let mut nag = String::new();
+                while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+                    nag.push(digit);
+                }
+                tokens.extend(nag.parse().ok().map(Token::Nag));
+            }
+            c if c.is_whitespace() => (),
+            c => {
+                let mut token = c.to_string();
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !"{}();[$".contains(*c))
+                {
+                    token.push(c);
+                }
+
+                if is_result(&token) {
+                    tokens.push(Token::Result(token));
+                    continue;
+                }
+
+                // move numbers like `12.`, `12...` or the start of `12.e4`
+                let san = match token.find('.') {
+                    Some(i) if i > 0 && token[..i].chars().all(|c| c.is_ascii_digit()) => {
+                        token[i..].trim_start_matches('.')
+                    }
+                    _ => token.as_str(),
+                };
+                let san_end = san.trim_end_matches(['!', '?']).len();
+                let (san, suffix) = san.split_at(san_end);
+                if !san.is_empty() {
+                    tokens.push(Token::San(san.to_string()));
+                }
+                if let Some(i) = SUFFIX_ANNOTATIONS.iter().position(|s| *s == suffix) {
+                    tokens.push(Token::Nag(i as u8 + 1));
+                }
+            }
+        }
+    }
+
+    tokens
+}
+
+// a game starts with its tags and ends with its result, or with the tags of the next game
+fn split_games(tokens: Vec<Token>) -> Vec<Vec<Token>> {
+    let mut games = vec![];
+    let mut game = vec![];
+    let mut in_movetext = false;
+    let mut depth = 0;
+
+    for token in tokens {
+        match token {
+            Token::Tag(..) if in_movetext => {
+                games.push(std::mem::take(&mut game));
+                in_movetext = false;
+                depth = 0;
+            }
+            Token::Tag(..) => (),
+            Token::StartVariation => depth += 1,
+            Token::EndVariation => depth -= 1,
+            Token::Result(_) if depth <= 0 => {
+                game.push(token);
+                games.push(std::mem::take(&mut game));
+                in_movetext = false;
+                depth = 0;
+                continue;
+            }
+            _ => (),
+        }
+        if !matches!(token, Token::Tag(..)) {
+            in_movetext = true;
+        }
+        game.push(token);
+    }
+
+    if !game.is_empty() {
+        games.push(game);
+    }
+
+    games
+}
+
+fn append_comment(to: &mut Option<String>, comment: &str) {
+    match to {
+        Some(existing) => {
+            existing.push(' ');
+            existing.push_str(comment);
+        }
+        None => *to = Some(comment.to_string()),
+    }
+}
+
+// reads moves until the end of the line, leaving the game as it was before the line
+fn parse_line(
+    tokens: &mut Peekable<Iter<Token>>,
+    game: &mut Game,
+    depth: usize,
+) -> Result<Line, String> {
+    let mut line = Line::default();
+    let mut made_moves = vec![];
+
+    loop {
+        match tokens.peek() {
+            None | Some(Token::Result(_)) | Some(Token::Tag(..)) if depth > 0 => {
+                return Err("variation is missing its closing bracket".to_string());
+            }
+            None | Some(Token::Result(_)) | Some(Token::Tag(..)) => break,
+            Some(Token::EndVariation) if depth == 0 => {
+                return Err("closing bracket without a variation".to_string());
+            }
+            Some(Token::EndVariation) => {
+                tokens.next();
+                break;
+            }
+            _ => (),
+        }
+
+        match tokens.next() {
+            Some(Token::Comment(comment)) => match line.moves.last_mut() {
+                Some(pgn_move) => append_comment(&mut pgn_move.comment, comment),
+                None => append_comment(&mut line.comment, comment),
+            },
+            Some(Token::Nag(nag)) => match line.moves.last_mut() {
+                Some(pgn_move) => pgn_move.nags.push(*nag),
+                None => return Err(format!("${} is before the first move", nag)),
+            },
+            Some(Token::San(san)) => {
+                let mv = game.parse_san(san)?;
+                line.moves.push(PgnMove::new(mv, game.move_to_san(mv)));
+
+                let prev_state = game.state().encode();
+                let capture = game.make_move(mv);
+                made_moves.push((mv, capture, prev_state));
+            }
+            Some(Token::StartVariation) => {
+                // a variation replaces the move before it
+                let Some((mv, capture, prev_state)) = made_moves.last() else {
+                    return Err("variation is before the first move".to_string());
+                };
+                game.unmake_move(*mv, *capture, *prev_state);
+                let variation = parse_line(tokens, game, depth + 1)?;
+                game.make_move(*mv);
+
+                if let Some(pgn_move) = line.moves.last_mut() {
+                    pgn_move.variations.push(variation);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    for (mv, capture, prev_state) in made_moves.into_iter().rev() {
+        game.unmake_move(mv, capture, prev_state);
+    }
+
+    Ok(line)
+}
+
+fn parse_game(tokens: &[Token]) -> Result<GameRecord, String> {
+    let mut record = GameRecord::default();
+    let mut tokens = tokens.iter().peekable();
+    while let Some(Token::Tag(name, value)) = tokens.peek() {
+        record.set_tag(name, value);
+        tokens.next();
+    }
+    if let Some(result) = record.tag("Result").map(String::from) {
+        record.result = result;
+    }
+
+    let mut game = Game::from_fen(record.start_fen())?;
+    record.mainline = parse_line(&mut tokens, &mut game, 0)?;
+
+    // the result at the end of the movetext wins over the tag, unless it's unknown
+    if let Some(Token::Result(result)) = tokens.next() {
+        if result != "*" || record.tag("Result").is_none() {
+            record.set_result(result);
+        }
+    }
+
+    Ok(record)
+}
+
+// every game in the text, a game that can't be read doesn't stop the games after it
+pub fn read_pgn(text: &str) -> Vec<Result<GameRecord, String>> {
+    split_games(tokenize(text))
+        .iter()
+        .map(|tokens| parse_game(tokens))
+        .collect()
+}
+
+fn escape_tag_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn push_line_tokens(line: &Line, ply: usize, tokens: &mut Vec<String>) {
+    if let Some(comment) = &line.comment {
+        tokens.push(format!("{{{}}}", comment.replace('}', "")));
+    }
+
+    // black's moves only get a number at the start of a line or after a comment or variation
+    let mut needs_number = true;
+    for (i, pgn_move) in line.moves.iter().enumerate() {
+        let ply = ply + i;
+        if ply.is_multiple_of(2) {
+            tokens.push(format!("{}.", ply / 2 + 1));
+        } else if needs_number {
+            tokens.push(format!("{}...", ply / 2 + 1));
+        }
+
+        tokens.push(pgn_move.san.clone());
+        for nag in &pgn_move.nags {
+            tokens.push(format!("${}", nag));
+        }
+        if let Some(comment) = &pgn_move.comment {
+            tokens.push(format!("{{{}}}", comment.replace('}', "")));
+        }
+
+        for variation in &pgn_move.variations {
+            let mut variation_tokens = vec![];
+            push_line_tokens(variation, ply, &mut variation_tokens);
+            match variation_tokens.len() {
+                0 => variation_tokens.push("()".to_string()),
+                len => {
+                    variation_tokens[0].insert(0, '(');
+                    variation_tokens[len - 1].push(')');
+                }
+            }
+            tokens.extend(variation_tokens);
+        }
+
+        needs_number = pgn_move.comment.is_some() || !pgn_move.variations.is_empty();
+    }
+}
+
+// the seven tag roster comes first, then the other tags in the order they were added. movetext
+// lines are kept under 80 characters
+pub fn write_pgn(record: &GameRecord) -> String {
+    let mut pgn = String::new();
+    for (name, default) in SEVEN_TAG_ROSTER {
+        let value = match name {
+            "Result" => record.result.as_str(),
+            _ => record.tag(name).unwrap_or(default),
+        };
+        pgn.push_str(&format!("[{} \"{}\"]\n", name, escape_tag_value(value)));
+    }
+    for (name, value) in &record.tags {
+        if SEVEN_TAG_ROSTER
+            .iter()
+            .all(|(roster_name, _)| roster_name != name)
+        {
+            pgn.push_str(&format!("[{} \"{}\"]\n", name, escape_tag_value(value)));
+        }
+    }
+    pgn.push('\n');
+
+    let start_ply = Game::from_fen(record.start_fen()).map_or(0, |game| {
+        let is_black = game.state().side_to_move() == Side::Black;
+        (game.state().fullmoves().max(1) as usize - 1) * 2 + is_black as usize
+    });
+
+    let mut tokens = vec![];
+    push_line_tokens(&record.mainline, start_ply, &mut tokens);
+    tokens.push(record.result.clone());
+
+    let mut line_length = 0;
+    for token in tokens {
+        if line_length > 0 && line_length + 1 + token.len() > MAX_LINE_LENGTH {
+            pgn.push('\n');
+            line_length = 0;
+        } else if line_length > 0 {
+            pgn.push(' ');
+            line_length += 1;
+        }
+        pgn.push_str(&token);
+        line_length += token.len();
+    }
+    pgn.push('\n');
+
+    pgn
+}
+
+#[cfg(test)]
+pub mod test_pgn {
+    use super::*;
+
+    fn read_one(text: &str) -> GameRecord {
+        let mut records = read_pgn(text);
+        assert_eq!(records.len(), 1);
+        let result = records.remove(0);
+        assert!(result.is_ok(), "{:?}", result);
+        result.unwrap()
+    }
+
+    fn sans(line: &Line) -> Vec<&str> {
+        line.moves
+            .iter()
+            .map(|pgn_move| pgn_move.san.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn tags_and_result() {
+        let record = read_one(
+            r#"[Event "Club \"Open\""]
+[White "A"]
+[Result "1/2-1/2"]
+
+1. e4 e5 1/2-1/2"#,
+        );
+        assert_eq!(record.tag("Event"), Some("Club \"Open\""));
+        assert_eq!(record.tag("White"), Some("A"));
+        assert_eq!(record.tag("Black"), None);
+        assert_eq!(record.result, "1/2-1/2");
+        assert_eq!(sans(&record.mainline), vec!["e4", "e5"]);
+    }
+
+    #[test]
+    fn comments_nags_and_lenient_san() {
+        let record = read_one(
+            "{opening} 1.e4! {king's pawn} e5 $2 2.Ng1-f3 ; to the center\n\
+             Nc6?! 3.Bb5 {a} {b} 1-0",
+        );
+        assert_eq!(record.mainline.comment.as_deref(), Some("opening"));
+        let moves = &record.mainline.moves;
+        assert_eq!(
+            sans(&record.mainline),
+            vec!["e4", "e5", "Nf3", "Nc6", "Bb5"]
+        );
+        assert_eq!(moves[0].nags, vec![1]);
+        assert_eq!(moves[0].comment.as_deref(), Some("king's pawn"));
+        assert_eq!(moves[1].nags, vec![2]);
+        assert_eq!(moves[2].comment.as_deref(), Some("to the center"));
+        assert_eq!(moves[3].nags, vec![6]);
+        assert_eq!(moves[4].comment.as_deref(), Some("a b"));
+        assert_eq!(record.result, "1-0");
+    }
+
+    #[test]
+    fn nested_variations() {
+        let record = read_one("1. e4 e5 (1... c5 2. Nf3 (2. c3 d5) d6) (1... e6) 2. Nf3 Nc6 *");
+        let moves = &record.mainline.moves;
+        assert_eq!(sans(&record.mainline), vec!["e4", "e5", "Nf3", "Nc6"]);
+        assert_eq!(moves[1].variations.len(), 2);
+        let sicilian = &moves[1].variations[0];
+        assert_eq!(sans(sicilian), vec!["c5", "Nf3", "d6"]);
+        assert_eq!(sans(&sicilian.moves[1].variations[0]), vec!["c3", "d5"]);
+        assert_eq!(sans(&moves[1].variations[1]), vec!["e6"]);
+        assert_eq!(record.result, "*");
+    }
+
+    #[test]
+    fn several_games_and_errors() {
+        let records = read_pgn(
+            "[Event \"a\"]\n\n1. e4 e5 1-0\n\n\
+             [Event \"b\"]\n\n1. e4 e4 0-1\n\n\
+             [Event \"c\"]\n\n1. d4 (1. e4 d5 0-1\n\n\
+             % escaped line\n\
+             [Event \"d\"]\n[FEN \"4k3/8/8/8/8/8/8/R3K3 b Q - 0 40\"]\n\n40... Kd7 41. O-O-O+",
+        );
+        assert_eq!(records.len(), 4);
+        assert!(records[0].is_ok());
+        assert!(records[1].is_err());
+        assert!(records[2].is_err());
+        assert!(records[3].is_ok());
+
+        let record = records[3].as_ref().unwrap();
+        assert_eq!(sans(&record.mainline), vec!["Kd7", "O-O-O+"]);
+        assert_eq!(record.result, "*");
+    }
+
+    #[test]
+    fn write_game() {
+        let mut record = read_one(
+            "[White \"A\"]\n[ECO \"C20\"]\n\n\
+             {start} 1. e4 $1 e5 {reply} (1... c5 2. Nf3) 2. Nf3 Nc6 1-0",
+        );
+        record.set_tag("Event", "test");
+
+        let expected = "[Event \"test\"]\n\
+                        [Site \"?\"]\n\
+                        [Date \"????.??.??\"]\n\
+                        [Round \"?\"]\n\
+                        [White \"A\"]\n\
+                        [Black \"?\"]\n\
+                        [Result \"1-0\"]\n\
+                        [ECO \"C20\"]\n\
+                        \n\
+                        {start} 1. e4 $1 e5 {reply} (1... c5 2. Nf3) 2. Nf3 Nc6 1-0\n";
+        assert_eq!(write_pgn(&record), expected);
+        assert_eq!(read_one(&write_pgn(&record)).mainline, record.mainline);
+    }
+
+    #[test]
+    fn write_from_fen_and_wrap() {
+        let result = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 b - - 0 40");
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+
+        let mut record = GameRecord::default();
+        record.set_tag("SetUp", "1");
+        record.set_tag("FEN", "4k3/8/8/8/8/8/8/R3K3 b - - 0 40");
+        for san in [
+            "Kd7", "Ra7+", "Kc6", "Ra6+", "Kb5", "Ra1", "Kc4", "Ke2", "Kd4",
+        ] {
+            let result = game.parse_san(san);
+            assert!(result.is_ok());
+            let mv = result.unwrap();
+            record
+                .mainline
+                .moves
+                .push(PgnMove::new(mv, san.to_string()));
+            game.make_move(mv);
+        }
+
+        let pgn = write_pgn(&record);
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/8/R3K3 b - - 0 40\"]\n"));
+        assert!(pgn.contains("\n40... Kd7 41. Ra7+ Kc6 42. Ra6+ Kb5 43. Ra1 Kc4 44. Ke2 Kd4 *\n"));
+        assert_eq!(read_one(&pgn).mainline, record.mainline);
+
+        let long = "1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 4. Ba4 Nf6 5. O-O Be7 6. Re1 b5 7. Bb3 d6 \
+                    8. c3 O-O 9. h3 Nb8 10. d4 Nbd7 1/2-1/2";
+        let record = read_one(long);
+        let pgn = write_pgn(&record);
+        let movetext: Vec<&str> = pgn.lines().skip(8).collect();
+        assert_eq!(movetext.len(), 2);
+        assert!(movetext.iter().all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert_eq!(read_one(&pgn).mainline, record.mainline);
+    }
+}
@@ -36,6 +36,8 @@ pub enum PieceType {
     Rook = ROOK_ID,
     #[subenum(PromoteType)]
     Queen = QUEEN_ID,
+    // only antichess promotes to a king
+    #[subenum(PromoteType)]
     King = KING_ID,
 }
 
@@ -48,25 +50,11 @@ const fn generate_piece_type_map() -> [PieceType; PIECE_TYPE_COUNT] {
     piece_type_map[QUEEN_ID as usize] = PieceType::Queen;
     piece_type_map[KING_ID as usize] = PieceType::King;
 
-    return piece_type_map;
-}
-
-const fn generate_piece_type_score_map() -> [u32; PIECE_TYPE_COUNT] {
-    let mut piece_type_score_map: [u32; PIECE_TYPE_COUNT] = [0; PIECE_TYPE_COUNT];
-    piece_type_score_map[PAWN_ID as usize] = 100;
-    piece_type_score_map[KNIGHT_ID as usize] = 300;
-    piece_type_score_map[BISHOP_ID as usize] = 350;
-    piece_type_score_map[ROOK_ID as usize] = 500;
-    piece_type_score_map[QUEEN_ID as usize] = 900;
-    piece_type_score_map[KING_ID as usize] = 10000;
-
-    return piece_type_score_map;
+    piece_type_map
 }
 
 pub const PIECE_TYPE_MAP: [PieceType; PIECE_TYPE_COUNT] = generate_piece_type_map();
 
-pub const PIECE_TYPE_SCORE_MAP: [u32; PIECE_TYPE_COUNT] = generate_piece_type_score_map();
-
 pub const PROMOTE_TYPE_ARR: [PromoteType; 4] = [
     PromoteType::Knight,
     PromoteType::Bishop,
@@ -93,15 +81,11 @@ impl PieceType {
     }
 
     pub fn to_algebra(self) -> String {
-        return if self == PieceType::Pawn {
+        if self == PieceType::Pawn {
             "".to_string()
         } else {
             PIECE_CHARS[self.to_usize()].to_string().to_uppercase()
-        };
-    }
-
-    pub fn score(self) -> u32 {
-        PIECE_TYPE_SCORE_MAP[self.to_usize()]
+        }
     }
 
     pub fn pseudo_legal_moves_bb(
@@ -196,7 +180,7 @@ impl PieceType {
         enemy_occupied: BB,
         en_passant: Option<Square>,
     ) {
-        return match self {
+        match self {
             PieceType::Pawn => {
                 let promote_rank_bb = bitboard::ROW_8 | bitboard::ROW_1;
 
@@ -246,7 +230,7 @@ impl PieceType {
                     Move::Piece(EncodedMove::new(from, to, self, enemy_occupied.is_set(to)))
                 });
             }
-        };
+        }
     }
 
     pub fn has_legal_moves(
@@ -285,17 +269,13 @@ impl PieceType {
                     return false;
                 }
 
-                return !pinned || (pseudo_legal_moves & pin_direction(from, king_sq)).not_empty();
+                !pinned || (pseudo_legal_moves & pin_direction(from, king_sq)).not_empty()
             }
             PieceType::Knight => {
                 let pseudo_legal_moves = pseudo_legal::knight_attacks(from, friendly_occupied);
                 let pinned = pinned_pieces_bb.is_set(from);
 
-                if pseudo_legal_moves.empty() || pinned {
-                    return false;
-                } else {
-                    return true;
-                }
+                pseudo_legal_moves.not_empty() && !pinned
             }
             PieceType::Bishop => {
                 let pseudo_legal_moves =
@@ -306,7 +286,7 @@ impl PieceType {
                     return false;
                 }
 
-                return !pinned || (pseudo_legal_moves & pin_direction(from, king_sq)).not_empty();
+                !pinned || (pseudo_legal_moves & pin_direction(from, king_sq)).not_empty()
             }
             PieceType::Rook => {
                 let pseudo_legal_moves =
@@ -317,7 +297,7 @@ impl PieceType {
                     return false;
                 }
 
-                return !pinned || (pseudo_legal_moves & pin_direction(from, king_sq)).not_empty();
+                !pinned || (pseudo_legal_moves & pin_direction(from, king_sq)).not_empty()
             }
             PieceType::Queen => {
                 let pseudo_legal_moves =
@@ -328,13 +308,13 @@ impl PieceType {
                     return false;
                 }
 
-                return !pinned || (pseudo_legal_moves & pin_direction(from, king_sq)).not_empty();
+                !pinned || (pseudo_legal_moves & pin_direction(from, king_sq)).not_empty()
             }
             PieceType::King => {
                 let safe_squares = pseudo_legal::king_attacks(from, friendly_occupied)
                     & !legal_check_preprocessing.controlled_squares_with_king_gone_bb();
 
-                return safe_squares.not_empty();
+                safe_squares.not_empty()
             }
         }
     }
@@ -369,6 +349,7 @@ impl TryFrom<char> for PromoteType {
             'b' => Ok(PromoteType::Bishop),
             'r' => Ok(PromoteType::Rook),
             'q' => Ok(PromoteType::Queen),
+            'k' => Ok(PromoteType::King),
             _ => {
                 println!(
                     "PromoteType/TryFrom, encountered an valid piece, piece: {}",
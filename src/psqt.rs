@@ -8,11 +8,31 @@ use raw_psqt::*;
 // RawPsqt contains scores for 8 ranks of 4 files. Scores are flipped along the files to get the
 // scores for the remaining files ie. the score of the first file along the rank also applies to
 // the last file along the rank (score of a1 is equal to score of h1)
-type RawPsqt = [Score; 32];
-type Psqt = [Score; BOARD_LENGTH];
-type PsqtTable = [Psqt; PIECE_TYPE_COUNT];
+pub type RawPsqt = [Score; 32];
+pub type Psqt = [Score; BOARD_LENGTH];
+pub type PsqtTable = [Psqt; PIECE_TYPE_COUNT];
+
+// the tables as they are written out, before being mirrored along the files and flipped for black
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RawPsqtTables {
+    pub pawn: Psqt,
+    pub knight: RawPsqt,
+    pub bishop: RawPsqt,
+    pub rook: RawPsqt,
+    pub queen: RawPsqt,
+    pub king: RawPsqt,
+}
+
+pub const DEFAULT_RAW_PSQT: RawPsqtTables = RawPsqtTables {
+    pawn: PAWN_PSQT,
+    knight: KNIGHT_PSQT,
+    bishop: BISHOP_PSQT,
+    rook: ROOK_PSQT,
+    queen: QUEEN_PSQT,
+    king: KING_PSQT,
+};
 
-pub static PSQT: [PsqtTable; 2] = [generate_white_psqt(), generate_black_psqt()];
+pub static PSQT: [PsqtTable; 2] = generate_psqt(&DEFAULT_RAW_PSQT);
 
 #[rustfmt::skip]
 mod raw_psqt {
@@ -123,31 +143,35 @@ const fn flip_pqst(psqt: Psqt) -> Psqt {
 
     flipped_psqt
 }
-const fn generate_white_psqt() -> PsqtTable {
+const fn generate_white_psqt(raw: &RawPsqtTables) -> PsqtTable {
     let mut psqt = [[Score(0, 0, 0); BOARD_LENGTH]; PIECE_TYPE_COUNT];
 
-    psqt[PAWN_ID as usize] = PAWN_PSQT;
-    psqt[KNIGHT_ID as usize] = get_piece_psqt(KNIGHT_PSQT);
-    psqt[BISHOP_ID as usize] = get_piece_psqt(BISHOP_PSQT);
-    psqt[ROOK_ID as usize] = get_piece_psqt(ROOK_PSQT);
-    psqt[QUEEN_ID as usize] = get_piece_psqt(QUEEN_PSQT);
-    psqt[KING_ID as usize] = get_piece_psqt(KING_PSQT);
+    psqt[PAWN_ID as usize] = raw.pawn;
+    psqt[KNIGHT_ID as usize] = get_piece_psqt(raw.knight);
+    psqt[BISHOP_ID as usize] = get_piece_psqt(raw.bishop);
+    psqt[ROOK_ID as usize] = get_piece_psqt(raw.rook);
+    psqt[QUEEN_ID as usize] = get_piece_psqt(raw.queen);
+    psqt[KING_ID as usize] = get_piece_psqt(raw.king);
 
     psqt
 }
-const fn generate_black_psqt() -> PsqtTable {
+const fn generate_black_psqt(raw: &RawPsqtTables) -> PsqtTable {
     let mut psqt = [[Score(0, 0, 0); BOARD_LENGTH]; PIECE_TYPE_COUNT];
 
-    psqt[PAWN_ID as usize] = flip_pqst(PAWN_PSQT);
-    psqt[KNIGHT_ID as usize] = flip_pqst(get_piece_psqt(KNIGHT_PSQT));
-    psqt[BISHOP_ID as usize] = flip_pqst(get_piece_psqt(BISHOP_PSQT));
-    psqt[ROOK_ID as usize] = flip_pqst(get_piece_psqt(ROOK_PSQT));
-    psqt[QUEEN_ID as usize] = flip_pqst(get_piece_psqt(QUEEN_PSQT));
-    psqt[KING_ID as usize] = flip_pqst(get_piece_psqt(KING_PSQT));
+    psqt[PAWN_ID as usize] = flip_pqst(raw.pawn);
+    psqt[KNIGHT_ID as usize] = flip_pqst(get_piece_psqt(raw.knight));
+    psqt[BISHOP_ID as usize] = flip_pqst(get_piece_psqt(raw.bishop));
+    psqt[ROOK_ID as usize] = flip_pqst(get_piece_psqt(raw.rook));
+    psqt[QUEEN_ID as usize] = flip_pqst(get_piece_psqt(raw.queen));
+    psqt[KING_ID as usize] = flip_pqst(get_piece_psqt(raw.king));
 
     psqt
 }
 
+pub const fn generate_psqt(raw: &RawPsqtTables) -> [PsqtTable; 2] {
+    [generate_white_psqt(raw), generate_black_psqt(raw)]
+}
+
 #[cfg(test)]
 mod test_psqt {
     use crate::side::Side;
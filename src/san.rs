@@ -0,0 +1,357 @@
+use crate::{
+    game::Game,
+    move_gen::check_legal::LegalCheckPreprocessing,
+    mv::{castle::Castle, Move},
+    piece_type::PieceType,
+    square::Square,
+    uci::{algebra_to_move, move_to_algebra},
+};
+
+// from square, to square and promotion in coordinate notation, so `e7e8q` is ("e7", "e8", 'q')
+fn coordinates(game: &Game, mv: Move) -> (String, String, Option<char>) {
+    let algebra = move_to_algebra(mv, game.state().side_to_move());
+    (
+        algebra[0..2].to_string(),
+        algebra[2..4].to_string(),
+        algebra.chars().nth(4),
+    )
+}
+
+fn square_from_coordinate(coordinate: &str) -> Square {
+    let bytes = coordinate.as_bytes();
+    Square::from((bytes[1] - b'1') as usize, (bytes[0] - b'a') as usize)
+}
+
+fn piece_type_from_san(c: char) -> Option<PieceType> {
+    match c {
+        'N' => Some(PieceType::Knight),
+        'B' => Some(PieceType::Bishop),
+        'R' => Some(PieceType::Rook),
+        'Q' => Some(PieceType::Queen),
+        'K' => Some(PieceType::King),
+        _ => None,
+    }
+}
+
+// writes a legal move in standard algebraic notation, with the file, rank or both of the from
+// square added when another piece of the same type can reach the same square. drops are written
+// with the piece even for pawns, like `P@e4`
+pub fn move_to_san(game: &mut Game, mv: Move) -> String {
+    let mut san = match mv {
+        Move::Castle(Castle::Kingside) => "O-O".to_string(),
+        Move::Castle(Castle::Queenside) => "O-O-O".to_string(),
+        Move::Drop(drop_mv) => drop_mv.to_string(),
+        _ => {
+            let (from, to, promotion) = coordinates(game, mv);
+            let piece_type = game
+                .position()
+                .at(square_from_coordinate(&from))
+                .map_or(PieceType::Pawn, |pc| pc.piece_type());
+            let is_capture = matches!(mv, Move::EnPassant(_))
+                || game.position().at(square_from_coordinate(&to)).is_some();
+
+            let mut san = String::new();
+            if piece_type == PieceType::Pawn {
+                if is_capture {
+                    san.push_str(&from[0..1]);
+                }
+            } else {
+                san.push_str(&piece_type.to_algebra());
+
+                let others: Vec<String> = game
+                    .legal_moves()
+                    .into_iter()
+                    .filter(|other| {
+                        *other != mv && !matches!(other, Move::Castle(_) | Move::Drop(_))
+                    })
+                    .map(|other| coordinates(game, other))
+                    .filter(|(other_from, other_to, _)| {
+                        *other_to == to
+                            && game
+                                .position()
+                                .at(square_from_coordinate(other_from))
+                                .is_some_and(|pc| pc.piece_type() == piece_type)
+                    })
+                    .map(|(other_from, _, _)| other_from)
+                    .collect();
+
+                if !others.is_empty() {
+                    if others.iter().all(|other| other[0..1] != from[0..1]) {
+                        san.push_str(&from[0..1]);
+                    } else if others.iter().all(|other| other[1..2] != from[1..2]) {
+                        san.push_str(&from[1..2]);
+                    } else {
+                        san.push_str(&from);
+                    }
+                }
+            }
+
+            if is_capture {
+                san.push('x');
+            }
+            san.push_str(&to);
+            if let Some(promotion) = promotion {
+                san.push('=');
+                san.push(promotion.to_ascii_uppercase());
+            }
+            san
+        }
+    };
+
+    let prev_state = game.state().encode();
+    let capture = game.make_move(mv);
+    let side = game.state().side_to_move();
+    if LegalCheckPreprocessing::from(game, side).in_check() {
+        san.push(if game.legal_moves().is_empty() {
+            '#'
+        } else {
+            '+'
+        });
+    }
+    game.unmake_move(mv, capture, prev_state);
+
+    san
+}
+
+// finds the legal move written in standard algebraic notation, like `Nbd7`, `exd5`, `e8=Q+`,
+// `O-O` or `N@f3`. check marks, annotations, `e.p.`, a leading `P` for pawns, `-` between squares and
+// lowercase promotions are all accepted, so coordinate notation like `e7e8q` is too
+pub fn parse_san(game: &mut Game, san: &str) -> Result<Move, String> {
+    let trimmed = san
+        .trim()
+        .trim_end_matches("e.p.")
+        .trim_end_matches(['+', '#', '!', '?']);
+
+    let legal_moves = game.legal_moves();
+    let castle = match trimmed {
+        "O-O" | "0-0" => Some(Castle::Kingside),
+        "O-O-O" | "0-0-0" => Some(Castle::Queenside),
+        _ => None,
+    };
+    if let Some(castle) = castle {
+        return legal_moves
+            .into_iter()
+            .find(|mv| *mv == Move::Castle(castle))
+            .ok_or_else(|| format!("{} is not a legal move", san));
+    }
+
+    // crazyhouse drops, like `N@f3`. pawn drops can leave out the piece, like `@e4`
+    if trimmed.contains('@') {
+        let notation = match trimmed.strip_prefix('@') {
+            Some(to) => format!("P@{}", to),
+            None => trimmed.to_string(),
+        };
+        return algebra_to_move(&notation, game)
+            .ok()
+            .filter(|mv| legal_moves.contains(mv))
+            .ok_or_else(|| format!("{} is not a legal move", san));
+    }
+
+    let mut chars: Vec<char> = trimmed
+        .chars()
+        .filter(|c| !matches!(c, 'x' | ':' | '-'))
+        .collect();
+    if chars.first() == Some(&'P') {
+        chars.remove(0);
+    }
+
+    let piece_type = match chars.first().and_then(|c| piece_type_from_san(*c)) {
+        Some(piece_type) => {
+            chars.remove(0);
+            piece_type
+        }
+        None => PieceType::Pawn,
+    };
+
+    let mut promotion = None;
+    if piece_type == PieceType::Pawn {
+        if let Some(last) = chars.last() {
+            if let Some(promote_type) = piece_type_from_san(last.to_ascii_uppercase()) {
+                promotion = Some(promote_type.to_char());
+                chars.pop();
+                if chars.last() == Some(&'=') {
+                    chars.pop();
+                }
+            }
+        }
+    }
+
+    if chars.len() < 2 {
+        return Err(format!("{} is invalid san", san));
+    }
+    let to: String = chars[chars.len() - 2..].iter().collect();
+    let disambiguation = &chars[..chars.len() - 2];
+    let from_file = disambiguation.iter().find(|c| c.is_ascii_lowercase());
+    let from_rank = disambiguation.iter().find(|c| c.is_ascii_digit());
+
+    let matches: Vec<Move> = legal_moves
+        .into_iter()
+        .filter(|mv| {
+            if matches!(mv, Move::Castle(_) | Move::Drop(_)) {
+                return false;
+            }
+            let (mv_from, mv_to, mv_promotion) = coordinates(game, *mv);
+            let mv_piece_type = game
+                .position()
+                .at(square_from_coordinate(&mv_from))
+                .map(|pc| pc.piece_type());
+
+            mv_piece_type == Some(piece_type)
+                && mv_to == to
+                && mv_promotion == promotion
+                && from_file.is_none_or(|file| mv_from.starts_with(*file))
+                && from_rank.is_none_or(|rank| mv_from.ends_with(*rank))
+        })
+        .collect();
+
+    match matches.len() {
+        0 => algebra_to_move(&trimmed.to_lowercase(), game)
+            .ok()
+            .filter(|mv| game.legal_moves().contains(mv))
+            .ok_or_else(|| format!("{} is not a legal move", san)),
+        1 => Ok(matches[0]),
+        _ => Err(format!("{} is ambiguous", san)),
+    }
+}
+
+#[cfg(test)]
+pub mod test_san {
+    use super::*;
+    use crate::{fen::STARTING_POSITION_FEN, mv::DropMove, square::*, variant::Variant};
+
+    fn parse(fen: &str, san: &str) -> Result<String, String> {
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let stm = game.state().side_to_move();
+        parse_san(&mut game, san).map(|mv| move_to_algebra(mv, stm))
+    }
+
+    #[test]
+    fn pawn_and_piece_moves() {
+        assert_eq!(parse(STARTING_POSITION_FEN, "e4"), Ok("e2e4".to_string()));
+        assert_eq!(parse(STARTING_POSITION_FEN, "Nf3"), Ok("g1f3".to_string()));
+        assert!(parse(STARTING_POSITION_FEN, "e5").is_err());
+        assert!(parse(STARTING_POSITION_FEN, "Ke2").is_err());
+    }
+
+    #[test]
+    fn captures_and_disambiguation() {
+        let fen = "r1bqkbnr/ppp2ppp/2n5/3pp3/4P3/2N2N2/PPPP1PPP/R1BQKB1R w KQkq - 0 4";
+        assert_eq!(parse(fen, "exd5"), Ok("e4d5".to_string()));
+        assert_eq!(parse(fen, "Nxd5"), Ok("c3d5".to_string()));
+        assert_eq!(parse(fen, "Nxe5"), Ok("f3e5".to_string()));
+
+        let fen = "4k3/8/8/8/8/8/4K3/R6R w - - 0 1";
+        assert!(parse(fen, "Rf1").is_err());
+        assert_eq!(parse(fen, "Rhf1"), Ok("h1f1".to_string()));
+        assert_eq!(parse(fen, "Raa8+"), Ok("a1a8".to_string()));
+    }
+
+    #[test]
+    fn castling_and_promotion() {
+        let fen = "r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1";
+        assert_eq!(parse(fen, "O-O"), Ok("e1g1".to_string()));
+        assert_eq!(parse(fen, "O-O-O"), Ok("e1c1".to_string()));
+        assert_eq!(parse(fen, "bxa8=Q+"), Ok("b7a8q".to_string()));
+        assert_eq!(parse(fen, "b8=N"), Ok("b7b8n".to_string()));
+        assert!(parse(fen, "b8").is_err());
+    }
+
+    #[test]
+    fn drops() {
+        let fen = "r1bqkb1r/pppp1ppp/5n2/4p3/8/8/PPPP1PPP/R1BQKBNR[NPn] w KQkq - 0 4";
+        let result = Game::from_variant_fen(fen, Variant::Crazyhouse);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let result = parse_san(&mut game, "@e4");
+        assert_eq!(result, Ok(Move::Drop(DropMove::new(E4, PieceType::Pawn))));
+        assert_eq!(game.move_to_san(result.unwrap()), "P@e4");
+
+        let result = parse_san(&mut game, "N@d5");
+        assert_eq!(result, Ok(Move::Drop(DropMove::new(D5, PieceType::Knight))));
+        assert_eq!(game.move_to_san(result.unwrap()), "N@d5");
+        assert!(parse_san(&mut game, "P@e8").is_err());
+        assert!(parse_san(&mut game, "B@e4").is_err());
+    }
+
+    fn san(fen: &str, notation: &str) -> String {
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let result = algebra_to_move(notation, &game);
+        assert!(result.is_ok());
+        game.move_to_san(result.unwrap())
+    }
+
+    #[test]
+    fn san_for_pieces_and_pawns() {
+        assert_eq!(san(STARTING_POSITION_FEN, "e2e4"), "e4");
+        assert_eq!(san(STARTING_POSITION_FEN, "g1f3"), "Nf3");
+
+        let fen = "r1bqkbnr/ppp2ppp/2n5/3pp3/4P3/2N2N2/PPPP1PPP/R1BQKB1R w KQkq - 0 4";
+        assert_eq!(san(fen, "e4d5"), "exd5");
+        assert_eq!(san(fen, "f3e5"), "Nxe5");
+        assert_eq!(san(fen, "c3d5"), "Nxd5");
+
+        let fen = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3";
+        assert_eq!(san(fen, "e5f6"), "exf6");
+    }
+
+    #[test]
+    fn san_disambiguation() {
+        let fen = "4k3/8/8/8/8/8/4K3/R6R w - - 0 1";
+        assert_eq!(san(fen, "h1f1"), "Rhf1");
+        assert_eq!(san(fen, "a1a8"), "Ra8+");
+
+        let fen = "4k3/8/8/R7/8/8/4K3/R7 w - - 0 1";
+        assert_eq!(san(fen, "a1a3"), "R1a3");
+
+        let fen = "4k3/8/8/8/8/Q1Q5/4K3/Q7 w - - 0 1";
+        assert_eq!(san(fen, "a3b2"), "Qa3b2");
+        assert_eq!(san(fen, "c3b2"), "Qcb2");
+    }
+
+    #[test]
+    fn san_castling_promotion_and_mate() {
+        let fen = "r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1";
+        assert_eq!(san(fen, "e1g1"), "O-O");
+        assert_eq!(san(fen, "e1c1"), "O-O-O");
+        assert_eq!(san(fen, "b7a8q"), "bxa8=Q+");
+        assert_eq!(san(fen, "b7b8n"), "b8=N");
+
+        let fen = "6k1/5ppp/8/8/8/8/8/R3K3 w - - 0 1";
+        assert_eq!(san(fen, "a1a8"), "Ra8#");
+    }
+
+    #[test]
+    fn lenient_parsing() {
+        let fen = "r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1";
+        assert_eq!(parse(fen, "0-0"), Ok("e1g1".to_string()));
+        assert_eq!(parse(fen, "O-O!?"), Ok("e1g1".to_string()));
+        assert_eq!(parse(fen, "e1g1"), Ok("e1g1".to_string()));
+        assert_eq!(parse(fen, "bxa8Q"), Ok("b7a8q".to_string()));
+        assert_eq!(parse(fen, "b7a8q"), Ok("b7a8q".to_string()));
+        assert_eq!(parse(fen, "Ra1-d1"), Ok("a1d1".to_string()));
+
+        assert_eq!(parse(STARTING_POSITION_FEN, "Pe4"), Ok("e2e4".to_string()));
+        assert_eq!(
+            parse(STARTING_POSITION_FEN, "Ng1f3"),
+            Ok("g1f3".to_string())
+        );
+        assert!(parse(STARTING_POSITION_FEN, "").is_err());
+        assert!(parse(STARTING_POSITION_FEN, "Zz9").is_err());
+    }
+
+    #[test]
+    fn san_round_trip() {
+        let result =
+            Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        for mv in game.legal_moves() {
+            let san = game.move_to_san(mv);
+            assert_eq!(game.parse_san(&san), Ok(mv), "{}", san);
+        }
+    }
+}
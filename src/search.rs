@@ -1,12 +1,21 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
 use crate::piece_type::PIECE_TYPE_COUNT;
 use crate::{
-    eval::{eval, DRAW_SCORE, MAX_EVAL},
+    book::{next_random, Book, BookSelection},
+    eval::{eval, kpk, params::EvalParams, DRAW_SCORE, MAX_EVAL},
     game::Game,
-    move_gen::check_legal::LegalCheckPreprocessing,
+    move_gen::{check_legal::LegalCheckPreprocessing, legal::MoveGen},
     move_list::MoveList,
+    nnue::{accumulator::AccumulatorStack, Network},
     mv::{Decode, Move},
     piece_type::PieceType,
     side::Side,
+    syzygy::{Tablebase, Wdl},
+    variant::Variant,
 };
 
 pub const DEFAULT_DEPTH: Depth = 7;
@@ -37,6 +46,9 @@ const TT_MOVE_SORT_VAL: u8 = 60;
 const KILLER_MOVE_1_SORT_VAL: u8 = 9;
 const KILLER_MOVE_2_SORT_VAL: u8 = 8;
 
+// tablebase wins are scored above any eval but below mates found by the search
+const TB_WIN: Eval = MAX_EVAL / 2;
+
 type Eval = i32;
 
 #[derive(Debug)]
@@ -64,10 +76,70 @@ impl TtDetails {
     }
 }
 
+// what a sliced search knows after an iteration, or part way through one
+#[cfg(any(target_arch = "wasm32", test))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchProgress {
+    pub depth: Depth,
+    // from white's point of view, like the eval returned by get
+    pub eval: Eval,
+    pub pv: Vec<Move>,
+    // nodes visited by the whole search so far
+    pub nodes: u64,
+    // false when the slice ran out of nodes during the iteration. the move and eval are then the
+    // best of the root moves it got through, and the pv is only that move
+    pub finished: bool,
+}
+
+// a search that runs a slice at a time so it can share a thread with something else, like the
+// ui of a browser tab. a slice that runs out of nodes part way through an iteration leaves it to
+// the next slice, which starts it over with at least twice the nodes it got through. the tt
+// doesn't keep enough of an unfinished iteration to be sure the next try gets further, so without
+// the extra nodes slices that are too small for an iteration would never finish it. only the
+// browser client searches in slices
+#[cfg(any(target_arch = "wasm32", test))]
+pub struct SlicedSearch {
+    game: Game,
+    depth: Depth,
+    next_depth: Depth,
+    best: Option<(Move, Eval)>,
+    nodes: u64,
+    // the nodes the next slice gets at least, 0 unless the last slice cut an iteration short
+    retry_nodes: u64,
+}
+
+#[cfg(any(target_arch = "wasm32", test))]
+impl SlicedSearch {
+    pub fn best_move(&self) -> Option<Move> {
+        self.best.map(|(mv, _)| mv)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.next_depth > self.depth
+    }
+}
+
 pub struct MoveFinder {
     tt: TranspositionTable,
     depth: Depth,
     max_depth: Depth,
+    eval_params: EvalParams,
+    network: Option<Arc<Network>>,
+    use_nnue: bool,
+    // the accumulators of the line being searched, only there when the network is used
+    accumulators: Option<AccumulatorStack>,
+    tablebase: Option<Arc<Tablebase>>,
+    book: Option<Arc<Book>>,
+    own_book: bool,
+    book_selection: BookSelection,
+    book_seed: u64,
+    node_limit: Option<u64>,
+    move_time: Option<Duration>,
+    deadline: Option<Instant>,
+    nodes: u64,
+    // only set once the first iteration is done, so there is always a move to play
+    can_stop: bool,
+    stopped: bool,
 }
 
 impl MoveFinder {
@@ -76,6 +148,89 @@ impl MoveFinder {
             depth,
             max_depth,
             tt: TranspositionTable::new(),
+            eval_params: EvalParams::default(),
+            network: None,
+            use_nnue: false,
+            accumulators: None,
+            tablebase: None,
+            book: None,
+            own_book: false,
+            book_selection: BookSelection::WeightedRandom,
+            book_seed: 1,
+            node_limit: None,
+            move_time: None,
+            deadline: None,
+            nodes: 0,
+            can_stop: false,
+            stopped: false,
+        }
+    }
+
+    pub fn set_eval_params(&mut self, eval_params: EvalParams) {
+        self.eval_params = eval_params
+    }
+
+    pub fn set_network(&mut self, network: Option<Arc<Network>>) {
+        self.network = network
+    }
+
+    pub fn set_use_nnue(&mut self, use_nnue: bool) {
+        self.use_nnue = use_nnue
+    }
+
+    pub fn set_tablebase(&mut self, tablebase: Option<Arc<Tablebase>>) {
+        self.tablebase = tablebase
+    }
+
+    pub fn set_book(&mut self, book: Option<Arc<Book>>) {
+        self.book = book
+    }
+
+    pub fn set_own_book(&mut self, own_book: bool) {
+        self.own_book = own_book
+    }
+
+    pub fn set_book_selection(&mut self, book_selection: BookSelection) {
+        self.book_selection = book_selection
+    }
+
+    pub fn set_book_seed(&mut self, book_seed: u64) {
+        self.book_seed = book_seed
+    }
+
+    fn book_move(&mut self, game: &mut Game) -> Option<Move> {
+        if !self.own_book || game.state().variant() != Variant::Standard {
+            return None;
+        }
+        let book = self.book.as_ref()?;
+        self.book_seed = next_random(self.book_seed);
+
+        book.pick(game, self.book_selection, self.book_seed)
+    }
+
+    // the network is only used when it is turned on and one has been loaded
+    pub fn nnue_enabled(&self) -> bool {
+        self.use_nnue && self.network.is_some()
+    }
+
+    fn start_accumulators(&mut self, game: &Game) {
+        // the network, book, tablebases and kpk are all trained on or built for standard chess
+        self.accumulators = self
+            .network
+            .clone()
+            .filter(|_| self.use_nnue && game.state().variant() == Variant::Standard)
+            .map(|network| AccumulatorStack::new(network, game.position()));
+    }
+
+    fn push_accumulator(&mut self, game: &Game) {
+        if let Some(accumulators) = &mut self.accumulators {
+            accumulators.push(game.position());
+        }
+    }
+
+    fn pop_accumulator(&mut self) {
+        if let Some(accumulators) = &mut self.accumulators {
+            accumulators.pop();
         }
     }
 
@@ -87,6 +242,34 @@ impl MoveFinder {
         self.max_depth = depth
     }
 
+    // with a node or time limit the search deepens one ply at a time up to the search depth and
+    // plays the move from the last finished iteration
+    pub fn set_node_limit(&mut self, node_limit: Option<u64>) {
+        self.node_limit = node_limit
+    }
+
+    pub fn set_move_time(&mut self, move_time: Option<Duration>) {
+        self.move_time = move_time
+    }
+
+    // nodes visited by the last search
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    fn out_of_budget(&self) -> bool {
+        if !self.can_stop {
+            return false;
+        }
+
+        let out_of_nodes = self.node_limit.is_some_and(|limit| self.nodes >= limit);
+        // the clock is only read every so often since it's slow compared to a node
+        let out_of_time = self.nodes.is_multiple_of(1024)
+            && self.deadline.is_some_and(|time| Instant::now() >= time);
+
+        out_of_nodes || out_of_time
+    }
+
     pub fn score_moves_with_killer_moves(
         &self,
         game: &Game,
@@ -150,7 +333,7 @@ impl MoveFinder {
                                 [PieceType::Pawn.to_usize()]
                         }
                     }
-                    Move::DoublePawnPush(_) | Move::Castle(_) => {}
+                    Move::DoublePawnPush(_) | Move::Castle(_) | Move::Drop(_) => {}
                 }
             }
         }
@@ -207,7 +390,7 @@ impl MoveFinder {
                                 [PieceType::Pawn.to_usize()]
                         }
                     }
-                    Move::DoublePawnPush(_) | Move::Castle(_) => {}
+                    Move::DoublePawnPush(_) | Move::Castle(_) | Move::Drop(_) => {}
                 }
             }
         }
@@ -241,8 +424,37 @@ impl MoveFinder {
     }
 
     pub fn get(&mut self, game: &mut Game) -> Option<(Move, Eval)> {
+        self.nodes = 0;
+        // book moves aren't searched so there is no eval for them
+        if let Some(mv) = self.book_move(game) {
+            return Some((mv, 0));
+        }
+
         self.tt.update_age(&game);
+        self.start_accumulators(game);
+
+        self.stopped = false;
+        if self.node_limit.is_none() && self.move_time.is_none() {
+            return self.search_root(game, self.depth);
+        }
+
+        self.deadline = self.move_time.map(|move_time| Instant::now() + move_time);
+        let mut best = None;
+        for depth in 1..=self.depth {
+            self.can_stop = depth > 1;
+            let result = self.search_root(game, depth);
+            if self.stopped {
+                break;
+            }
+            best = result;
+        }
+        self.can_stop = false;
+        self.deadline = None;
+
+        best
+    }
 
+    fn search_root(&mut self, game: &mut Game, depth: Depth) -> Option<(Move, Eval)> {
         let mut best_move = None;
 
         let mut alpha = -MAX_EVAL;
@@ -251,32 +463,40 @@ impl MoveFinder {
         let stm = game.state().side_to_move();
 
         let legal_check_preprocessing = LegalCheckPreprocessing::from(game, stm);
-        let mut pseudo_legal_mv_list = if legal_check_preprocessing.num_of_checkers() == 0 {
-            game.pseudo_legal_moves(stm)
-        } else {
-            game.pseudo_legal_escape_moves(stm, &legal_check_preprocessing)
-        };
+        let mut mv_list = game.legal_move_list(MoveGen::All, &legal_check_preprocessing);
 
-        let mut killer_mv_table = KillerMoveTable::new(self.depth);
+        // quiescence searches check evasions at depth 1, even in a depth 1 search
+        let mut killer_mv_table = KillerMoveTable::new(depth.max(2));
 
-        let tt_mv_result = self.tt.probe_move(game.state().zobrist().to_u64(), self.depth);
-        let mut scores = self.score_moves(&game, &pseudo_legal_mv_list, tt_mv_result.as_ref());
+        // only search the moves that keep the tablebase result
+        let tb_root_moves = self
+            .tablebase
+            .as_ref()
+            .filter(|_| game.state().variant() == Variant::Standard)
+            .and_then(|tablebase| tablebase.root_moves(game));
 
-        for i in 0..pseudo_legal_mv_list.list().len() {
-            let mv = self.pick_move(&mut pseudo_legal_mv_list, &mut scores, i);
-            if !game.is_legal(mv, &legal_check_preprocessing) {
+        let tt_mv_result = self.tt.probe_move(game.state().zobrist().to_u64(), depth);
+        let mut scores = self.score_moves(&game, &mv_list, tt_mv_result.as_ref());
+
+        for i in 0..mv_list.list().len() {
+            let mv = self.pick_move(&mut mv_list, &mut scores, i);
+            if tb_root_moves
+                .as_ref()
+                .is_some_and(|tb_root_moves| !tb_root_moves.contains(&mv))
+            {
                 continue;
             }
 
             let prev_state = game.state().encode();
             let capture = game.make_move(mv);
+            self.push_accumulator(game);
 
             let eval: Eval = if game.is_draw() {
                 DRAW_SCORE.get(game.position().phase())
             } else {
                 -self.alpha_beta(
                     game,
-                    self.depth - 1,
+                    depth - 1,
                     -beta,
                     -alpha,
                     1,
@@ -285,12 +505,13 @@ impl MoveFinder {
                 )
             };
 
-            println!(
-                "eval: {eval}, mv: {mv}, sq score: {}",
-                game.position().sq_score(stm) - game.position().sq_score(stm.opposite())
-            );
-
             game.unmake_move(mv, capture, prev_state);
+            self.pop_accumulator();
+
+            // the move the search stopped in has no eval
+            if self.stopped {
+                break;
+            }
 
             if eval > alpha {
                 alpha = eval;
@@ -300,17 +521,21 @@ impl MoveFinder {
             }
         }
 
+        let eval = if stm == Side::White { alpha } else { -alpha };
+        // a stopped search gives the best of the moves it got through, if any, but it isn't exact
+        // so it stays out of the tt
+        if self.stopped {
+            return best_move.map(|mv| (mv, eval));
+        }
+
         self.tt.store(
             game.state().zobrist().to_u64(),
-            self.depth,
+            depth,
             TtFlag::Exact,
             alpha,
             best_move,
         );
-        Some((
-            best_move.unwrap(),
-            if stm == Side::White { alpha } else { -alpha },
-        ))
+        Some((best_move.unwrap(), eval))
     }
 
     fn alpha_beta(
@@ -324,10 +549,44 @@ impl MoveFinder {
         killer_mv_table: &mut KillerMoveTable,
         do_null_move: bool,
     ) -> Eval {
+        if self.stopped || self.out_of_budget() {
+            self.stopped = true;
+            return 0;
+        }
+        self.nodes += 1;
+
+        if game.is_variant_loss() {
+            return -(MAX_EVAL - levels_searched as Eval);
+        }
+
         if depth == 0 {
             return self.quiescence(game, alpha, beta, levels_searched, killer_mv_table);
         };
 
+        let variant = game.state().variant();
+
+        // kpk draws are known exactly so there is nothing to search
+        if variant == Variant::Standard
+            && kpk::probe(game.position(), game.state().side_to_move()) == Some(false)
+        {
+            return DRAW_SCORE.get(game.position().phase());
+        }
+
+        // tablebase positions are known exactly too. they are only probed right after a capture
+        // or pawn move since that's the only time the material changes
+        if variant == Variant::Standard && game.state().halfmoves() == 0 {
+            let wdl = self
+                .tablebase
+                .as_ref()
+                .and_then(|tablebase| tablebase.probe_wdl(game));
+            match wdl {
+                Some(Wdl::Win) => return TB_WIN - levels_searched as Eval,
+                Some(Wdl::Loss) => return -TB_WIN + levels_searched as Eval,
+                Some(_) => return DRAW_SCORE.get(game.position().phase()),
+                None => {}
+            }
+        }
+
         // get tt results
         let tt_val_result = self.tt.probe_val(game.state().zobrist().to_u64(), depth, alpha, beta);
 
@@ -335,11 +594,15 @@ impl MoveFinder {
             return tt_val;
         }
 
-        // null move pruning
+        // null move pruning. passing isn't safe in antichess where zugzwang is the whole game
         let stm = game.state().side_to_move();
 
         let legal_check_preprocessing = LegalCheckPreprocessing::from(game, stm);
-        if do_null_move && !legal_check_preprocessing.in_check() && depth > R {
+        if do_null_move
+            && !legal_check_preprocessing.in_check()
+            && depth > R
+            && variant != Variant::Antichess
+        {
             let en_passant_option = game.state().en_passant();
             game.make_null_move();
             let eval = -self.alpha_beta(
@@ -357,38 +620,30 @@ impl MoveFinder {
             }
         }
 
-        // getting pseudo legal moves
-        let mut pseudo_legal_mv_list = if legal_check_preprocessing.in_check() {
-            game.pseudo_legal_escape_moves(stm, &legal_check_preprocessing)
-        } else {
-            game.pseudo_legal_moves(stm)
-        };
+        // getting legal moves
+        let mut mv_list = game.legal_move_list(MoveGen::All, &legal_check_preprocessing);
 
         // scores moves
         let tt_mv_result = self.tt.probe_move(game.state().zobrist().to_u64(), depth);
         let mut scores = self.score_moves_with_killer_moves(
             &game,
-            &pseudo_legal_mv_list,
+            &mv_list,
             tt_mv_result.as_ref(),
             killer_mv_table,
             depth as usize,
         );
 
-        let mut legal_moves_available = false;
+        let legal_moves_available = !mv_list.list().is_empty();
 
         let mut tt_details = TtDetails::new();
         let mut found_pv = false;
 
-        for i in 0..pseudo_legal_mv_list.list().len() {
-            let mv = self.pick_move(&mut pseudo_legal_mv_list, &mut scores, i);
-            if !game.is_legal(mv, &legal_check_preprocessing) {
-                continue;
-            }
-
-            legal_moves_available = true;
+        for i in 0..mv_list.list().len() {
+            let mv = self.pick_move(&mut mv_list, &mut scores, i);
 
             let prev_state = game.state().encode();
             let capture = game.make_move(mv);
+            self.push_accumulator(game);
 
             let eval = if game.is_draw() {
                 DRAW_SCORE.get(game.position().phase())
@@ -430,6 +685,12 @@ impl MoveFinder {
             };
 
             game.unmake_move(mv, capture, prev_state);
+            self.pop_accumulator();
+
+            // the eval is meaningless once the search has been stopped
+            if self.stopped {
+                return 0;
+            }
 
             if eval >= beta {
                 // store lower bound for position
@@ -451,7 +712,10 @@ impl MoveFinder {
             }
         }
 
-        if !legal_moves_available && legal_check_preprocessing.in_check() {
+        if !legal_moves_available && variant == Variant::Antichess {
+            // running out of moves wins antichess
+            return MAX_EVAL - levels_searched as Eval;
+        } else if !legal_moves_available && legal_check_preprocessing.in_check() {
             return -(MAX_EVAL - levels_searched as Eval);
         } else if !legal_moves_available && DRAW_SCORE.get(game.position().phase()) > alpha {
             // is a stalemate
@@ -483,15 +747,35 @@ impl MoveFinder {
         levels_searched: u8,
         killer_mv_table: &mut KillerMoveTable,
     ) -> Eval {
+        if self.stopped || self.out_of_budget() {
+            self.stopped = true;
+            return 0;
+        }
+        self.nodes += 1;
+
         // defining variables we need for the rest of the function
         let stm = game.state().side_to_move();
         let legal_check_preprocessing = LegalCheckPreprocessing::from(game, stm);
 
-        if levels_searched == self.max_depth {
-            return eval(game, &legal_check_preprocessing, levels_searched);
+        if levels_searched >= self.max_depth {
+            return eval(
+                game,
+                &legal_check_preprocessing,
+                levels_searched,
+                &self.eval_params,
+                self.accumulators.as_ref().map(AccumulatorStack::top),
+            );
         }
 
-        if legal_check_preprocessing.in_check() {
+        // taking is compulsory in antichess so there's no standing pat when a capture can be
+        // made, all of the moves are captures then
+        let must_capture = game.state().variant() == Variant::Antichess
+            && game
+                .legal_move_list(MoveGen::Quiets, &legal_check_preprocessing)
+                .list()
+                .is_empty();
+
+        if legal_check_preprocessing.in_check() || must_capture {
             return self.alpha_beta(
                 game,
                 1,
@@ -504,7 +788,13 @@ impl MoveFinder {
         }
 
         // handle standpat score
-        let stand_pat = eval(game, &legal_check_preprocessing, levels_searched);
+        let stand_pat = eval(
+            game,
+            &legal_check_preprocessing,
+            levels_searched,
+            &self.eval_params,
+            self.accumulators.as_ref().map(AccumulatorStack::top),
+        );
         if stand_pat >= beta {
             return stand_pat;
         }
@@ -512,18 +802,15 @@ impl MoveFinder {
             alpha = stand_pat;
         }
 
-        let mut pseudo_legal_mv_list = game.pseudo_legal_loud_moves(stm);
+        let mut mv_list = game.legal_move_list(MoveGen::Captures, &legal_check_preprocessing);
 
-        let mut scores = self.score_moves(&game, &pseudo_legal_mv_list, None);
-
-        for i in 0..pseudo_legal_mv_list.list().len() {
-            let mv = self.pick_move(&mut pseudo_legal_mv_list, &mut scores, i);
-            if !game.is_legal(mv, &legal_check_preprocessing) {
-                continue;
-            }
+        let mut scores = self.score_moves(&game, &mv_list, None);
 
+        for i in 0..mv_list.list().len() {
+            let mv = self.pick_move(&mut mv_list, &mut scores, i);
             let prev_state = game.state().encode();
             let capture = game.make_move(mv);
+            self.push_accumulator(game);
 
             let eval: Eval = if game.is_draw() {
                 DRAW_SCORE.get(game.position().phase())
@@ -532,6 +819,7 @@ impl MoveFinder {
             };
 
             game.unmake_move(mv, capture, prev_state);
+            self.pop_accumulator();
 
             if eval >= beta {
                 return eval;
@@ -551,8 +839,113 @@ impl MoveFinder {
     }
 }
 
+#[cfg(any(target_arch = "wasm32", test))]
+impl MoveFinder {
+    // the search works on its own copy of the game, so the game can change while it runs
+    pub fn start_sliced(&mut self, game: &Game) -> SlicedSearch {
+        let mut game = game.clone();
+        let mut next_depth = 1;
+        let mut best = None;
+        // book moves aren't searched, and with no legal moves there is nothing to search
+        if game.legal_moves().is_empty() {
+            next_depth = self.depth + 1;
+        } else if let Some(mv) = self.book_move(&mut game) {
+            best = Some((mv, 0));
+            next_depth = self.depth + 1;
+        }
+
+        SlicedSearch {
+            game,
+            depth: self.depth,
+            next_depth,
+            best,
+            nodes: 0,
+            retry_nodes: 0,
+        }
+    }
+
+    // searches for about the given number of nodes and reports each iteration that finishes, and
+    // the best move so far of the one it stops in. the first iteration always runs to the end so
+    // there is a move to play, and a slice after one that cut an iteration short can run for more
+    // nodes than it was given, see SlicedSearch. the node and time limits of get don't apply, the
+    // caller decides how long the search runs. returns whether the search has reached its depth
+    pub fn search_slice(
+        &mut self,
+        search: &mut SlicedSearch,
+        nodes: u64,
+        mut on_progress: impl FnMut(&SearchProgress),
+    ) -> bool {
+        self.tt.update_age(&search.game);
+        self.start_accumulators(&search.game);
+
+        let node_limit = self.node_limit.replace(nodes.max(search.retry_nodes));
+        self.deadline = None;
+        self.nodes = 0;
+        self.stopped = false;
+        while !search.is_done() && !self.stopped {
+            let depth = search.next_depth;
+            self.can_stop = depth > 1;
+            let nodes_before = self.nodes;
+            let result = self.search_root(&mut search.game, depth);
+            let iteration_nodes = self.nodes - nodes_before;
+            search.nodes += iteration_nodes;
+            if self.stopped {
+                search.retry_nodes = 2 * iteration_nodes;
+                if let Some((mv, eval)) = result {
+                    on_progress(&SearchProgress {
+                        depth,
+                        eval,
+                        pv: vec![mv],
+                        nodes: search.nodes,
+                        finished: false,
+                    });
+                }
+                break;
+            }
+
+            search.best = result;
+            search.next_depth += 1;
+            search.retry_nodes = 0;
+            if let Some((_, eval)) = result {
+                on_progress(&SearchProgress {
+                    depth,
+                    eval,
+                    pv: self.pv(&mut search.game, depth),
+                    nodes: search.nodes,
+                    finished: true,
+                });
+            }
+        }
+        self.node_limit = node_limit;
+        self.can_stop = false;
+
+        search.is_done()
+    }
+
+    // follows the best moves stored in the tt from the root
+    fn pv(&self, game: &mut Game, depth: Depth) -> Vec<Move> {
+        let mut pv = vec![];
+        let mut undo = vec![];
+        while pv.len() < depth as usize {
+            let mv = match self.tt.probe_move(game.state().zobrist().to_u64(), 0) {
+                Some(mv) if game.legal_moves().contains(&mv) => mv,
+                _ => break,
+            };
+            pv.push(mv);
+            let prev_state = game.state().encode();
+            undo.push((mv, game.make_move(mv), prev_state));
+        }
+        for (mv, capture, prev_state) in undo.into_iter().rev() {
+            game.unmake_move(mv, capture, prev_state);
+        }
+
+        pv
+    }
+}
+
 #[cfg(test)]
 pub mod test_basic_tactics {
+    use crate::eval::piece_score;
     use crate::fen::STARTING_POSITION_FEN;
     use crate::mv::EncodedMove;
     use crate::piece_type::PieceType;
@@ -561,6 +954,65 @@ pub mod test_basic_tactics {
 
     use super::*;
 
+    #[test]
+    fn kpk_keeps_the_win() {
+        let fen = "4k3/8/4K3/4P3/8/8/8/8 w - - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let mut mv_finder = MoveFinder::new(DEFAULT_DEPTH, DEFAULT_MAX_DEPTH);
+
+        let best_move_result = mv_finder.get(&mut game);
+        assert!(best_move_result.is_some());
+        let (best_move, _) = best_move_result.unwrap();
+        game.make_move(best_move);
+
+        assert_eq!(
+            kpk::probe(game.position(), game.state().side_to_move()),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn kpk_draw() {
+        let fen = "k7/8/8/P7/8/8/8/7K w - - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let mut mv_finder = MoveFinder::new(DEFAULT_DEPTH, DEFAULT_MAX_DEPTH);
+
+        let best_move_result = mv_finder.get(&mut game);
+        assert!(best_move_result.is_some());
+        let (_, eval) = best_move_result.unwrap();
+        assert!(eval.abs() < 100);
+    }
+
+    #[test]
+    fn nnue_search() {
+        let fen = "r3rk2/pb4p1/4QbBp/1p1q4/2pP4/2P5/PP3PPP/R3R1K1 w - - 0 21";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let network = crate::nnue::test_nnue::test_network();
+        let mut mv_finder = MoveFinder::new(3, DEFAULT_MAX_DEPTH);
+        mv_finder.set_network(Some(network.clone()));
+        mv_finder.set_use_nnue(true);
+
+        // a mate in one is found whatever the network thinks of the position
+        let best_move_result = mv_finder.get(&mut game);
+        assert!(best_move_result.is_some());
+        let (best_move, _) = best_move_result.unwrap();
+        assert_eq!(best_move.to_string(), "e6e8");
+
+        // every move made during the search was popped again
+        let accumulators = mv_finder.accumulators.as_ref();
+        assert!(accumulators.is_some());
+        assert!(
+            *accumulators.unwrap().top()
+                == crate::nnue::accumulator::Accumulator::new(network, game.position())
+        );
+    }
+
     #[test]
     fn pos_1() {
         let fen = "r3rk2/pb4p1/4QbBp/1p1q4/2pP4/2P5/PP3PPP/R3R1K1 w - - 0 21";
@@ -615,7 +1067,22 @@ pub mod test_basic_tactics {
 
         assert!(best_move_result.is_some());
         let (best_move, _) = best_move_result.unwrap();
-        assert_ne!(best_move.to_string(), "d7d5")
+
+        // 1...d5 used to be ruled out here, but it doesn't drop a pawn since 2.exd5 is met by
+        // Qxd5, and it now scores within a centipawn of 1...e5. so instead of ruling out a move,
+        // white's best answer to whatever black picks mustn't win a pawn
+        game.make_move(best_move);
+        let mut mv_finder = MoveFinder::new(DEFAULT_DEPTH, DEFAULT_MAX_DEPTH);
+        let reply_result = mv_finder.get(&mut game);
+        assert!(reply_result.is_some());
+        let (reply, eval) = reply_result.unwrap();
+        assert!(
+            eval < 100,
+            "{} {} leaves white up {}",
+            best_move,
+            reply,
+            eval
+        )
     }
 
     #[test]
@@ -677,8 +1144,8 @@ pub mod test_basic_tactics {
         let (best_move, eval) = best_move_result.unwrap();
         println!(
             "white score: {}, black score: {}",
-            game.position().piece_score(Side::White),
-            game.position().piece_score(Side::Black)
+            piece_score(game.position(), Side::White, &EvalParams::default()),
+            piece_score(game.position(), Side::Black, &EvalParams::default())
         );
         println!("{}", game.position());
         println!("\nbest move: {}; eval: {}", best_move, eval);
@@ -701,8 +1168,8 @@ pub mod test_basic_tactics {
         let (best_move, eval) = best_move_result.unwrap();
         println!(
             "white score: {}, black score: {}",
-            game.position().piece_score(Side::White),
-            game.position().piece_score(Side::Black)
+            piece_score(game.position(), Side::White, &EvalParams::default()),
+            piece_score(game.position(), Side::Black, &EvalParams::default())
         );
         println!("{}", game.position());
         println!("\nbest move: {}; eval: {}", best_move, eval);
@@ -725,11 +1192,111 @@ pub mod test_basic_tactics {
         let (best_move, eval) = best_move_result.unwrap();
         println!(
             "white score: {}, black score: {}",
-            game.position().piece_score(Side::White),
-            game.position().piece_score(Side::Black)
+            piece_score(game.position(), Side::White, &EvalParams::default()),
+            piece_score(game.position(), Side::Black, &EvalParams::default())
         );
         println!("{}", game.position());
         println!("\nbest move: {}; eval: {}", best_move, eval);
         assert_eq!(best_move.to_string(), "d2d1q");
     }
 }
+
+#[cfg(test)]
+pub mod test_sliced_search {
+    use crate::mv::EncodedMove;
+    use crate::square::*;
+
+    use super::*;
+
+    #[test]
+    fn finds_the_same_move_in_slices() {
+        let fen = "r3rk2/pb4p1/4QbBp/1p1q4/2pP4/2P5/PP3PPP/R3R1K1 w - - 0 21";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        let mut mv_finder = MoveFinder::new(5, DEFAULT_MAX_DEPTH);
+
+        let mut search = mv_finder.start_sliced(&game);
+        let mut iterations: Vec<SearchProgress> = vec![];
+        let mut slices = 0;
+        while !mv_finder.search_slice(&mut search, 1000, |progress| {
+            iterations.push(progress.clone())
+        }) {
+            slices += 1;
+        }
+        let expected = Move::Piece(EncodedMove::new(E6, E8, PieceType::Queen, true));
+
+        assert!(slices > 1);
+        assert_eq!(search.best_move(), Some(expected));
+        let finished: Vec<&SearchProgress> = iterations
+            .iter()
+            .filter(|progress| progress.finished)
+            .collect();
+        assert_eq!(
+            finished
+                .iter()
+                .map(|progress| progress.depth)
+                .collect::<Vec<Depth>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+        let last = finished.last().unwrap();
+        assert_eq!(last.pv.first(), Some(&expected));
+        assert!(last.eval > 0);
+        assert!(iterations
+            .windows(2)
+            .all(|pair| pair[0].nodes <= pair[1].nodes));
+
+        // a finished search has nothing left to report
+        assert!(mv_finder.search_slice(&mut search, 1000, |_| panic!("already done")));
+    }
+
+    #[test]
+    fn finishes_with_tiny_slices() {
+        let fen = "r3rk2/pb4p1/4QbBp/1p1q4/2pP4/2P5/PP3PPP/R3R1K1 w - - 0 21";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        let mut mv_finder = MoveFinder::new(4, DEFAULT_MAX_DEPTH);
+
+        // far fewer nodes than any iteration past the first needs
+        let mut search = mv_finder.start_sliced(&game);
+        let mut iterations: Vec<SearchProgress> = vec![];
+        let mut slices = 0;
+        while !mv_finder.search_slice(&mut search, 10, |progress| {
+            iterations.push(progress.clone())
+        }) {
+            slices += 1;
+            assert!(slices < 1000, "the search isn't getting anywhere");
+        }
+
+        let partial: Vec<&SearchProgress> = iterations
+            .iter()
+            .filter(|progress| !progress.finished)
+            .collect();
+        assert!(!partial.is_empty());
+        assert!(partial.iter().all(|progress| progress.pv.len() == 1));
+        assert_eq!(
+            iterations
+                .iter()
+                .filter(|progress| progress.finished)
+                .map(|progress| progress.depth)
+                .collect::<Vec<Depth>>(),
+            vec![1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn nothing_to_search() {
+        let fen = "4k3/4Pn2/4K3/7B/8/8/8/8 b - - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        let mut mv_finder = MoveFinder::new(DEFAULT_DEPTH, DEFAULT_MAX_DEPTH);
+
+        let mut search = mv_finder.start_sliced(&game);
+
+        assert!(search.is_done());
+        assert!(mv_finder.search_slice(&mut search, 1000, |_| {}));
+        assert_eq!(search.best_move(), None);
+    }
+}
@@ -1,8 +1,5 @@
-use crate::square::*;
 use core::fmt;
 
-const ROOK_START_SQUARES: [(Square, Square); 2] = [(A1, H1), (A8, H8)];
-
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Side {
     White = 0,
@@ -24,10 +21,6 @@ impl Side {
         self as u8
     }
 
-    pub fn rook_start_squares(&self) -> (Square, Square) {
-        ROOK_START_SQUARES[self.to_usize()]
-    }
-
     pub fn opposite(self) -> Side {
         if self == Side::White {
             Side::Black
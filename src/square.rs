@@ -48,6 +48,11 @@ impl Square {
         Square((self.file()) | (rank * DISTANCE_BETWEEN_RANKS))
     }
 
+    pub fn change_file(self, file: Internal) -> Square {
+        assert!(file < DISTANCE_BETWEEN_RANKS);
+        Square((self.rank() * DISTANCE_BETWEEN_RANKS) | file)
+    }
+
     pub fn rank_down(self) -> Square {
         assert!(self.0 > H1.to_usize());
         Square(self.0 - DISTANCE_BETWEEN_RANKS)
@@ -1,15 +1,21 @@
 pub mod castle_rights;
+pub mod polyglot;
 pub mod position;
 pub mod zobrist;
 
 use core::fmt;
 use std::collections::HashMap;
 
-use self::{castle_rights::CastleRights, zobrist::Zobrist};
+use self::{
+    castle_rights::{CastleFiles, CastleRights, STANDARD_FILES},
+    zobrist::Zobrist,
+};
 use crate::{
+    bitboard::{self, BB},
     mv::castle::Castle,
     side::{Side, SIDE_MAP},
     square::{self, Square},
+    variant::Variant,
 };
 
 type ZobristTable = HashMap<u64, u8>;
@@ -18,6 +24,14 @@ pub struct State {
     en_passant: Option<Square>,
     side_to_move: Side,
     castle_rights: CastleRights,
+    // the castle files and variant don't change during a game so they aren't encoded
+    castle_files: CastleFiles,
+    variant: Variant,
+    // the checks each side has given, only counted in three-check
+    checks: [u8; 2],
+    // pieces that were pawns, which go back to the pocket as pawns when they are captured in
+    // crazyhouse
+    promoted: BB,
     zobrist: Zobrist,
     halfmoves: u16,
     fullmoves: u16,
@@ -37,6 +51,10 @@ impl State {
             en_passant,
             side_to_move,
             castle_rights,
+            castle_files: STANDARD_FILES,
+            variant: Variant::Standard,
+            checks: [0; 2],
+            promoted: bitboard::EMPTY,
             halfmoves,
             fullmoves,
             zobrist,
@@ -48,15 +66,29 @@ impl State {
         self.zobrist.hash_en_passant(self.en_passant);
         self.zobrist.hash_side(self.side_to_move);
         self.zobrist.hash_castle_rights_all(self.castle_rights);
+        self.hash_checks();
 
         self.en_passant = encoded_state.en_passant();
         self.side_to_move = encoded_state.side_to_move();
         self.castle_rights = encoded_state.castle_rights();
         self.halfmoves = encoded_state.halfmoves();
         self.fullmoves = encoded_state.fullmoves();
+        self.checks = [
+            encoded_state.checks_given(Side::White),
+            encoded_state.checks_given(Side::Black),
+        ];
+        self.set_promoted(encoded_state.promoted());
 
         self.zobrist.hash_en_passant(self.en_passant);
+        self.zobrist.hash_side(self.side_to_move);
         self.zobrist.hash_castle_rights_all(self.castle_rights);
+        self.hash_checks();
+    }
+
+    fn hash_checks(&mut self) {
+        for side in SIDE_MAP {
+            self.zobrist.hash_checks(side, self.checks[side.to_usize()]);
+        }
     }
 
     pub fn en_passant(&self) -> Option<Square> {
@@ -71,6 +103,47 @@ impl State {
         self.castle_rights
     }
 
+    pub fn castle_files(&self) -> CastleFiles {
+        self.castle_files
+    }
+
+    pub fn set_castle_files(&mut self, castle_files: CastleFiles) {
+        self.castle_files = castle_files;
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    pub fn set_variant(&mut self, variant: Variant) {
+        self.variant = variant;
+    }
+
+    pub fn checks_given(&self, side: Side) -> u8 {
+        self.checks[side.to_usize()]
+    }
+
+    pub fn set_checks_given(&mut self, side: Side, checks: u8) {
+        self.zobrist.hash_checks(side, self.checks[side.to_usize()]);
+        self.checks[side.to_usize()] = checks;
+        self.zobrist.hash_checks(side, checks);
+    }
+
+    pub fn add_check(&mut self, side: Side) {
+        self.set_checks_given(side, self.checks_given(side) + 1);
+    }
+
+    pub fn promoted(&self) -> BB {
+        self.promoted
+    }
+
+    pub fn set_promoted(&mut self, promoted: BB) {
+        for sq in (self.promoted ^ promoted).iter() {
+            self.zobrist.hash_promoted(sq);
+        }
+        self.promoted = promoted;
+    }
+
     pub fn halfmoves(&self) -> u16 {
         self.halfmoves
     }
@@ -152,11 +225,12 @@ impl State {
     }
 
     pub fn revert_side_to_move(&mut self) {
-        self.zobrist.hash_side(self.side_to_move);
-        self.side_to_move = self.side_to_move.opposite();
+        self.update_side_to_move();
     }
 
+    // the side key is only in the hash when black is to move
     pub fn update_side_to_move(&mut self) {
+        self.zobrist.hash_side(self.side_to_move);
         self.side_to_move = self.side_to_move.opposite();
         self.zobrist.hash_side(self.side_to_move);
     }
@@ -180,14 +254,16 @@ impl State {
             .or_insert(1);
     }
 
-    pub fn is_draw_by_repetition(&self, last_zobrist: Zobrist) -> bool {
-        let count_result = self.zobrist_table.get(&last_zobrist.to_u64());
+    // how many times the position with this zobrist has been on the board
+    pub fn repetitions(&self, zobrist: Zobrist) -> u8 {
+        self.zobrist_table
+            .get(&zobrist.to_u64())
+            .copied()
+            .unwrap_or(0)
+    }
 
-        if let Some(count) = count_result {
-            count > &2u8
-        } else {
-            false
-        }
+    pub fn is_draw_by_repetition(&self, last_zobrist: Zobrist) -> bool {
+        self.repetitions(last_zobrist) > 2
     }
 
     pub fn is_draw_by_halfmoves(&self) -> bool {
@@ -209,22 +285,30 @@ impl fmt::Display for State {
     }
 }
 
+// the promoted pieces don't fit in the rest of the state so they're kept whole next to it
 #[derive(Clone, Copy)]
-pub struct EncodedState(u32);
+pub struct EncodedState(u64, BB);
 
 impl EncodedState {
     pub fn new(state: &State) -> EncodedState {
         EncodedState(
-            state.side_to_move.to_u32() << 31
-                | state.castle_rights.to_u32() << 27
-                | state.en_passant.unwrap_or(square::NULL).to_u32() << 20
-                | (state.halfmoves as u32) << 10
-                | (state.fullmoves as u32),
+            (state.checks[Side::Black.to_usize()] as u64) << 34
+                | (state.checks[Side::White.to_usize()] as u64) << 32
+                | (state.side_to_move.to_u32() as u64) << 31
+                | (state.castle_rights.to_u32() as u64) << 27
+                | (state.en_passant.unwrap_or(square::NULL).to_u32() as u64) << 20
+                | (state.halfmoves as u64) << 10
+                | (state.fullmoves as u64),
+            state.promoted,
         )
     }
 
+    pub fn checks_given(&self, side: Side) -> u8 {
+        ((self.0 >> (32 + 2 * side.to_usize())) & 3) as u8
+    }
+
     pub fn side_to_move(&self) -> Side {
-        SIDE_MAP[(self.0 >> 31) as usize]
+        SIDE_MAP[((self.0 >> 31) & 1) as usize]
     }
 
     pub fn castle_rights(&self) -> CastleRights {
@@ -247,6 +331,10 @@ impl EncodedState {
     pub fn fullmoves(&self) -> u16 {
         (self.0 & 1023) as u16
     }
+
+    pub fn promoted(&self) -> BB {
+        self.1
+    }
 }
 
 #[cfg(test)]
@@ -355,4 +443,17 @@ pub mod test_zobrist_state {
 
         assert_eq!(expected, state.zobrist);
     }
+
+    #[test]
+    fn promoted_decode() {
+        let mut state = State::new(None, Side::White, castle_rights::NONE, 0, 0, Zobrist(0));
+        let encoded_state = state.encode();
+
+        state.set_promoted(BB::new(crate::square::H1));
+        assert_ne!(state.zobrist, Zobrist(0));
+
+        state.decode_from(encoded_state);
+        assert!(state.promoted().empty());
+        assert_eq!(state.zobrist, Zobrist(0));
+    }
 }
@@ -5,6 +5,7 @@ use crate::{
     bitboard::{self, BB},
     mv::castle::Castle,
     side::Side,
+    square::Square,
 };
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -61,6 +62,80 @@ impl CastleRights {
     }
 }
 
+// the files the king and rooks castle from. standard chess uses e, a and h, in chess960 they can be
+// any files with the king between the rooks. the king and rook always end up on the standard
+// castled squares
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CastleFiles {
+    king: [usize; 2],
+    // indexed by side then castle
+    rooks: [[usize; 2]; 2],
+}
+
+pub const STANDARD_FILES: CastleFiles = CastleFiles {
+    king: [4, 4],
+    rooks: [[0, 7], [0, 7]],
+};
+
+// the squares on the back rank from one file to the other, both included
+fn back_rank_span(side: Side, from_file: usize, to_file: usize) -> BB {
+    let rank = if side == Side::White { 0 } else { 7 };
+    (from_file.min(to_file)..=from_file.max(to_file)).fold(bitboard::EMPTY, |bb, file| {
+        bb | BB::new(Square::from(rank, file))
+    })
+}
+
+impl CastleFiles {
+    pub fn set(
+        self,
+        side: Side,
+        king_file: usize,
+        castle: Castle,
+        rook_file: usize,
+    ) -> CastleFiles {
+        let mut castle_files = self;
+        castle_files.king[side.to_usize()] = king_file;
+        castle_files.rooks[side.to_usize()][castle.to_usize()] = rook_file;
+        castle_files
+    }
+
+    pub fn is_standard(&self) -> bool {
+        *self == STANDARD_FILES
+    }
+
+    pub fn rook_file(&self, side: Side, castle: Castle) -> usize {
+        self.rooks[side.to_usize()][castle.to_usize()]
+    }
+
+    // returns the king squares in a tuple eg. (from, to)
+    pub fn king_squares(&self, side: Side, castle: Castle) -> (Square, Square) {
+        let (standard_from, to) = castle.king_squares(side);
+        (standard_from.change_file(self.king[side.to_usize()]), to)
+    }
+
+    // returns the rook squares in a tuple eg. (from, to)
+    pub fn rook_squares(&self, side: Side, castle: Castle) -> (Square, Square) {
+        let (standard_from, to) = castle.rook_squares(side);
+        (standard_from.change_file(self.rook_file(side, castle)), to)
+    }
+
+    // everything the king and rook move over or land on, other than themselves
+    pub fn must_clear_squares(&self, side: Side, castle: Castle) -> BB {
+        let (king_from, king_to) = self.king_squares(side, castle);
+        let (rook_from, rook_to) = self.rook_squares(side, castle);
+        let path = back_rank_span(side, king_from.file(), king_to.file())
+            | back_rank_span(side, rook_from.file(), rook_to.file());
+
+        path & !BB::new(king_from) & !BB::new(rook_from)
+    }
+
+    // the squares the king moves over or lands on, which can't be attacked
+    pub fn pass_through_squares(&self, side: Side, castle: Castle) -> BB {
+        let (king_from, king_to) = self.king_squares(side, castle);
+        back_rank_span(side, king_from.file(), king_to.file()) & !BB::new(king_from)
+    }
+}
+
 pub struct CastleRightsIterator(BB);
 impl Iterator for CastleRightsIterator {
     type Item = usize;
@@ -5,22 +5,30 @@ use crate::move_gen::is_sq_attacked;
 use crate::phase::Phase;
 use crate::piece::Piece;
 use crate::piece_type::{PieceType, PIECE_TYPE_COUNT};
-use crate::psqt::PSQT;
 use crate::side::*;
 use crate::square::Square;
 use crate::util::grid_to_string;
 
 pub type Board = [Option<Piece>; BOARD_LENGTH];
-pub type Scores = [i32; 2];
-
-#[derive(Clone, PartialEq, Copy)]
+// how many of each piece type a side holds to drop in crazyhouse, indexed by piece type
+pub type Pocket = [u8; PIECE_TYPE_COUNT];
+
+// the pieces that can be in a pocket, in the order they are generated and written in a fen
+pub const POCKET_PIECE_TYPES: [PieceType; 5] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Pawn,
+];
+
+#[derive(Clone, PartialEq)]
 pub struct Position {
     bb_sides: [BB; 2],
     bb_pieces: [BB; PIECE_TYPE_COUNT],
     board: [Option<Piece>; BOARD_LENGTH],
-    piece_score: Scores,
-    sq_score: Scores,
     phase: Phase,
+    pockets: [Pocket; 2],
 }
 impl Position {
     pub fn new(
@@ -29,17 +37,19 @@ impl Position {
         board: Board,
         phase: Phase,
     ) -> Position {
-        let (piece_score, sq_score) = Position::calc_score(board, phase);
         Position {
-            sq_score,
-            piece_score,
             bb_sides,
             bb_pieces,
             board,
             phase,
+            pockets: [[0; PIECE_TYPE_COUNT]; 2],
         }
     }
 
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
     pub fn phase(&self) -> Phase {
         self.phase
     }
@@ -72,12 +82,20 @@ impl Position {
         self.bb_pieces[piece_type.to_usize()] & self.bb_sides[side.to_usize()]
     }
 
-    pub fn piece_score(&self, side: Side) -> i32 {
-        self.piece_score[side.to_usize()]
+    pub fn pocket(&self, side: Side) -> Pocket {
+        self.pockets[side.to_usize()]
+    }
+
+    pub fn pocket_count(&self, side: Side, piece_type: PieceType) -> u8 {
+        self.pockets[side.to_usize()][piece_type.to_usize()]
+    }
+
+    pub fn set_pockets(&mut self, pockets: [Pocket; 2]) {
+        self.pockets = pockets;
     }
 
-    pub fn sq_score(&self, side: Side) -> i32 {
-        self.sq_score[side.to_usize()]
+    pub fn set_pocket_count(&mut self, side: Side, piece_type: PieceType, count: u8) {
+        self.pockets[side.to_usize()][piece_type.to_usize()] = count;
     }
 
     pub fn bb_sliders(&self, side: Side) -> (BB, BB) {
@@ -108,10 +126,6 @@ impl Position {
         );
 
         self.board[from.to_usize()] = None;
-
-        self.piece_score[side.to_usize()] -= piece_type.score() as i32;
-        self.sq_score[side.to_usize()] -=
-            PSQT[side.to_usize()][piece_type.to_usize()][from.to_usize()].get(self.phase);
     }
 
     pub fn remove_at(&mut self, sq: Square) -> Option<Piece> {
@@ -136,10 +150,6 @@ impl Position {
         debug_assert_ne!(self.bb_sides[side.to_usize()] & to_bb, bitboard::EMPTY);
 
         self.board[to.to_usize()] = Some(Piece::new(side, piece_type));
-
-        self.piece_score[side.to_usize()] += piece_type.score() as i32;
-        self.sq_score[side.to_usize()] +=
-            PSQT[side.to_usize()][piece_type.to_usize()][to.to_usize()].get(self.phase);
     }
 
     pub fn move_piece(&mut self, piece_type: PieceType, from: Square, to: Square, side: Side) {
@@ -191,23 +201,6 @@ impl Position {
 
         false
     }
-
-    pub fn calc_score(board: Board, phase: Phase) -> (Scores, Scores) {
-        let mut piece_score = [0; 2];
-        let mut sq_score = [0; 2];
-        for (sq, piece_result) in board.iter().enumerate() {
-            if let Some(piece) = piece_result {
-                let side = piece.side();
-                let piece_type = piece.piece_type();
-
-                piece_score[side.to_usize()] += piece_type.score() as i32;
-                sq_score[side.to_usize()] +=
-                    PSQT[side.to_usize()][piece_type.to_usize()][sq as usize].get(phase);
-            }
-        }
-
-        (piece_score, sq_score)
-    }
 }
 
 impl fmt::Display for Position {
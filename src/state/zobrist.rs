@@ -7,7 +7,10 @@ use crate::{
     square::Square,
 };
 
-use super::{castle_rights::CastleRights, position::Position};
+use super::{
+    castle_rights::CastleRights,
+    position::{Position, POCKET_PIECE_TYPES},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Zobrist(pub u64);
@@ -32,6 +35,24 @@ impl Zobrist {
         }
     }
 
+    // no key for no checks, so positions outside three-check keep their keys
+    pub fn hash_checks(&mut self, side: Side, checks: u8) {
+        if checks > 0 {
+            self.0 ^= CHECKS_KEY[side.to_usize()][checks as usize - 1];
+        }
+    }
+
+    // like the checks, an empty pocket has no key so positions outside crazyhouse keep theirs
+    pub fn hash_pocket(&mut self, side: Side, piece_type: PieceType, count: u8) {
+        if count > 0 {
+            self.0 ^= POCKET_KEY[side.to_usize()][piece_type.to_usize()][count as usize - 1];
+        }
+    }
+
+    pub fn hash_promoted(&mut self, sq: Square) {
+        self.0 ^= PROMOTED_KEY[sq.to_usize()];
+    }
+
     pub fn hash_castle_rights_all(&mut self, castle_rights: CastleRights) {
         for bit_set in castle_rights.iter() {
             self.0 ^= CASTLE_RIGHTS_KEY[bit_set];
@@ -63,6 +84,12 @@ impl Zobrist {
             }
         }
 
+        for side in SIDE_MAP {
+            for piece_type in POCKET_PIECE_TYPES {
+                zobrist.hash_pocket(side, piece_type, position.pocket_count(side, piece_type));
+            }
+        }
+
         let castle_map: [Castle; 2] = [Castle::Queenside, Castle::Kingside];
         for side in SIDE_MAP {
             for castle in castle_map {
@@ -158,6 +185,11 @@ const NUM_UNIQUE_PIECES: usize = 6;
 const NUM_SIDES: usize = 2;
 const NUM_FILES: usize = 8;
 const NUM_CASTLE_RIGHTS: usize = 4;
+const MAX_CHECKS: usize = 3;
+// every piece but the kings could be in one pocket
+const MAX_POCKET: usize = 30;
+// a king is never captured into a pocket
+const NUM_POCKET_PIECES: usize = 5;
 
 const PIECES_KEY: [[[u64; BOARD_SIZE]; NUM_UNIQUE_PIECES]; NUM_SIDES] = [
     [
@@ -978,6 +1010,413 @@ const CASTLE_RIGHTS_KEY: [u64; NUM_CASTLE_RIGHTS] = [
 
 const SIDE_KEY: u64 = 14998782759734077275;
 
+const CHECKS_KEY: [[u64; MAX_CHECKS]; NUM_SIDES] = [
+    [
+        14444818062078477998,
+        6077566062586341848,
+        1176345752787233938,
+    ],
+    [
+        8436295047071123616,
+        17567134936854982115,
+        14594833245389863190,
+    ],
+];
+
+const POCKET_KEY: [[[u64; MAX_POCKET]; NUM_POCKET_PIECES]; NUM_SIDES] = [
+    [
+        [
+            4066192373987086397,
+            8716639949739440730,
+            8075516613370422092,
+            1957053473565493239,
+            9121271871243074207,
+            1185625057615572594,
+            4716618758894963100,
+            12692335738881061655,
+            1585784699371156068,
+            3422178939070379636,
+            8232065539051848987,
+            5053238880887649622,
+            2869097961811949616,
+            10525007657997942915,
+            5415781665966159378,
+            3414727569930330212,
+            13210078608125000669,
+            17673487018855208962,
+            7548432242092500717,
+            9931182808661234296,
+            11805856458573243101,
+            2029818724293392878,
+            6139053639264963843,
+            15905425965807585745,
+            6138447029569045407,
+            16781864152580396629,
+            18391502472476335829,
+            5815394745901402361,
+            11415081466384144574,
+            3489205325085047364,
+        ],
+        [
+            12105439896977687492,
+            18195753866480890092,
+            461021669473946291,
+            6544769801442548535,
+            17219005189540337694,
+            972046344537180357,
+            16213125327234584514,
+            11152394853621313221,
+            10069100707766171168,
+            8331790944990897566,
+            16899287540829630704,
+            8507018016371054301,
+            10607367397397686918,
+            16726309907154875512,
+            1702580539270840754,
+            6567165374053150942,
+            16869155108876547216,
+            5572337369149287419,
+            1677557125231170305,
+            9538866509073069739,
+            105319422790789052,
+            12401766279652003284,
+            4726875177531380478,
+            664944205794880493,
+            14958408264398128240,
+            10224816481512248353,
+            16083118607375619797,
+            11345396540507878113,
+            11540198491404769228,
+            15393136299395661417,
+        ],
+        [
+            3517454148504862288,
+            702674102463492969,
+            13872347548281420371,
+            12574839941173627128,
+            5313466698671389887,
+            5669110628703858098,
+            12544633804454397310,
+            16840434227925758811,
+            2507473365051441618,
+            15587485249164421733,
+            9012967085041673772,
+            12861514129610222026,
+            16739640822926013748,
+            2348189301130251750,
+            5957158148056547451,
+            8797604927321932781,
+            16784555454239519319,
+            12026319562605703216,
+            17569937289901914711,
+            2626421651064376856,
+            15141224417219840404,
+            5589806387240186657,
+            4205150625809262981,
+            6458525534492730341,
+            1048089737321427518,
+            13978567529910254015,
+            13227735100980005045,
+            17115561886130488642,
+            4087452027917923181,
+            6706312180046473309,
+        ],
+        [
+            3044236650070634741,
+            8966471587718845705,
+            10208948583249226595,
+            4419369090946737487,
+            17409817115697155523,
+            14724772628564150557,
+            4242192649673844413,
+            7871633102372477753,
+            14661771744833318707,
+            18186139320408850126,
+            14281845545137779184,
+            1191663514361140511,
+            8452927213125045216,
+            4404527299048484326,
+            14031601235635147272,
+            5507176771833013077,
+            15726218786100701561,
+            9733148241182029672,
+            15019595034689163839,
+            15730731357968281275,
+            15896745572491548417,
+            3181029662197212676,
+            16770046089004548073,
+            5307825612365722546,
+            4272770660759335663,
+            12137335178071447500,
+            16558786187994363182,
+            17980600749577295897,
+            16625540868129949197,
+            2135848738708784828,
+        ],
+        [
+            14782432873494111478,
+            7797902393093165950,
+            11083991140777144959,
+            14510881346557091239,
+            1973580744758314402,
+            88080804923093571,
+            5625147899339820092,
+            17852597009191252331,
+            6899870770102573025,
+            16450856502031638789,
+            15093178566749678067,
+            12659754692510259353,
+            14302050128859045259,
+            45560613582234045,
+            3748403517758915952,
+            5008462973797725648,
+            9886892269467286356,
+            4788675389716753052,
+            7661968672590489298,
+            10620272195428183641,
+            3627954739245376862,
+            7391218082902471293,
+            4198231071231687010,
+            12739464938509162672,
+            4144129680160083897,
+            15098066007618984802,
+            571325149674109915,
+            5064190848305918890,
+            2175739185634776124,
+            4993657571840571199,
+        ],
+    ],
+    [
+        [
+            7044553762413716343,
+            3330530231568975633,
+            10345957077600789081,
+            16336914543090695612,
+            13172047729780359836,
+            16193736822383065200,
+            10522320189931243631,
+            7355435520512596060,
+            14153245711827787862,
+            15721730313828579913,
+            6366633678156486284,
+            10423413377270178582,
+            11415178668580591060,
+            15130411290742598668,
+            13947708488933375083,
+            2991362118142225244,
+            15709273052392621788,
+            8250528322305203391,
+            10830525478446464101,
+            8077185807360746755,
+            6979753709302885156,
+            12051916147985181795,
+            9178043006036654205,
+            18139490838567691820,
+            9256187507596820054,
+            1983507219932054691,
+            7323245120695859607,
+            13375064808348558487,
+            1639442485140762211,
+            3925694701396008831,
+        ],
+        [
+            14966797161287643876,
+            16733639693680825114,
+            14270660850163873356,
+            13910550047464994369,
+            17692895178749692363,
+            3536783808641915736,
+            5969732688646753345,
+            8894441592161343079,
+            4837686566329942928,
+            11735768812740657210,
+            10036453051185452007,
+            5702532530412177687,
+            14115843286179138521,
+            14613735792950517508,
+            5161069116498804061,
+            3280656453803563027,
+            3041535326157720149,
+            4733284255409729060,
+            17322070004318942424,
+            1032843916249399210,
+            2199949693201182312,
+            14369029159767636076,
+            17744148723542509596,
+            14396269041293477197,
+            1984903538600601188,
+            15779651312900047529,
+            4784081962748226522,
+            14433083427411785163,
+            421632157404062376,
+            3507182635427838392,
+        ],
+        [
+            10451977158531411927,
+            6003256866416289414,
+            9409880970285805818,
+            11879175102138706077,
+            1721977080363090268,
+            11166934092073471432,
+            4727594328143053037,
+            504106700075969213,
+            12870097224560122565,
+            14547517086836946580,
+            1900313664874850525,
+            16006516940130601876,
+            17335299254158216923,
+            17364314484507366690,
+            15719595222640226771,
+            16743699915146460585,
+            3283373504233907950,
+            1408006584494120365,
+            8206270897925957846,
+            528149383226034391,
+            555466253066322517,
+            14722452545769389289,
+            15855101086196997987,
+            16681697986813174135,
+            1937716807426130745,
+            17558617141972480893,
+            931890978576719046,
+            11477568706605087425,
+            16216523221262851182,
+            16680812908816612957,
+        ],
+        [
+            11270203557778165836,
+            15294300879654402489,
+            5705952121885522768,
+            1404721489671843095,
+            6654806055647071462,
+            12172623111105060864,
+            12332480368232139185,
+            5888050426002222071,
+            4291942635200892753,
+            2207120182431652568,
+            15214092045529064367,
+            1493486525246337762,
+            13877843403913059807,
+            6576279530646196147,
+            12452948764728597331,
+            11909065754965204172,
+            264619281690670121,
+            4000196864336745672,
+            491715840950527661,
+            3527406597711864089,
+            947308298572487252,
+            2812800477443805099,
+            13713283910667771058,
+            2794307009710856736,
+            5871347970359063628,
+            12998052033487406671,
+            14046820957250522744,
+            14840633681934435669,
+            7709840910587499137,
+            14749249608838035326,
+        ],
+        [
+            1187058077572036262,
+            11227239569596947188,
+            9825844767135410192,
+            14027866161830617533,
+            16128744588049562602,
+            16075057446318128322,
+            15013529295249104818,
+            8144679721391229515,
+            9373342181204530069,
+            13242831570864211780,
+            17449916798499916188,
+            18377300653008553459,
+            124062011830953830,
+            18169598219545919087,
+            4705823214994678146,
+            215897577693952582,
+            5434893448300069213,
+            9589976981475566950,
+            5384371709175459166,
+            6591588658577830964,
+            5786098774849927507,
+            10338502554653771015,
+            8302494336419478473,
+            5899794376257289439,
+            14898619419536153898,
+            14195609676158070812,
+            4328038070744971569,
+            1770157436971295694,
+            2071142140340810963,
+            3692094253672522317,
+        ],
+    ],
+];
+
+const PROMOTED_KEY: [u64; BOARD_SIZE] = [
+    96154625124374645,
+    2957916413206356910,
+    8025404602803801823,
+    4296878549194040381,
+    10248323955673647359,
+    533349372042370966,
+    14608775762110973333,
+    1923260535460569997,
+    6296650437186286612,
+    15316323025703972873,
+    4303476577997978203,
+    12115118220831989956,
+    11762952141589129409,
+    7035063602042864708,
+    2363347662509008472,
+    9946250031646600476,
+    5968646889787016457,
+    4186868883784166008,
+    9714120461899984382,
+    1637121104380329113,
+    6680281911362240053,
+    13421656627150773152,
+    16176252469048068623,
+    2781997380124216607,
+    15932577650724833966,
+    8401483167885656052,
+    16956057250863581125,
+    6198706217087713104,
+    7267209344738405680,
+    6668806197430027561,
+    9084236506664322511,
+    5707653459730146410,
+    15716438719986085765,
+    8364687150785047462,
+    1050262429937707161,
+    12197902846517929418,
+    3517370890086424078,
+    18139889976344673113,
+    3179318963618982736,
+    14227287242334820113,
+    315815756908923575,
+    14545208217152849312,
+    10155523323735694996,
+    67218063296360153,
+    9281714569208463224,
+    14741623918255442469,
+    12708246896072705274,
+    12388041840288794036,
+    13617038168292341541,
+    15460779181738454624,
+    3847023261063623084,
+    13767195628677879608,
+    2560535908332993189,
+    2961725287260736878,
+    15331538726961799610,
+    9191433537225113693,
+    12480656355265721686,
+    13583538786728744746,
+    5239823584328713892,
+    4427115354109077971,
+    4079391033774565019,
+    3442345372135880574,
+    2049973226946645948,
+    17773121661073547054,
+];
+
 #[cfg(test)]
 extern crate rand;
 
@@ -0,0 +1,512 @@
+mod table;
+
+use std::{collections::HashMap, env, fs, path::PathBuf, sync::OnceLock};
+
+use crate::{game::Game, move_gen::check_legal::LegalCheckPreprocessing, mv::Move, side::Side};
+
+use self::table::{material_name, Lookup, Table, TableInfo, TableType};
+
+// dtz ranks at the root, a win that converts within the fifty move rule is ranked the same
+// however long it takes so the search is free to pick between them
+const MAX_DTZ: i32 = 1 << 18;
+
+// win, draw, loss from the side to move's perspective. cursed wins and blessed losses are wins
+// and losses that the fifty move rule turns into draws
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Wdl {
+    Loss = -2,
+    BlessedLoss = -1,
+    Draw = 0,
+    CursedWin = 1,
+    Win = 2,
+}
+
+impl Wdl {
+    fn from_i32(value: i32) -> Wdl {
+        match value {
+            -2 => Wdl::Loss,
+            -1 => Wdl::BlessedLoss,
+            0 => Wdl::Draw,
+            1 => Wdl::CursedWin,
+            _ => Wdl::Win,
+        }
+    }
+}
+
+// the files of a table, which are only read the first time the table is probed
+struct TableFiles {
+    info: TableInfo,
+    wdl_path: PathBuf,
+    dtz_path: Option<PathBuf>,
+    wdl: OnceLock<Option<Table>>,
+    dtz: OnceLock<Option<Table>>,
+}
+
+fn load_table(path: &PathBuf, info: &TableInfo, table_type: TableType) -> Option<Table> {
+    let bytes = fs::read(path).ok()?;
+    Table::from_bytes(bytes, info.clone(), table_type).ok()
+}
+
+pub struct Tablebase {
+    tables: HashMap<String, TableFiles>,
+    max_pieces: usize,
+}
+
+impl Tablebase {
+    // `path` can hold several directories separated the same way as the PATH variable
+    pub fn open(path: &str) -> Result<Tablebase, String> {
+        let mut wdl_paths = HashMap::new();
+        let mut dtz_paths = HashMap::new();
+
+        for dir in env::split_paths(path) {
+            let entries = fs::read_dir(&dir)
+                .map_err(|err| format!("unable to read syzygy path {}: {}", dir.display(), err))?;
+
+            for entry in entries.flatten() {
+                let file_path = entry.path();
+                let (Some(name), Some(extension)) = (
+                    file_path.file_stem().and_then(|name| name.to_str()),
+                    file_path
+                        .extension()
+                        .and_then(|extension| extension.to_str()),
+                ) else {
+                    continue;
+                };
+
+                if extension == TableType::Wdl.extension() {
+                    wdl_paths.insert(name.to_string(), file_path.clone());
+                } else if extension == TableType::Dtz.extension() {
+                    dtz_paths.insert(name.to_string(), file_path.clone());
+                }
+            }
+        }
+
+        let mut tables = HashMap::new();
+        for (name, wdl_path) in wdl_paths {
+            let Ok(info) = TableInfo::from_name(&name) else {
+                continue;
+            };
+            let dtz_path = dtz_paths.remove(&name);
+            tables.insert(
+                name,
+                TableFiles {
+                    info,
+                    wdl_path,
+                    dtz_path,
+                    wdl: OnceLock::new(),
+                    dtz: OnceLock::new(),
+                },
+            );
+        }
+
+        if tables.is_empty() {
+            return Err(format!("no syzygy tables found in {}", path));
+        }
+
+        let max_pieces = tables
+            .values()
+            .map(|files| files.info.piece_count())
+            .max()
+            .unwrap();
+
+        Ok(Tablebase { tables, max_pieces })
+    }
+
+    pub fn table_count(&self) -> usize {
+        self.tables.len()
+    }
+
+    pub fn max_pieces(&self) -> usize {
+        self.max_pieces
+    }
+
+    // whether the position can be looked up at all, the tables don't know about castling
+    pub fn can_probe(&self, game: &Game) -> bool {
+        game.position().bb_occupied().count_ones() as usize <= self.max_pieces
+            && game.state().castle_rights().to_u32() == 0
+    }
+
+    // the table for the material on the board, and whether black has the pieces that the table
+    // stores as white's
+    fn files(&self, game: &Game) -> Option<(&TableFiles, bool)> {
+        let position = game.position();
+        if let Some(files) = self.tables.get(&material_name(position, Side::White)) {
+            return Some((files, false));
+        }
+        self.tables
+            .get(&material_name(position, Side::Black))
+            .map(|files| (files, true))
+    }
+
+    fn probe_table(&self, game: &Game, table_type: TableType, wdl: i32) -> Option<Lookup> {
+        // only the two kings left
+        if game.position().bb_occupied().count_ones() == 2 {
+            return Some(Lookup::Value(0));
+        }
+
+        let (files, black_stronger) = self.files(game)?;
+        let table = match table_type {
+            TableType::Wdl => files
+                .wdl
+                .get_or_init(|| load_table(&files.wdl_path, &files.info, table_type)),
+            TableType::Dtz => files.dtz.get_or_init(|| {
+                files
+                    .dtz_path
+                    .as_ref()
+                    .and_then(|path| load_table(path, &files.info, table_type))
+            }),
+        };
+
+        table.as_ref().map(|table| {
+            table.probe(
+                game.position(),
+                game.state().side_to_move(),
+                black_stronger,
+                wdl,
+            )
+        })
+    }
+
+    // the tables assume the side to move can't capture, so the captures (and with
+    // `check_zeroing_moves` the pawn moves) are searched first. also returns whether the best
+    // move is one that resets the fifty move counter
+    fn search(&self, game: &mut Game, check_zeroing_moves: bool) -> Option<(i32, bool)> {
+        let moves = game.legal_moves();
+        let mut move_count = 0;
+        let mut best_value = -2;
+
+        for mv in moves.iter() {
+            let prev_state = game.state().encode();
+            let capture = game.make_move(*mv);
+            let is_capture = capture.is_some() || matches!(mv, Move::EnPassant(_));
+
+            let zeroing = is_capture || (check_zeroing_moves && is_pawn_move(mv));
+            if !zeroing {
+                game.unmake_move(*mv, capture, prev_state);
+                continue;
+            }
+
+            move_count += 1;
+            let result = self.search(game, false);
+            game.unmake_move(*mv, capture, prev_state);
+            let value = -result?.0;
+
+            if value > best_value {
+                best_value = value;
+                if value >= 2 {
+                    return Some((value, true));
+                }
+            }
+        }
+
+        // when every move was searched there's no need to look at the table
+        let no_more_moves = move_count > 0 && move_count == moves.len();
+        let value = if no_more_moves {
+            best_value
+        } else {
+            match self.probe_table(game, TableType::Wdl, 0)? {
+                Lookup::Value(value) => value,
+                Lookup::ChangeStm => return None,
+            }
+        };
+
+        if best_value >= value {
+            return Some((best_value, best_value > 0 || no_more_moves));
+        }
+
+        Some((value, false))
+    }
+
+    pub fn probe_wdl(&self, game: &mut Game) -> Option<Wdl> {
+        if !self.can_probe(game) {
+            return None;
+        }
+
+        self.search(game, false)
+            .map(|(value, _)| Wdl::from_i32(value))
+    }
+
+    // plies to the next capture or pawn move with best play, positive when winning. the value can
+    // be off by one ply, and for positions on the edge of the fifty move rule it may be further
+    // off, so it should only be trusted to keep a won position won
+    fn dtz(&self, game: &mut Game) -> Option<i32> {
+        let (wdl, zeroing_best_move) = self.search(game, true)?;
+        if wdl == 0 {
+            return Some(0);
+        }
+        if zeroing_best_move {
+            return Some(dtz_before_zeroing(wdl));
+        }
+
+        match self.probe_table(game, TableType::Dtz, wdl)? {
+            Lookup::Value(dtz) => Some((dtz + 100 * (wdl == -1 || wdl == 1) as i32) * wdl.signum()),
+            // the table only has the other side to move, so look one ply ahead
+            Lookup::ChangeStm => {
+                let mut min_dtz = 0xffff;
+
+                for mv in game.legal_moves().iter() {
+                    let prev_state = game.state().encode();
+                    let capture = game.make_move(*mv);
+                    let zeroing = capture.is_some() || is_pawn_move(mv);
+
+                    let result = if zeroing {
+                        self.search(game, false)
+                            .map(|(value, _)| -dtz_before_zeroing(value))
+                    } else {
+                        self.dtz(game).map(|dtz| -dtz)
+                    };
+                    let mates = result == Some(1) && is_checkmate(game);
+                    game.unmake_move(*mv, capture, prev_state);
+
+                    let mut dtz = result?;
+                    if mates {
+                        min_dtz = 1;
+                    }
+                    if !zeroing {
+                        dtz += dtz.signum();
+                    }
+                    if dtz < min_dtz && dtz.signum() == wdl.signum() {
+                        min_dtz = dtz;
+                    }
+                }
+
+                Some(if min_dtz == 0xffff { -1 } else { min_dtz })
+            }
+        }
+    }
+
+    // the root moves that keep the best result the tables can guarantee, so a win is never
+    // thrown away to the fifty move rule. none if the position isn't in the tables
+    pub fn root_moves(&self, game: &mut Game) -> Option<Vec<Move>> {
+        if !self.can_probe(game) {
+            return None;
+        }
+
+        let halfmoves = game.state().halfmoves() as i32;
+        // after a repetition only the fastest wins are kept so the game can't go round in circles
+        let repeated = game
+            .state()
+            .zobrist_table()
+            .values()
+            .any(|count| *count > 1);
+
+        let mut ranked_moves = vec![];
+        for mv in game.legal_moves().iter() {
+            let prev_state = game.state().encode();
+            let capture = game.make_move(*mv);
+
+            let result = if game.state().halfmoves() == 0 {
+                self.search(game, false)
+                    .map(|(value, _)| dtz_before_zeroing(-value))
+            } else if game.is_draw() {
+                Some(0)
+            } else {
+                self.dtz(game).map(|dtz| match -dtz {
+                    dtz if dtz > 0 => dtz + 1,
+                    dtz if dtz < 0 => dtz - 1,
+                    _ => 0,
+                })
+            };
+            let mates = result == Some(2) && is_checkmate(game);
+            game.unmake_move(*mv, capture, prev_state);
+
+            let dtz = if mates { 1 } else { result? };
+            let rank = if dtz > 0 {
+                if dtz + halfmoves <= 99 && !repeated {
+                    MAX_DTZ
+                } else {
+                    MAX_DTZ - (dtz + halfmoves)
+                }
+            } else if dtz < 0 {
+                if -dtz * 2 + halfmoves < 100 {
+                    -MAX_DTZ
+                } else {
+                    -MAX_DTZ + (-dtz + halfmoves)
+                }
+            } else {
+                0
+            };
+
+            ranked_moves.push((*mv, rank));
+        }
+
+        let best_rank = ranked_moves.iter().map(|(_, rank)| *rank).max()?;
+
+        Some(
+            ranked_moves
+                .into_iter()
+                .filter(|(_, rank)| *rank == best_rank)
+                .map(|(mv, _)| mv)
+                .collect(),
+        )
+    }
+}
+
+// dtz of a position where the best move resets the fifty move counter
+fn dtz_before_zeroing(wdl: i32) -> i32 {
+    match wdl {
+        2 => 1,
+        1 => 101,
+        -1 => -101,
+        -2 => -1,
+        _ => 0,
+    }
+}
+
+fn is_pawn_move(mv: &Move) -> bool {
+    matches!(
+        mv,
+        Move::Pawn(_) | Move::DoublePawnPush(_) | Move::EnPassant(_) | Move::Promotion(_)
+    )
+}
+
+fn is_checkmate(game: &mut Game) -> bool {
+    let stm = game.state().side_to_move();
+    let legal_check_preprocessing = LegalCheckPreprocessing::from(game, stm);
+    game.is_checkmate(&legal_check_preprocessing)
+}
+
+#[cfg(test)]
+pub mod test_syzygy {
+    use super::*;
+
+    // KQvK, KRvK and KPvK with wdl and dtz, and KNvK with wdl only. they're written by
+    // resources/test/syzygy/generate.py
+    fn tablebase() -> Tablebase {
+        let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        d.push("resources/test/syzygy");
+        let result = Tablebase::open(d.to_str().unwrap());
+        assert!(result.is_ok());
+        result.unwrap()
+    }
+
+    fn load(fen: &str) -> Game {
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        result.unwrap()
+    }
+
+    #[test]
+    fn missing_path() {
+        assert!(Tablebase::open("/this/path/does/not/exist").is_err());
+    }
+
+    #[test]
+    fn opens_every_table() {
+        let tablebase = tablebase();
+        assert_eq!(tablebase.table_count(), 4);
+        assert_eq!(tablebase.max_pieces(), 3);
+
+        // four pieces aren't covered
+        let game = load("8/8/4k3/8/8/8/3QK2q/8 w - - 0 1");
+        assert!(!tablebase.can_probe(&game));
+    }
+
+    #[test]
+    fn wdl_of_known_positions() {
+        let tablebase = tablebase();
+
+        let mut game = load("8/8/4k3/8/8/8/3QK3/8 w - - 0 1");
+        assert_eq!(tablebase.probe_wdl(&mut game), Some(Wdl::Win));
+        let mut game = load("8/8/4k3/8/8/8/3QK3/8 b - - 0 1");
+        assert_eq!(tablebase.probe_wdl(&mut game), Some(Wdl::Loss));
+
+        // the rook is hanging
+        let mut game = load("8/8/8/8/8/3k4/3R4/6K1 b - - 0 1");
+        assert_eq!(tablebase.probe_wdl(&mut game), Some(Wdl::Draw));
+
+        let mut game = load("8/8/8/8/8/8/8/KNk5 w - - 0 1");
+        assert_eq!(tablebase.probe_wdl(&mut game), Some(Wdl::Draw));
+
+        // castling rights aren't in the tables
+        let mut game = load("8/8/4k3/8/8/8/8/R3K3 w Q - 0 1");
+        assert_eq!(tablebase.probe_wdl(&mut game), None);
+    }
+
+    #[test]
+    fn wdl_matches_kpk_bitbase() {
+        let tablebase = tablebase();
+
+        for fen in [
+            "4k3/8/4K3/4P3/8/8/8/8 w - - 0 1",
+            "8/8/4k3/8/4K3/4P3/8/8 w - - 0 1",
+            "8/8/4k3/8/4K3/4P3/8/8 b - - 0 1",
+            "k7/8/8/P7/8/8/8/7K w - - 0 1",
+            "8/8/3p4/3k4/8/3K4/8/8 b - - 0 1",
+            "8/8/3p4/3k4/8/3K4/8/8 w - - 0 1",
+        ] {
+            let mut game = load(fen);
+            let stm = game.state().side_to_move();
+            let kpk = crate::eval::kpk::probe(game.position(), stm).unwrap();
+            let pawn_side = if game.position().bb_side(Side::White).count_ones() == 2 {
+                Side::White
+            } else {
+                Side::Black
+            };
+            let expected = match (kpk, stm == pawn_side) {
+                (false, _) => Wdl::Draw,
+                (true, true) => Wdl::Win,
+                (true, false) => Wdl::Loss,
+            };
+            assert_eq!(tablebase.probe_wdl(&mut game), Some(expected), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn dtz_agrees_with_wdl() {
+        let tablebase = tablebase();
+
+        for fen in [
+            "8/8/4k3/8/8/8/3QK3/8 w - - 0 1",
+            "8/8/4k3/8/8/8/3QK3/8 b - - 0 1",
+            "8/8/8/8/3k4/8/3PK3/8 w - - 0 1",
+            "8/8/8/8/3k4/8/3PK3/8 b - - 0 1",
+            "8/8/8/8/8/3k4/3R4/6K1 b - - 0 1",
+        ] {
+            let mut game = load(fen);
+            let wdl = tablebase.probe_wdl(&mut game);
+            let dtz = tablebase.dtz(&mut game);
+            assert!(wdl.is_some() && dtz.is_some(), "{}", fen);
+            assert_eq!(
+                (wdl.unwrap() as i32).signum(),
+                dtz.unwrap().signum(),
+                "{}",
+                fen
+            );
+        }
+    }
+
+    #[test]
+    fn dtz_of_mates() {
+        let tablebase = tablebase();
+
+        let mut game = load("k7/8/1K6/8/8/8/8/6Q1 w - - 0 1");
+        assert_eq!(tablebase.dtz(&mut game), Some(1));
+        let mut game = load("k7/8/1K6/8/8/8/8/6R1 w - - 0 1");
+        assert_eq!(tablebase.dtz(&mut game), Some(1));
+
+        // mate in 14
+        let mut game = load("8/8/8/3k4/8/8/8/R3K3 w - - 0 1");
+        assert_eq!(tablebase.dtz(&mut game), Some(27));
+        let mut game = load("8/8/8/3k4/8/8/8/R3K3 b - - 0 1");
+        assert_eq!(tablebase.dtz(&mut game), Some(-28));
+    }
+
+    #[test]
+    fn root_moves_keep_the_win() {
+        let tablebase = tablebase();
+
+        let mut game = load("8/8/4k3/8/8/8/3QK3/8 w - - 0 1");
+        let root_moves = tablebase.root_moves(&mut game);
+        assert!(root_moves.is_some());
+        let root_moves = root_moves.unwrap();
+        assert!(!root_moves.is_empty());
+
+        for mv in root_moves {
+            let prev_state = game.state().encode();
+            let capture = game.make_move(mv);
+            assert_eq!(tablebase.probe_wdl(&mut game), Some(Wdl::Loss), "{}", mv);
+            game.unmake_move(mv, capture, prev_state);
+        }
+    }
+}
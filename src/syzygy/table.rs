@@ -0,0 +1,1239 @@
+use std::sync::OnceLock;
+
+use crate::{
+    bitboard::{BB, KING_MOVES},
+    piece_type::PieceType,
+    side::Side,
+    square::Square,
+    state::position::Position,
+};
+
+// port of the table format and indexing from the syzygy probing code in stockfish
+// (https://github.com/syzygy1/tb), all offsets below are relative to the start of the file
+
+pub const WDL_MAGIC: [u8; 4] = [0x71, 0xe8, 0x23, 0x5d];
+pub const DTZ_MAGIC: [u8; 4] = [0xd7, 0x66, 0x0c, 0xa5];
+
+pub const MAX_PIECES: usize = 7;
+
+// table header flags
+const SPLIT: u8 = 1;
+const HAS_PAWNS: u8 = 2;
+
+// pairs data flags
+const STM: u8 = 1;
+const MAPPED: u8 = 2;
+const WIN_PLIES: u8 = 4;
+const LOSS_PLIES: u8 = 8;
+const WIDE: u8 = 16;
+const SINGLE_VALUE: u8 = 128;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TableType {
+    Wdl,
+    Dtz,
+}
+
+impl TableType {
+    pub fn extension(self) -> &'static str {
+        match self {
+            TableType::Wdl => "rtbw",
+            TableType::Dtz => "rtbz",
+        }
+    }
+
+    fn magic(self) -> [u8; 4] {
+        match self {
+            TableType::Wdl => WDL_MAGIC,
+            TableType::Dtz => DTZ_MAGIC,
+        }
+    }
+}
+
+// index tables shared by every table
+struct Encoding {
+    // squares below the a1-h8 diagonal, then the ones on it
+    map_b1h1h7: [u64; 64],
+    // squares in the a1-d1-d4 triangle, diagonal squares last
+    map_a1d1d4: [u64; 64],
+    // the 462 legal placements of two kings with the first king in the triangle
+    map_kk: [[u64; 64]; 10],
+    binomial: [[u64; 64]; 7],
+    // pawn squares numbered down from a2 and h2 towards the centre files, the leading pawn is the
+    // one with the highest number
+    map_pawns: [u64; 64],
+    lead_pawn_idx: [[u64; 64]; 6],
+    lead_pawns_size: [[u64; 4]; 6],
+}
+
+fn off_a1h8(sq: usize) -> i32 {
+    (sq / 8) as i32 - (sq % 8) as i32
+}
+
+fn edge_distance(file: usize) -> usize {
+    file.min(7 - file)
+}
+
+fn flip_diagonal(sq: usize) -> usize {
+    ((sq >> 3) | (sq << 3)) & 63
+}
+
+impl Encoding {
+    fn new() -> Encoding {
+        let mut encoding = Encoding {
+            map_b1h1h7: [0; 64],
+            map_a1d1d4: [0; 64],
+            map_kk: [[0; 64]; 10],
+            binomial: [[0; 64]; 7],
+            map_pawns: [0; 64],
+            lead_pawn_idx: [[0; 64]; 6],
+            lead_pawns_size: [[0; 4]; 6],
+        };
+
+        let mut code = 0;
+        for sq in 0..64 {
+            if off_a1h8(sq) < 0 {
+                encoding.map_b1h1h7[sq] = code;
+                code += 1;
+            }
+        }
+        for sq in 0..64 {
+            if off_a1h8(sq) == 0 {
+                encoding.map_b1h1h7[sq] = code;
+                code += 1;
+            }
+        }
+
+        let mut diagonal = vec![];
+        code = 0;
+        for sq in [0, 1, 2, 3, 9, 10, 11, 18, 19, 27] {
+            if off_a1h8(sq) < 0 {
+                encoding.map_a1d1d4[sq] = code;
+                code += 1;
+            } else if off_a1h8(sq) == 0 {
+                diagonal.push(sq);
+            }
+        }
+        for sq in diagonal {
+            encoding.map_a1d1d4[sq] = code;
+            code += 1;
+        }
+
+        // kings next to each other are left out, and so are placements that mirror onto one
+        // another along the diagonal
+        let mut both_on_diagonal = vec![];
+        code = 0;
+        for idx in 0..10 {
+            // squares outside the triangle are also mapped to 0, b1 is the one that really is
+            for s1 in (0..28)
+                .filter(|s1| encoding.map_a1d1d4[*s1] as usize == idx && (idx != 0 || *s1 == 1))
+            {
+                for s2 in 0..64 {
+                    if s1 == s2 || KING_MOVES[s1].is_set(Square(s2)) {
+                        continue;
+                    }
+                    if off_a1h8(s1) == 0 && off_a1h8(s2) > 0 {
+                        continue;
+                    }
+                    if off_a1h8(s1) == 0 && off_a1h8(s2) == 0 {
+                        both_on_diagonal.push((idx, s2));
+                    } else {
+                        encoding.map_kk[idx][s2] = code;
+                        code += 1;
+                    }
+                }
+            }
+        }
+        for (idx, s2) in both_on_diagonal {
+            encoding.map_kk[idx][s2] = code;
+            code += 1;
+        }
+
+        encoding.binomial[0][0] = 1;
+        for n in 1..64 {
+            for k in 0..7.min(n + 1) {
+                encoding.binomial[k][n] = if k > 0 {
+                    encoding.binomial[k - 1][n - 1]
+                } else {
+                    0
+                } + if k < n {
+                    encoding.binomial[k][n - 1]
+                } else {
+                    0
+                };
+            }
+        }
+
+        let mut available_squares: u64 = 48;
+        for lead_pawns_cnt in 1..=5 {
+            for file in 0..4 {
+                let mut idx = 0;
+                for rank in 1..7 {
+                    let sq = Square::from(rank, file).to_usize();
+                    if lead_pawns_cnt == 1 {
+                        available_squares -= 1;
+                        encoding.map_pawns[sq] = available_squares;
+                        available_squares -= 1;
+                        encoding.map_pawns[sq ^ 7] = available_squares;
+                    }
+                    encoding.lead_pawn_idx[lead_pawns_cnt][sq] = idx;
+                    idx += encoding.binomial[lead_pawns_cnt - 1][encoding.map_pawns[sq] as usize];
+                }
+                encoding.lead_pawns_size[lead_pawns_cnt][file] = idx;
+            }
+        }
+
+        encoding
+    }
+}
+
+fn encoding() -> &'static Encoding {
+    static ENCODING: OnceLock<Encoding> = OnceLock::new();
+    ENCODING.get_or_init(Encoding::new)
+}
+
+// what the name of a table says about it, like `KRPvKR`
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableInfo {
+    name: String,
+    piece_count: usize,
+    has_pawns: bool,
+    has_unique_pieces: bool,
+    // pawns of the leading side first
+    pawn_count: [usize; 2],
+    symmetric: bool,
+}
+
+impl TableInfo {
+    pub fn from_name(name: &str) -> Result<TableInfo, String> {
+        let sides: Vec<&str> = name.split('v').collect();
+        if sides.len() != 2
+            || sides
+                .iter()
+                .any(|side| !side.starts_with('K') || !side.chars().all(|c| "KQRBNP".contains(c)))
+        {
+            return Err(format!("invalid table name: {}", name));
+        }
+
+        let piece_count = name.len() - 1;
+        if piece_count > MAX_PIECES {
+            return Err(format!("too many pieces in table: {}", name));
+        }
+
+        let count = |side: &str, c: char| side.chars().filter(|piece| *piece == c).count();
+        let white_pawns = count(sides[0], 'P');
+        let black_pawns = count(sides[1], 'P');
+
+        // the leading side has the fewest pawns, but at least one
+        let white_leads = black_pawns == 0 || (white_pawns > 0 && black_pawns >= white_pawns);
+        let pawn_count = if white_leads {
+            [white_pawns, black_pawns]
+        } else {
+            [black_pawns, white_pawns]
+        };
+
+        let has_unique_pieces = sides
+            .iter()
+            .any(|side| "QRBNP".chars().any(|c| count(side, c) == 1));
+
+        Ok(TableInfo {
+            name: name.to_string(),
+            piece_count,
+            has_pawns: white_pawns + black_pawns > 0,
+            has_unique_pieces,
+            pawn_count,
+            symmetric: sides[0] == sides[1],
+        })
+    }
+
+    pub fn piece_count(&self) -> usize {
+        self.piece_count
+    }
+}
+
+// material of a position as a table name, starting with `first`
+pub fn material_name(position: &Position, first: Side) -> String {
+    [first, first.opposite()]
+        .iter()
+        .map(|side| {
+            let mut pieces = String::from("K");
+            for (piece_type, c) in [
+                (PieceType::Queen, 'Q'),
+                (PieceType::Rook, 'R'),
+                (PieceType::Bishop, 'B'),
+                (PieceType::Knight, 'N'),
+                (PieceType::Pawn, 'P'),
+            ] {
+                let count = position.bb_pc(piece_type, *side).count_ones() as usize;
+                pieces.extend(std::iter::repeat_n(c, count));
+            }
+            pieces
+        })
+        .collect::<Vec<String>>()
+        .join("v")
+}
+
+// piece codes as stored in the tables, black pieces have the 8 bit set
+fn piece_code(side: Side, piece_type: PieceType) -> u8 {
+    ((side == Side::Black) as u8) << 3 | (piece_type.to_u8() + 1)
+}
+
+#[derive(Clone, Debug, Default)]
+struct PairsData {
+    flags: u8,
+    block_size: usize,
+    span: usize,
+    num_blocks: usize,
+    max_sym_len: usize,
+    min_sym_len: usize,
+    num_syms: usize,
+    lowest_sym: usize,
+    btree: usize,
+    block_length: usize,
+    block_length_size: usize,
+    sparse_index: usize,
+    sparse_index_size: usize,
+    data: usize,
+    base64: Vec<u64>,
+    symlen: Vec<u8>,
+    pieces: [u8; MAX_PIECES],
+    group_idx: [u64; MAX_PIECES + 1],
+    group_len: [usize; MAX_PIECES + 1],
+    // dtz only, offsets into the value map for each of the four wdl results
+    map_idx: [usize; 4],
+}
+
+pub enum Lookup {
+    Value(i32),
+    // dtz tables only store one side to move, the other side has to be found with a search
+    ChangeStm,
+}
+
+pub struct Table {
+    info: TableInfo,
+    table_type: TableType,
+    bytes: Vec<u8>,
+    // indexed by side to move * 4 + file of the leading pawn
+    items: Vec<PairsData>,
+    map: usize,
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> usize {
+    bytes
+        .get(offset..offset + 2)
+        .map_or(0, |b| u16::from_le_bytes([b[0], b[1]]) as usize)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> usize {
+    bytes
+        .get(offset..offset + 4)
+        .map_or(0, |b| u32::from_le_bytes(b.try_into().unwrap()) as usize)
+}
+
+fn read_u32_be(bytes: &[u8], offset: usize) -> u64 {
+    bytes
+        .get(offset..offset + 4)
+        .map_or(0, |b| u32::from_be_bytes(b.try_into().unwrap()) as u64)
+}
+
+fn read_u64_be(bytes: &[u8], offset: usize) -> u64 {
+    (read_u32_be(bytes, offset) << 32) | read_u32_be(bytes, offset + 4)
+}
+
+fn byte(bytes: &[u8], offset: usize) -> Result<u8, String> {
+    bytes
+        .get(offset)
+        .copied()
+        .ok_or_else(|| "table file is truncated".to_string())
+}
+
+impl Table {
+    pub fn from_bytes(
+        bytes: Vec<u8>,
+        info: TableInfo,
+        table_type: TableType,
+    ) -> Result<Table, String> {
+        if bytes.len() < 5 || bytes.len() % 64 != 16 {
+            return Err(format!("corrupt table file for {}", info.name));
+        }
+        if bytes[..4] != table_type.magic() {
+            return Err(format!(
+                "{}.{} is not a syzygy table",
+                info.name,
+                table_type.extension()
+            ));
+        }
+
+        let mut table = Table {
+            info,
+            table_type,
+            bytes,
+            items: vec![PairsData::default(); 8],
+            map: 0,
+        };
+        table.parse()?;
+
+        Ok(table)
+    }
+
+    fn sides(&self) -> usize {
+        if self.table_type == TableType::Wdl && !self.info.symmetric {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn max_file(&self) -> usize {
+        if self.info.has_pawns {
+            3
+        } else {
+            0
+        }
+    }
+
+    fn item_idx(&self, stm: usize, file: usize) -> usize {
+        (stm % self.sides()) * 4 + if self.info.has_pawns { file } else { 0 }
+    }
+
+    fn parse(&mut self) -> Result<(), String> {
+        let info = self.info.clone();
+        let bytes = std::mem::take(&mut self.bytes);
+        let result = self.parse_bytes(&bytes, &info);
+        self.bytes = bytes;
+        result
+    }
+
+    fn parse_bytes(&mut self, bytes: &[u8], info: &TableInfo) -> Result<(), String> {
+        let mut data = 4;
+        let flags = byte(bytes, data)?;
+        if (flags & HAS_PAWNS != 0) != info.has_pawns
+            || (self.table_type == TableType::Wdl && (flags & SPLIT != 0) == info.symmetric)
+        {
+            return Err(format!("table file doesn't match its name {}", info.name));
+        }
+        data += 1;
+
+        let sides = self.sides();
+        let both_sides_have_pawns = info.has_pawns && info.pawn_count[1] > 0;
+
+        for file in 0..=self.max_file() {
+            let order_byte = byte(bytes, data)?;
+            let pawn_order_byte = if both_sides_have_pawns {
+                byte(bytes, data + 1)?
+            } else {
+                0xff
+            };
+            let order = [
+                [order_byte & 0xf, pawn_order_byte & 0xf],
+                [order_byte >> 4, pawn_order_byte >> 4],
+            ];
+            data += 1 + both_sides_have_pawns as usize;
+
+            for k in 0..info.piece_count {
+                let pieces = byte(bytes, data)?;
+                for side in 0..sides {
+                    self.items[side * 4 + file].pieces[k] =
+                        if side == 0 { pieces & 0xf } else { pieces >> 4 };
+                }
+                data += 1;
+            }
+
+            for (side, order) in order.iter().enumerate().take(sides) {
+                set_groups(info, &mut self.items[side * 4 + file], *order, file);
+            }
+        }
+
+        data += data & 1;
+
+        for file in 0..=self.max_file() {
+            for side in 0..sides {
+                data = set_sizes(bytes, &mut self.items[side * 4 + file], data)?;
+            }
+        }
+
+        if self.table_type == TableType::Dtz {
+            self.map = data;
+            for file in 0..=self.max_file() {
+                let item = &mut self.items[file];
+                if item.flags & MAPPED == 0 {
+                    continue;
+                }
+                if item.flags & WIDE != 0 {
+                    data += data & 1;
+                    for i in 0..4 {
+                        item.map_idx[i] = (data - self.map) / 2 + 1;
+                        data += 2 * read_u16(bytes, data) + 2;
+                    }
+                } else {
+                    for i in 0..4 {
+                        item.map_idx[i] = data - self.map + 1;
+                        data += byte(bytes, data)? as usize + 1;
+                    }
+                }
+            }
+            data += data & 1;
+        }
+
+        for file in 0..=self.max_file() {
+            for side in 0..sides {
+                let item = &mut self.items[side * 4 + file];
+                item.sparse_index = data;
+                data += item.sparse_index_size * 6;
+            }
+        }
+
+        for file in 0..=self.max_file() {
+            for side in 0..sides {
+                let item = &mut self.items[side * 4 + file];
+                item.block_length = data;
+                data += item.block_length_size * 2;
+            }
+        }
+
+        for file in 0..=self.max_file() {
+            for side in 0..sides {
+                let item = &mut self.items[side * 4 + file];
+                data = (data + 0x3f) & !0x3f;
+                item.data = data;
+                data += item.num_blocks * item.block_size;
+            }
+        }
+
+        if data > bytes.len() {
+            return Err(format!("table file for {} is truncated", info.name));
+        }
+
+        Ok(())
+    }
+
+    // the stored value for a position, with white as the stronger side unless `black_stronger`.
+    // for dtz tables `wdl` is the wdl result of the position, which decides how the value is
+    // mapped back to a distance
+    pub fn probe(
+        &self,
+        position: &Position,
+        side_to_move: Side,
+        black_stronger: bool,
+        wdl: i32,
+    ) -> Lookup {
+        let Some((item_idx, tb_file, idx)) = self.index(position, side_to_move, black_stronger)
+        else {
+            return Lookup::ChangeStm;
+        };
+
+        let value = self.decompress_pairs(&self.items[item_idx], idx);
+
+        Lookup::Value(self.map_score(tb_file, value, wdl))
+    }
+
+    // the item the position is stored in, the file of the leading pawn and the index within the
+    // item. none when the position is stored with the other side to move
+    fn index(
+        &self,
+        position: &Position,
+        side_to_move: Side,
+        black_stronger: bool,
+    ) -> Option<(usize, usize, u64)> {
+        let encoding = encoding();
+
+        // tables with the same material on both sides only store white to move, and tables are
+        // stored with white as the stronger side, so the board is flipped to fit
+        let flip = (self.info.symmetric && side_to_move == Side::Black) || black_stronger;
+        let flip_color = if flip { 8 } else { 0 };
+        let flip_squares = if flip { 56 } else { 0 };
+        let stm = (flip as usize) ^ side_to_move.to_usize();
+
+        let mut squares = [0; MAX_PIECES];
+        let mut pieces = [0; MAX_PIECES];
+        let mut size = 0;
+        let mut lead_pawns_cnt = 0;
+        let mut lead_pawns = BB(0);
+        let mut tb_file = 0;
+
+        if self.info.has_pawns {
+            // the leading pawns belong to the side of the first piece stored in the table
+            let lead_code = self.items[0].pieces[0] ^ flip_color;
+            let lead_side = if lead_code & 8 == 0 {
+                Side::White
+            } else {
+                Side::Black
+            };
+            lead_pawns = position.bb_pc(PieceType::Pawn, lead_side);
+            for sq in lead_pawns.iter() {
+                squares[size] = sq.to_usize() ^ flip_squares;
+                size += 1;
+            }
+            lead_pawns_cnt = size;
+
+            let mut best = 0;
+            for i in 1..lead_pawns_cnt {
+                if encoding.map_pawns[squares[i]] > encoding.map_pawns[squares[best]] {
+                    best = i;
+                }
+            }
+            squares.swap(0, best);
+
+            tb_file = edge_distance(squares[0] % 8);
+        }
+
+        if self.table_type == TableType::Dtz {
+            let item = &self.items[self.item_idx(0, tb_file)];
+            let stores_both_sides = self.info.symmetric && !self.info.has_pawns;
+            if (item.flags & STM != 0) != (stm != 0) && !stores_both_sides {
+                return None;
+            }
+        }
+
+        for sq in (position.bb_occupied() ^ lead_pawns).iter() {
+            let (side, piece_type) = position.at(sq).unwrap().decode();
+            squares[size] = sq.to_usize() ^ flip_squares;
+            pieces[size] = piece_code(side, piece_type) ^ flip_color;
+            size += 1;
+        }
+
+        let item_idx = self.item_idx(stm, tb_file);
+        let item = &self.items[item_idx];
+
+        // orders the pieces the same way as the table so each group is together
+        for i in lead_pawns_cnt..size - 1 {
+            for j in i + 1..size {
+                if item.pieces[i] == pieces[j] {
+                    pieces.swap(i, j);
+                    squares.swap(i, j);
+                    break;
+                }
+            }
+        }
+
+        // mirror so the first piece is on files a to d
+        if squares[0] % 8 > 3 {
+            for sq in squares.iter_mut().take(size) {
+                *sq ^= 7;
+            }
+        }
+
+        let mut idx;
+        if self.info.has_pawns {
+            idx = encoding.lead_pawn_idx[lead_pawns_cnt][squares[0]];
+
+            squares[1..lead_pawns_cnt].sort_by_key(|sq| encoding.map_pawns[*sq]);
+            for (i, sq) in squares.iter().enumerate().take(lead_pawns_cnt).skip(1) {
+                idx += encoding.binomial[i][encoding.map_pawns[*sq] as usize];
+            }
+        } else {
+            // flip so the first piece is on ranks 1 to 4, then along the diagonal so it's in the
+            // a1-d1-d4 triangle. the first piece off the diagonal decides if the flip is needed
+            if squares[0] / 8 > 3 {
+                for sq in squares.iter_mut().take(size) {
+                    *sq ^= 56;
+                }
+            }
+
+            for i in 0..item.group_len[0] {
+                let off = off_a1h8(squares[i]);
+                if off == 0 {
+                    continue;
+                }
+                if off > 0 {
+                    for sq in squares.iter_mut().take(size).skip(i) {
+                        *sq = flip_diagonal(*sq);
+                    }
+                }
+                break;
+            }
+
+            idx = if self.info.has_unique_pieces {
+                self.unique_pieces_idx(&squares)
+            } else {
+                encoding.map_kk[encoding.map_a1d1d4[squares[0]] as usize][squares[1]]
+            };
+        }
+
+        idx *= item.group_idx[0];
+
+        // the rest of the groups are encoded as combinations of the squares still free
+        let mut group_start = item.group_len[0];
+        let mut remaining_pawns = self.info.has_pawns && self.info.pawn_count[1] > 0;
+        let mut next = 1;
+        while item.group_len[next] != 0 {
+            let len = item.group_len[next];
+            squares[group_start..group_start + len].sort();
+
+            let mut n = 0;
+            for i in 0..len {
+                let sq = squares[group_start + i];
+                let adjust = squares[..group_start].iter().filter(|s| sq > **s).count();
+                n += encoding.binomial[i + 1][sq - adjust - 8 * remaining_pawns as usize];
+            }
+
+            remaining_pawns = false;
+            idx += n * item.group_idx[next];
+            group_start += len;
+            next += 1;
+        }
+
+        Some((item_idx, tb_file, idx))
+    }
+
+    fn unique_pieces_idx(&self, squares: &[usize; MAX_PIECES]) -> u64 {
+        let encoding = encoding();
+        let adjust1 = (squares[1] > squares[0]) as u64;
+        let adjust2 = (squares[2] > squares[0]) as u64 + (squares[2] > squares[1]) as u64;
+        let [s0, s1, s2] = [squares[0], squares[1], squares[2]];
+
+        if off_a1h8(s0) != 0 {
+            (encoding.map_a1d1d4[s0] * 63 + (s1 as u64 - adjust1)) * 62 + s2 as u64 - adjust2
+        } else if off_a1h8(s1) != 0 {
+            (6 * 63 + (s0 / 8) as u64 * 28 + encoding.map_b1h1h7[s1]) * 62 + s2 as u64 - adjust2
+        } else if off_a1h8(s2) != 0 {
+            6 * 63 * 62
+                + 4 * 28 * 62
+                + (s0 / 8) as u64 * 7 * 28
+                + ((s1 / 8) as u64 - adjust1) * 28
+                + encoding.map_b1h1h7[s2]
+        } else {
+            6 * 63 * 62
+                + 4 * 28 * 62
+                + 4 * 7 * 28
+                + (s0 / 8) as u64 * 7 * 6
+                + ((s1 / 8) as u64 - adjust1) * 6
+                + ((s2 / 8) as u64 - adjust2)
+        }
+    }
+
+    fn decompress_pairs(&self, item: &PairsData, idx: u64) -> i32 {
+        let bytes = &self.bytes;
+        if item.flags & SINGLE_VALUE != 0 {
+            return item.min_sym_len as i32;
+        }
+
+        let idx = idx as usize;
+        let k = idx / item.span;
+        let mut block = read_u32(bytes, item.sparse_index + 6 * k);
+        let mut offset = read_u16(bytes, item.sparse_index + 6 * k + 4) as i64;
+        offset += (idx % item.span) as i64 - (item.span / 2) as i64;
+
+        let block_length = |block: usize| read_u16(bytes, item.block_length + 2 * block) as i64;
+        while offset < 0 {
+            block -= 1;
+            offset += block_length(block) + 1;
+        }
+        while offset > block_length(block) {
+            offset -= block_length(block) + 1;
+            block += 1;
+        }
+
+        let mut ptr = item.data + block * item.block_size;
+        let mut buf64 = read_u64_be(bytes, ptr);
+        ptr += 8;
+        let mut buf64_size = 64;
+        let mut sym;
+
+        loop {
+            let mut len = 0;
+            while buf64 < item.base64[len] {
+                len += 1;
+            }
+
+            sym = (buf64 - item.base64[len])
+                .checked_shr((64 - len - item.min_sym_len) as u32)
+                .unwrap_or(0) as usize;
+            sym += read_u16(bytes, item.lowest_sym + 2 * len);
+
+            if offset < item.symlen[sym] as i64 + 1 {
+                break;
+            }
+
+            offset -= item.symlen[sym] as i64 + 1;
+            len += item.min_sym_len;
+            buf64 <<= len;
+            buf64_size -= len;
+
+            if buf64_size <= 32 {
+                buf64_size += 32;
+                buf64 |= read_u32_be(bytes, ptr) << (64 - buf64_size);
+                ptr += 4;
+            }
+        }
+
+        // symbols are pairs of smaller symbols, walk down to the one at the offset
+        while item.symlen[sym] != 0 {
+            let left = btree_left(bytes, item.btree, sym);
+            if offset < item.symlen[left] as i64 + 1 {
+                sym = left;
+            } else {
+                offset -= item.symlen[left] as i64 + 1;
+                sym = btree_right(bytes, item.btree, sym);
+            }
+        }
+
+        btree_left(bytes, item.btree, sym) as i32
+    }
+
+    fn map_score(&self, tb_file: usize, value: i32, wdl: i32) -> i32 {
+        if self.table_type == TableType::Wdl {
+            return value - 2;
+        }
+
+        const WDL_MAP: [usize; 5] = [1, 3, 0, 2, 0];
+        let item = &self.items[self.item_idx(0, tb_file)];
+        let mut value = value;
+
+        if item.flags & MAPPED != 0 {
+            let idx = item.map_idx[WDL_MAP[(wdl + 2) as usize]] + value as usize;
+            value = if item.flags & WIDE != 0 {
+                read_u16(&self.bytes, self.map + 2 * idx) as i32
+            } else {
+                self.bytes[self.map + idx] as i32
+            };
+        }
+
+        // dtz is stored in full moves unless the flags say it's stored in plies
+        if (wdl == 2 && item.flags & WIN_PLIES == 0)
+            || (wdl == -2 && item.flags & LOSS_PLIES == 0)
+            || wdl == 1
+            || wdl == -1
+        {
+            value *= 2;
+        }
+
+        value + 1
+    }
+}
+
+fn btree_left(bytes: &[u8], btree: usize, sym: usize) -> usize {
+    let b = btree + 3 * sym;
+    ((byte(bytes, b + 1).unwrap_or(0) as usize & 0xf) << 8) | byte(bytes, b).unwrap_or(0) as usize
+}
+
+fn btree_right(bytes: &[u8], btree: usize, sym: usize) -> usize {
+    let b = btree + 3 * sym;
+    ((byte(bytes, b + 2).unwrap_or(0) as usize) << 4)
+        | (byte(bytes, b + 1).unwrap_or(0) as usize >> 4)
+}
+
+// the pieces of a table are split into groups, the first group is the leading pawns (or the two or
+// three pieces used for symmetry) and each following group is made of the same piece. `order`
+// says where the leading group and the other side's pawns come in the encoding
+fn set_groups(info: &TableInfo, item: &mut PairsData, order: [u8; 2], file: usize) {
+    let encoding = encoding();
+
+    let mut n = 0;
+    let mut first_len: i32 = if info.has_pawns {
+        0
+    } else if info.has_unique_pieces {
+        3
+    } else {
+        2
+    };
+    item.group_len[0] = 1;
+
+    for i in 1..info.piece_count {
+        first_len -= 1;
+        if first_len > 0 || item.pieces[i] == item.pieces[i - 1] {
+            item.group_len[n] += 1;
+        } else {
+            n += 1;
+            item.group_len[n] = 1;
+        }
+    }
+    n += 1;
+    item.group_len[n] = 0;
+
+    let both_sides_have_pawns = info.has_pawns && info.pawn_count[1] > 0;
+    let mut next = if both_sides_have_pawns { 2 } else { 1 };
+    let mut free_squares = 64
+        - item.group_len[0]
+        - if both_sides_have_pawns {
+            item.group_len[1]
+        } else {
+            0
+        };
+    let mut idx = 1;
+
+    let mut k = 0;
+    while next < n || k == order[0] as usize || k == order[1] as usize {
+        if k == order[0] as usize {
+            item.group_idx[0] = idx;
+            idx *= if info.has_pawns {
+                encoding.lead_pawns_size[item.group_len[0]][file]
+            } else if info.has_unique_pieces {
+                31332
+            } else {
+                462
+            };
+        } else if k == order[1] as usize {
+            item.group_idx[1] = idx;
+            idx *= encoding.binomial[item.group_len[1]][48 - item.group_len[0]];
+        } else {
+            item.group_idx[next] = idx;
+            idx *= encoding.binomial[item.group_len[next]][free_squares];
+            free_squares -= item.group_len[next];
+            next += 1;
+        }
+        k += 1;
+    }
+    item.group_idx[n] = idx;
+}
+
+// reads the huffman code lengths and the symbol tree, returning where the next item starts
+fn set_sizes(bytes: &[u8], item: &mut PairsData, mut data: usize) -> Result<usize, String> {
+    item.flags = byte(bytes, data)?;
+    data += 1;
+
+    if item.flags & SINGLE_VALUE != 0 {
+        item.num_blocks = 0;
+        item.block_length_size = 0;
+        item.span = 0;
+        item.sparse_index_size = 0;
+        item.min_sym_len = byte(bytes, data)? as usize;
+        return Ok(data + 1);
+    }
+
+    let tb_size = item.group_idx[item.group_len.iter().position(|len| *len == 0).unwrap()] as usize;
+
+    item.block_size = 1 << byte(bytes, data)?;
+    item.span = 1 << byte(bytes, data + 1)?;
+    item.sparse_index_size = tb_size.div_ceil(item.span);
+    let padding = byte(bytes, data + 2)? as usize;
+    item.num_blocks = read_u32(bytes, data + 3);
+    item.block_length_size = item.num_blocks + padding;
+    item.max_sym_len = byte(bytes, data + 7)? as usize;
+    item.min_sym_len = byte(bytes, data + 8)? as usize;
+    item.lowest_sym = data + 9;
+
+    if item.max_sym_len < item.min_sym_len {
+        return Err("invalid symbol lengths in table file".to_string());
+    }
+
+    // base64[i] is the lowest code of length min_sym_len + i, left aligned in 64 bits
+    let base64_size = item.max_sym_len - item.min_sym_len + 1;
+    let lowest_sym = |i: usize| read_u16(bytes, item.lowest_sym + 2 * i) as u64;
+    item.base64 = vec![0; base64_size];
+    for i in (0..base64_size - 1).rev() {
+        item.base64[i] = (item.base64[i + 1]
+            .wrapping_add(lowest_sym(i))
+            .wrapping_sub(lowest_sym(i + 1)))
+            / 2;
+    }
+    for i in 0..base64_size {
+        item.base64[i] = item.base64[i]
+            .checked_shl((64 - i - item.min_sym_len) as u32)
+            .unwrap_or(0);
+    }
+
+    data = item.lowest_sym + base64_size * 2;
+    item.num_syms = read_u16(bytes, data);
+    data += 2;
+    item.btree = data;
+
+    if item.btree + item.num_syms * 3 > bytes.len() {
+        return Err("table file is truncated".to_string());
+    }
+
+    item.symlen = vec![0; item.num_syms];
+    let mut visited = vec![false; item.num_syms];
+    for sym in 0..item.num_syms {
+        if !visited[sym] {
+            calc_symlen(bytes, item, sym, &mut visited);
+        }
+    }
+
+    Ok(data + item.num_syms * 3 + (item.num_syms & 1))
+}
+
+// number of values a symbol expands to, minus one
+fn calc_symlen(bytes: &[u8], item: &mut PairsData, sym: usize, visited: &mut [bool]) {
+    let right = btree_right(bytes, item.btree, sym);
+    if right == 0xfff {
+        item.symlen[sym] = 0;
+    } else {
+        let left = btree_left(bytes, item.btree, sym);
+        for child in [left, right] {
+            if child < item.num_syms && !visited[child] {
+                calc_symlen(bytes, item, child, visited);
+            }
+        }
+        let len = |child: usize| item.symlen.get(child).map_or(0, |len| *len as usize);
+        item.symlen[sym] = (len(left) + len(right) + 1) as u8;
+    }
+    visited[sym] = true;
+}
+
+#[cfg(test)]
+pub mod test_table {
+    use super::*;
+    use crate::game::Game;
+    use std::collections::HashMap;
+
+    #[test]
+    fn king_placements() {
+        let encoding = encoding();
+        let mut codes: Vec<u64> = vec![];
+        for idx in 0..10 {
+            for s2 in 0..64 {
+                codes.push(encoding.map_kk[idx][s2]);
+            }
+        }
+        assert_eq!(codes.iter().max(), Some(&461));
+    }
+
+    #[test]
+    fn binomials() {
+        let encoding = encoding();
+        assert_eq!(encoding.binomial[0][10], 1);
+        assert_eq!(encoding.binomial[1][10], 10);
+        assert_eq!(encoding.binomial[2][10], 45);
+        assert_eq!(encoding.binomial[3][62], 37820);
+    }
+
+    #[test]
+    fn lead_pawns() {
+        let encoding = encoding();
+        // one pawn on each of the six ranks of a file
+        assert_eq!(encoding.lead_pawns_size[1], [6; 4]);
+        assert_eq!(encoding.map_pawns[Square::from(1, 0).to_usize()], 47);
+        assert_eq!(encoding.map_pawns[Square::from(1, 7).to_usize()], 46);
+        assert_eq!(encoding.map_pawns[Square::from(2, 0).to_usize()], 45);
+    }
+
+    #[test]
+    fn table_names() {
+        let info = TableInfo::from_name("KRPvKR");
+        assert!(info.is_ok());
+        let info = info.unwrap();
+        assert_eq!(info.piece_count, 5);
+        assert!(info.has_pawns);
+        assert!(info.has_unique_pieces);
+        assert_eq!(info.pawn_count, [1, 0]);
+        assert!(!info.symmetric);
+
+        let info = TableInfo::from_name("KPvKPP").unwrap();
+        assert_eq!(info.pawn_count, [1, 2]);
+        let info = TableInfo::from_name("KPPvKP").unwrap();
+        assert_eq!(info.pawn_count, [1, 2]);
+
+        let info = TableInfo::from_name("KNNvKNN").unwrap();
+        assert!(info.symmetric);
+        assert!(!info.has_unique_pieces);
+
+        assert!(TableInfo::from_name("KRvKX").is_err());
+        assert!(TableInfo::from_name("KRK").is_err());
+        assert!(TableInfo::from_name("RvK").is_err());
+    }
+
+    #[test]
+    fn material_names() {
+        let result = Game::from_fen("8/8/4k3/2n5/8/3QK3/3P4/8 w - - 0 1");
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(material_name(game.position(), Side::White), "KQPvKN");
+        assert_eq!(material_name(game.position(), Side::Black), "KNvKQP");
+    }
+
+    fn load(fen: &str) -> Game {
+        let result = Game::from_fen_unchecked(fen);
+        assert!(result.is_ok());
+        result.unwrap()
+    }
+
+    // a table without any data, with the pieces stored in the given order
+    fn empty_table(name: &str, pieces: &[u8]) -> Table {
+        let result = TableInfo::from_name(name);
+        assert!(result.is_ok());
+        let info = result.unwrap();
+        let mut table = Table {
+            info: info.clone(),
+            table_type: TableType::Wdl,
+            bytes: vec![],
+            items: vec![PairsData::default(); 8],
+            map: 0,
+        };
+        for file in 0..=table.max_file() {
+            let item = &mut table.items[file];
+            item.pieces[..pieces.len()].copy_from_slice(pieces);
+            set_groups(&info, item, [0, 0xf], file);
+        }
+
+        table
+    }
+
+    // calls `f` with every legal placement of the three pieces on the board of `fen`, the
+    // squares are in the order the pieces are found from a1
+    fn placements(fen: &str, mut f: impl FnMut(&Position, [usize; 3])) {
+        let mut position = load(fen).position().clone();
+        let pieces: Vec<(Side, PieceType)> = position
+            .bb_occupied()
+            .iter()
+            .map(|sq| position.at(sq).unwrap().decode())
+            .collect();
+        let mut current: Vec<usize> = position.bb_occupied().iter().map(|sq| sq.0).collect();
+
+        for a in 0..64 {
+            for b in 0..64 {
+                for c in 0..64 {
+                    let squares = [a, b, c];
+                    if a == b || b == c || a == c {
+                        continue;
+                    }
+                    let kings: Vec<usize> = (0..3)
+                        .filter(|i| pieces[*i].1 == PieceType::King)
+                        .map(|i| squares[i])
+                        .collect();
+                    if KING_MOVES[kings[0]].is_set(Square(kings[1])) {
+                        continue;
+                    }
+                    if (0..3).any(|i| {
+                        pieces[i].1 == PieceType::Pawn && (squares[i] < 8 || squares[i] >= 56)
+                    }) {
+                        continue;
+                    }
+
+                    for i in 0..3 {
+                        position.remove_piece(pieces[i].1, Square(current[i]), pieces[i].0);
+                    }
+                    for i in 0..3 {
+                        position.place_piece(pieces[i].1, Square(squares[i]), pieces[i].0);
+                    }
+                    current = squares.to_vec();
+
+                    f(&position, squares);
+                }
+            }
+        }
+    }
+
+    fn symmetric_sq(sq: usize, symmetry: usize) -> usize {
+        let mut sq = sq;
+        if symmetry & 1 != 0 {
+            sq ^= 7;
+        }
+        if symmetry & 2 != 0 {
+            sq ^= 56;
+        }
+        if symmetry & 4 != 0 {
+            sq = flip_diagonal(sq);
+        }
+        sq
+    }
+
+    // every placement has an index within the table, and two placements only share an index when
+    // one is a reflection of the other
+    fn check_indexing(table: &Table, fen: &str, symmetries: &[usize]) {
+        let mut placement_of_idx = HashMap::new();
+        let mut idx_of_placement = HashMap::new();
+
+        placements(fen, |position, squares| {
+            let result = table.index(position, Side::White, false);
+            assert!(result.is_some());
+            let (item_idx, _, idx) = result.unwrap();
+            let item = &table.items[item_idx];
+            let size = item.group_idx[item.group_len.iter().position(|len| *len == 0).unwrap()];
+            assert!(idx < size, "{:?}", squares);
+
+            let canonical = symmetries
+                .iter()
+                .map(|symmetry| squares.map(|sq| symmetric_sq(sq, *symmetry)))
+                .min()
+                .unwrap();
+            assert_eq!(
+                *placement_of_idx.entry((item_idx, idx)).or_insert(canonical),
+                canonical,
+                "{:?}",
+                squares
+            );
+            assert_eq!(
+                *idx_of_placement.entry(canonical).or_insert((item_idx, idx)),
+                (item_idx, idx),
+                "{:?}",
+                squares
+            );
+        });
+    }
+
+    #[test]
+    fn unique_pieces_indexing() {
+        let table = empty_table("KQvK", &[6, 5, 14]);
+        check_indexing(
+            &table,
+            "8/8/8/8/8/8/8/KQk5 w - - 0 1",
+            &[0, 1, 2, 3, 4, 5, 6, 7],
+        );
+    }
+
+    #[test]
+    fn pawn_indexing() {
+        let table = empty_table("KPvK", &[1, 6, 14]);
+        check_indexing(&table, "8/8/8/8/8/8/P7/K1k5 w - - 0 1", &[0, 1]);
+    }
+
+    // a table written by resources/test/syzygy/generate.py
+    fn test_table(name: &str, table_type: TableType) -> Table {
+        let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("resources/test/syzygy");
+        path.push(format!("{}.{}", name, table_type.extension()));
+        let bytes = std::fs::read(path);
+        assert!(bytes.is_ok());
+        let result = Table::from_bytes(
+            bytes.unwrap(),
+            TableInfo::from_name(name).unwrap(),
+            table_type,
+        );
+        assert!(result.is_ok());
+        result.unwrap()
+    }
+
+    #[test]
+    fn tables_use_pair_symbols() {
+        for (name, table_type) in [
+            ("KQvK", TableType::Wdl),
+            ("KQvK", TableType::Dtz),
+            ("KRvK", TableType::Wdl),
+            ("KRvK", TableType::Dtz),
+            ("KPvK", TableType::Wdl),
+            ("KPvK", TableType::Dtz),
+        ] {
+            let table = test_table(name, table_type);
+            assert!(
+                table
+                    .items
+                    .iter()
+                    .any(|item| item.symlen.iter().any(|len| *len > 0)),
+                "{}.{}",
+                name,
+                table_type.extension()
+            );
+        }
+    }
+
+    // KQvK is won in at most 10 moves and KRvK in at most 16, so every value of the tables has
+    // to come out right for the longest distance to be 19 and 31 plies
+    #[test]
+    fn longest_wins() {
+        for (name, fen, longest) in [
+            ("KQvK", "8/8/8/8/8/8/8/KQk5 w - - 0 1", 19),
+            ("KRvK", "8/8/8/8/8/8/8/KRk5 w - - 0 1", 31),
+        ] {
+            let table = test_table(name, TableType::Dtz);
+            let mut max = 0;
+            placements(fen, |position, _| {
+                if let Lookup::Value(dtz) = table.probe(position, Side::White, false, 2) {
+                    max = max.max(dtz);
+                }
+            });
+            assert_eq!(max, longest, "{}", name);
+        }
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let info = TableInfo::from_name("KQvK").unwrap();
+        let mut bytes = vec![0; 64 + 16];
+        bytes[..4].copy_from_slice(&DTZ_MAGIC);
+        assert!(Table::from_bytes(bytes.clone(), info.clone(), TableType::Wdl).is_err());
+
+        bytes.pop();
+        assert!(Table::from_bytes(bytes, info, TableType::Dtz).is_err());
+    }
+}
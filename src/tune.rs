@@ -0,0 +1,452 @@
+use std::{
+    env, fs, mem,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::{
+    eval::{
+        eval,
+        params::{EvalParams, PARAM_NAMES},
+    },
+    game::Game,
+    move_gen::check_legal::LegalCheckPreprocessing,
+    piece_type::PieceType,
+    side::Side,
+};
+
+const DEFAULT_OUT_PATH: &str = "tuned_params.txt";
+const DEFAULT_MAX_ITERATIONS: usize = 100;
+
+// a quiet position along with the result of the game it was taken from, from white's perspective
+pub struct TuningPosition {
+    game: Game,
+    result: f64,
+}
+
+// parses a line in the form of `<fen> <result>`. halfmoves and fullmoves can be left out of the
+// fen, and the result can be written as 1-0, 0-1, 1/2-1/2 or as a number with optional decoration
+// so that both `[0.5]` and epd style `c9 "1/2-1/2";` lines are accepted
+pub fn parse_position(line: &str) -> Result<TuningPosition, String> {
+    let mut tokens = line.split_whitespace().peekable();
+
+    let mut fen_fields: Vec<&str> = tokens.by_ref().take(4).collect();
+    if fen_fields.len() != 4 {
+        return Err(format!("position is missing fen fields: {}", line));
+    }
+    while fen_fields.len() < 6 {
+        match tokens.peek() {
+            Some(token) if token.parse::<u16>().is_ok() => fen_fields.push(tokens.next().unwrap()),
+            _ => break,
+        }
+    }
+    if fen_fields.len() == 4 {
+        fen_fields.extend(["0", "1"]);
+    }
+
+    let result_str = tokens
+        .filter(|token| *token != "c9")
+        .collect::<String>()
+        .replace(['[', ']', '"', ';'], "");
+    let result = match result_str.as_str() {
+        "1-0" => 1.0,
+        "0-1" => 0.0,
+        "1/2-1/2" => 0.5,
+        num => match num.parse::<f64>() {
+            Ok(result) if (0.0..=1.0).contains(&result) => result,
+            _ => return Err(format!("invalid game result: {}", line)),
+        },
+    };
+
+    let game = Game::from_fen(&fen_fields.join(" "))?;
+
+    Ok(TuningPosition { game, result })
+}
+
+// positions that are already over are skipped since eval doesnt score them with the params
+pub fn load_positions(path: &str) -> Result<Vec<TuningPosition>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("unable to read positions from {}: {}", path, err))?;
+
+    let mut positions = vec![];
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut position = parse_position(line)?;
+        let stm = position.game.state().side_to_move();
+        let legal_check_preprocessing = LegalCheckPreprocessing::from(&mut position.game, stm);
+        if position.game.is_draw()
+            || position.game.is_checkmate(&legal_check_preprocessing)
+            || position.game.is_stalemate(&legal_check_preprocessing)
+        {
+            continue;
+        }
+
+        positions.push(position);
+    }
+
+    Ok(positions)
+}
+
+fn white_eval(game: &mut Game, params: &EvalParams) -> i32 {
+    let stm = game.state().side_to_move();
+    let legal_check_preprocessing = LegalCheckPreprocessing::from(game, stm);
+    let eval = eval(game, &legal_check_preprocessing, 0, params, None);
+
+    match stm {
+        Side::White => eval,
+        Side::Black => -eval,
+    }
+}
+
+// in the format EvalParams::load reads. only the tuner writes params, the engine just loads them
+fn save_params(params: &EvalParams, path: &str) -> Result<(), String> {
+    fs::write(path, params.to_string())
+        .map_err(|err| format!("unable to write eval params to {}: {}", path, err))
+}
+
+// maps a centipawn eval onto an expected game result between 0 and 1
+pub fn sigmoid(eval: f64, k: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-k * eval / 400.0))
+}
+
+fn squared_error(position: &mut TuningPosition, params: &EvalParams, k: f64) -> f64 {
+    let eval = white_eval(&mut position.game, params) as f64;
+    (position.result - sigmoid(eval, k)).powi(2)
+}
+
+type ErrorJob = (Arc<EvalParams>, f64);
+
+// the positions are split between worker threads once and each thread keeps its share, so the
+// thousands of error calculations in a tuning run only send the params to threads already running
+pub struct ErrorPool {
+    len: usize,
+    jobs: Vec<Sender<ErrorJob>>,
+    errors: Receiver<f64>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl ErrorPool {
+    pub fn new(mut positions: Vec<TuningPosition>, threads: usize) -> ErrorPool {
+        let len = positions.len();
+        let chunk_size = len.div_ceil(threads.max(1)).max(1);
+        let (error_sender, errors) = mpsc::channel();
+
+        let mut jobs = vec![];
+        let mut workers = vec![];
+        while !positions.is_empty() {
+            let rest = positions.split_off(chunk_size.min(positions.len()));
+            let mut chunk = mem::replace(&mut positions, rest);
+            let (job_sender, job_receiver) = mpsc::channel::<ErrorJob>();
+            let error_sender = error_sender.clone();
+
+            workers.push(thread::spawn(move || {
+                for (params, k) in job_receiver {
+                    let error = chunk
+                        .iter_mut()
+                        .map(|position| squared_error(position, &params, k))
+                        .sum();
+                    if error_sender.send(error).is_err() {
+                        break;
+                    }
+                }
+            }));
+            jobs.push(job_sender);
+        }
+
+        ErrorPool {
+            len,
+            jobs,
+            errors,
+            workers,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn mean_squared_error(&self, params: &EvalParams, k: f64) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+
+        let params = Arc::new(params.clone());
+        for job in &self.jobs {
+            job.send((params.clone(), k))
+                .expect("tuning worker stopped");
+        }
+        let total_error: f64 = (0..self.jobs.len())
+            .map(|_| self.errors.recv().expect("tuning worker stopped"))
+            .sum();
+
+        total_error / self.len as f64
+    }
+}
+
+impl Drop for ErrorPool {
+    fn drop(&mut self) {
+        // closing the job channels ends the workers' loops
+        self.jobs.clear();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+// finds the scaling constant that best fits the current params to the results so that the tuner
+// only moves the weights relative to each other
+pub fn find_k(pool: &ErrorPool, params: &EvalParams) -> f64 {
+    let mut best_k = 1.0;
+    let mut best_error = pool.mean_squared_error(params, best_k);
+
+    let mut step = 0.5;
+    for _ in 0..8 {
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for k in [best_k - step, best_k + step] {
+                if k <= 0.0 {
+                    continue;
+                }
+                let error = pool.mean_squared_error(params, k);
+                if error < best_error {
+                    best_k = k;
+                    best_error = error;
+                    improved = true;
+                }
+            }
+        }
+        step /= 2.0;
+    }
+
+    best_k
+}
+
+fn is_tunable(name: &str, i: usize) -> bool {
+    // the king is always on the board for both sides so its value never changes the eval
+    !(name == "piece_values" && i == PieceType::King.to_usize())
+}
+
+// texel's local search, every value is nudged up and down by one until none of the nudges lower
+// the error. `on_iteration` is called after each pass with the params found so far
+pub fn tune<F: FnMut(usize, &EvalParams, f64)>(
+    pool: &ErrorPool,
+    params: &EvalParams,
+    k: f64,
+    max_iterations: usize,
+    mut on_iteration: F,
+) -> EvalParams {
+    let mut best_params = params.clone();
+    let mut best_error = pool.mean_squared_error(&best_params, k);
+
+    for iteration in 1..=max_iterations {
+        let mut improved = false;
+
+        for name in PARAM_NAMES {
+            let len = best_params.values(name).unwrap().len();
+            for i in (0..len).filter(|i| is_tunable(name, *i)) {
+                for delta in [1, -1] {
+                    let mut values = best_params.values(name).unwrap();
+                    values[i] += delta;
+
+                    let mut new_params = best_params.clone();
+                    new_params.set_values(name, &values).unwrap();
+
+                    let error = pool.mean_squared_error(&new_params, k);
+                    if error < best_error {
+                        best_params = new_params;
+                        best_error = error;
+                        improved = true;
+                        break;
+                    }
+                }
+            }
+        }
+
+        on_iteration(iteration, &best_params, best_error);
+        if !improved {
+            break;
+        }
+    }
+
+    best_params
+}
+
+fn print_usage() {
+    println!("usage: tune <positions file> [--params <file>] [--out <file>] [--iterations <n>]");
+    println!("each line of the positions file is a quiet position fen followed by the game result");
+}
+
+pub fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let mut positions_path = None;
+    let mut params_path = None;
+    let mut out_path = DEFAULT_OUT_PATH.to_string();
+    let mut max_iterations = DEFAULT_MAX_ITERATIONS;
+
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--params" => params_path = args_iter.next().cloned(),
+            "--out" => match args_iter.next() {
+                Some(path) => out_path = path.clone(),
+                None => return print_usage(),
+            },
+            "--iterations" => match args_iter.next().map(|n| n.parse()) {
+                Some(Ok(n)) => max_iterations = n,
+                _ => return print_usage(),
+            },
+            path if positions_path.is_none() => positions_path = Some(path.to_string()),
+            _ => return print_usage(),
+        }
+    }
+
+    let Some(positions_path) = positions_path else {
+        return print_usage();
+    };
+
+    let params = match params_path {
+        Some(path) => EvalParams::load(&path),
+        None => Ok(EvalParams::default()),
+    };
+    let params = match params {
+        Ok(params) => params,
+        Err(err) => return println!("{}", err),
+    };
+
+    let positions = match load_positions(&positions_path) {
+        Ok(positions) => positions,
+        Err(err) => return println!("{}", err),
+    };
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let pool = ErrorPool::new(positions, threads);
+    println!("loaded {} positions", pool.len());
+
+    let k = find_k(&pool, &params);
+    println!(
+        "k: {}, starting error: {}",
+        k,
+        pool.mean_squared_error(&params, k)
+    );
+
+    tune(
+        &pool,
+        &params,
+        k,
+        max_iterations,
+        |iteration, params, error| {
+            println!("iteration: {}, error: {}", iteration, error);
+            if let Err(err) = save_params(params, &out_path) {
+                println!("{}", err);
+            }
+        },
+    );
+
+    println!("tuned params written to {}", out_path);
+}
+
+#[cfg(test)]
+pub mod test_tune {
+    use super::*;
+
+    #[test]
+    fn parse_bracket_result() {
+        let result =
+            parse_position("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1 [0.5]");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().result, 0.5);
+    }
+
+    #[test]
+    fn parse_epd_result() {
+        let result = parse_position("8/8/4k3/8/8/3QK3/8/8 w - - c9 \"1-0\";");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().result, 1.0);
+    }
+
+    #[test]
+    fn parse_plain_result() {
+        let result = parse_position("8/8/4k3/8/8/3qK3/8/8 w - - 12 40 0-1");
+        assert!(result.is_ok());
+        let position = result.unwrap();
+        assert_eq!(position.result, 0.0);
+        assert_eq!(position.game.state().halfmoves(), 12);
+    }
+
+    #[test]
+    fn invalid_result() {
+        assert!(parse_position("8/8/4k3/8/8/3QK3/8/8 w - - 0 1 2-0").is_err());
+        assert!(parse_position("8/8/4k3/8/8/3QK3/8/8 w - - 0 1").is_err());
+        assert!(parse_position("8/8/4k3/8/8/3QK3/8/8 w").is_err());
+    }
+
+    #[test]
+    fn sigmoid_is_centered() {
+        assert_eq!(sigmoid(0.0, 1.0), 0.5);
+        assert!(sigmoid(400.0, 1.0) > 0.9);
+        assert!(sigmoid(-400.0, 1.0) < 0.1);
+    }
+
+    #[test]
+    fn tune_does_not_increase_error() {
+        let positions: Vec<TuningPosition> = [
+            "8/8/4k3/8/8/3QK3/8/8 w - - 0 40 1-0",
+            "8/8/4k3/8/8/3qK3/8/8 w - - 0 40 0-1",
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1 1/2-1/2",
+        ]
+        .iter()
+        .map(|line| parse_position(line).unwrap())
+        .collect();
+
+        let pool = ErrorPool::new(positions, 2);
+        let params = EvalParams::default();
+        let k = find_k(&pool, &params);
+        let start_error = pool.mean_squared_error(&params, k);
+
+        let mut iterations = 0;
+        let tuned = tune(&pool, &params, k, 1, |_, _, _| iterations += 1);
+
+        assert_eq!(iterations, 1);
+        assert!(pool.mean_squared_error(&tuned, k) <= start_error);
+    }
+
+    #[test]
+    fn error_is_the_same_on_any_number_of_threads() {
+        let lines = [
+            "8/8/4k3/8/8/3QK3/8/8 w - - 0 40 1-0",
+            "8/8/4k3/8/8/3qK3/8/8 w - - 0 40 0-1",
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1 1/2-1/2",
+        ];
+        let pool = |threads| {
+            let positions = lines
+                .iter()
+                .map(|line| parse_position(line).unwrap())
+                .collect();
+            ErrorPool::new(positions, threads)
+        };
+        let params = EvalParams::default();
+        let error = pool(1).mean_squared_error(&params, 1.0);
+
+        assert!(error > 0.0);
+        for threads in [2, 3, 8] {
+            let error_on_threads = pool(threads).mean_squared_error(&params, 1.0);
+            assert!((error - error_on_threads).abs() < 1e-12);
+        }
+        assert_eq!(
+            ErrorPool::new(vec![], 4).mean_squared_error(&params, 1.0),
+            0.0
+        );
+    }
+}
@@ -1,21 +1,49 @@
-use std::io;
+use std::{
+    env, fs, io,
+    sync::Arc,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
 
 use crate::{
-    fen::STARTING_POSITION_FEN,
+    book::{Book, BookSelection},
+    eval::{kpk, params::EvalParams, MAX_EVAL},
+    fen::{EnPassantFen, STARTING_POSITION_FEN},
     game::Game,
     move_gen::pseudo_legal::is_double_pawn_push,
-    mv::{castle::Castle, Decode, EncodedMove, Move, PromotionMove},
-    perft::count_moves_debug,
+    nnue::Network,
+    mv::{castle::Castle, Decode, DropMove, EncodedMove, Move, PromotionMove},
+    perft::{
+        default_threads, nps, parallel_divide, perft_stats, run_suite, PerftTable,
+        DEFAULT_PERFT_HASH_MB,
+    },
+    pgn::{read_pgn, write_pgn, GameRecord, PgnMove},
     piece_type::{PieceType, PromoteType},
     search::{MoveFinder, DEFAULT_DEPTH, DEFAULT_MAX_DEPTH},
     side::Side,
     square::{self, Square},
+    syzygy::Tablebase,
+    variant::{Variant, VARIANTS},
 };
 
 pub fn main() {
     let mut game = Game::from_fen(STARTING_POSITION_FEN)
         .expect("game is not loading the starting position fen correctly");
     let mut mv_finder = MoveFinder::new(DEFAULT_DEPTH, DEFAULT_MAX_DEPTH);
+    let mut chess960 = false;
+    kpk::init();
+    // so the book doesn't pick the same moves every time the engine starts
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(1, |time| time.as_nanos() as u64);
+    mv_finder.set_book_seed(seed);
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(|arg| arg.as_str()) {
+        Some("analyse") => return analyse(&args[1..], &mut mv_finder),
+        Some("selfplay") => return selfplay(&args[1..], &mut mv_finder),
+        Some("perft") => return run_suite(&args[1..]),
+        _ => {}
+    }
 
     loop {
         let mut input_str = String::new();
@@ -29,16 +57,19 @@ pub fn main() {
                 input_is_ready();
             }
             "ucinewgame" => {
-                game = input_uci_new_game();
+                game = input_uci_new_game(game.state().variant());
             }
             input if input.starts_with("position") => {
                 input_position(&input_str, &mut game);
             }
-            input if input.starts_with("go perft") => input_perft(&input_str, &mut game),
-            input if input.starts_with("go") => input_go(&mut game, &mut mv_finder),
+            input if input.starts_with("setoption") => {
+                input_set_option(&input_str, &mut game, &mut mv_finder, &mut chess960);
+            }
+            input if input.starts_with("go perft") => input_perft(&input_str, &mut game, chess960),
+            input if input.starts_with("go") => input_go(&mut game, &mut mv_finder, chess960),
             "quit" => input_quit(),
             "stop" => input_quit(),
-            "print" => print(&game),
+            "print" | "d" => print(&mut game),
             _ => {
                 println!("Invalid input: {}", input_str);
             }
@@ -46,10 +77,222 @@ pub fn main() {
     }
 }
 
+fn print_analyse_usage() {
+    println!("usage: rust_engine analyse <pgn file> [--depth <n>]");
+}
+
+// annotates the mainline of every game in a pgn file with the engine's eval and best move
+fn analyse(args: &[String], mv_finder: &mut MoveFinder) {
+    let mut path = None;
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--depth" => match args_iter.next().map(|n| n.parse()) {
+                Some(Ok(depth)) => mv_finder.change_search_depth(depth),
+                _ => return print_analyse_usage(),
+            },
+            path_arg if path.is_none() => path = Some(path_arg),
+            _ => return print_analyse_usage(),
+        }
+    }
+
+    let Some(path) = path else {
+        return print_analyse_usage();
+    };
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) => return println!("unable to read {}: {}", path, err),
+    };
+
+    for (i, record) in read_pgn(&text).into_iter().enumerate() {
+        match record.and_then(|record| analyse_game(record, mv_finder)) {
+            Ok(record) => println!("{}", write_pgn(&record)),
+            Err(err) => println!("game {}: {}", i + 1, err),
+        }
+    }
+}
+
+fn analyse_game(mut record: GameRecord, mv_finder: &mut MoveFinder) -> Result<GameRecord, String> {
+    let mut game = Game::from_fen(record.start_fen())?;
+    for pgn_move in record.mainline.moves.iter_mut() {
+        // the eval is already from white's perspective
+        if let Some((best_move, eval)) = mv_finder.get(&mut game) {
+            let mut comment = format_eval(eval);
+            if best_move != pgn_move.mv {
+                comment = format!("{} best {}", comment, game.move_to_san(best_move));
+            }
+            pgn_move.comment = Some(match &pgn_move.comment {
+                Some(existing) => format!("{} {}", existing, comment),
+                None => comment,
+            });
+        }
+        game.make_move(pgn_move.mv);
+    }
+
+    Ok(record)
+}
+
+// in pawns from white's perspective, or the moves to mate
+fn format_eval(eval: i32) -> String {
+    let plies_to_mate = MAX_EVAL - eval.abs();
+    if plies_to_mate <= u8::MAX as i32 {
+        let sign = if eval < 0 { "-" } else { "" };
+        format!("#{}{}", sign, (plies_to_mate + 1) / 2)
+    } else {
+        format!("{:+.2}", eval as f64 / 100.0)
+    }
+}
+
+#[cfg(test)]
+pub mod test_analyse {
+    use super::*;
+
+    #[test]
+    fn eval_format() {
+        assert_eq!(format_eval(35), "+0.35");
+        assert_eq!(format_eval(-120), "-1.20");
+        assert_eq!(format_eval(0), "+0.00");
+        assert_eq!(format_eval(MAX_EVAL - 1), "#1");
+        assert_eq!(format_eval(-(MAX_EVAL - 4)), "#-2");
+    }
+
+    #[test]
+    fn annotate_game() {
+        let mut records = read_pgn("1. f3 e5 2. g4 {blunder} Qh4# 0-1");
+        assert_eq!(records.len(), 1);
+        let result = records.remove(0);
+        assert!(result.is_ok());
+
+        let mut mv_finder = MoveFinder::new(2, 4);
+        let result = analyse_game(result.unwrap(), &mut mv_finder);
+        assert!(result.is_ok());
+        let record = result.unwrap();
+
+        let comments: Vec<&str> = record
+            .mainline
+            .moves
+            .iter()
+            .map(|pgn_move| pgn_move.comment.as_deref().unwrap_or(""))
+            .collect();
+        assert!(comments[1].starts_with('+') || comments[1].starts_with('-'));
+        assert!(comments[2].starts_with("blunder "));
+        assert_eq!(comments[3], "#-1");
+    }
+}
+
+fn print_selfplay_usage() {
+    println!("usage: rust_engine selfplay [--games <n>] [--depth <n>] [--fen <fen>]");
+}
+
+// plays the engine against itself and writes the games as pgn
+fn selfplay(args: &[String], mv_finder: &mut MoveFinder) {
+    let mut games = 1;
+    let mut fen = STARTING_POSITION_FEN.to_string();
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        match (arg.as_str(), args_iter.next()) {
+            ("--games", Some(n)) => match n.parse() {
+                Ok(n) => games = n,
+                Err(_) => return print_selfplay_usage(),
+            },
+            ("--depth", Some(n)) => match n.parse() {
+                Ok(depth) => mv_finder.change_search_depth(depth),
+                Err(_) => return print_selfplay_usage(),
+            },
+            ("--fen", Some(fen_arg)) => fen = fen_arg.clone(),
+            _ => return print_selfplay_usage(),
+        }
+    }
+
+    for i in 0..games {
+        match play_game(&fen, mv_finder) {
+            Ok(record) => println!("{}", write_pgn(&record)),
+            Err(err) => println!("game {}: {}", i + 1, err),
+        }
+    }
+}
+
+// the adjudicator ends the game as soon as the rules do. fivefold repetition and the 75 move rule
+// end every game in the end, so there's no need for a move limit
+fn play_game(fen: &str, mv_finder: &mut MoveFinder) -> Result<GameRecord, String> {
+    let mut game = Game::from_fen(fen)?;
+    let mut record = GameRecord::default();
+    if fen != STARTING_POSITION_FEN {
+        record.set_tag("SetUp", "1");
+        record.set_tag("FEN", fen);
+    }
+
+    let status = loop {
+        let status = game.status();
+        if status.is_over() {
+            break status;
+        }
+
+        let (mv, _) = mv_finder
+            .get(&mut game)
+            .ok_or(format!("no move was found in a game that is {}", status))?;
+        let san = game.move_to_san(mv);
+        record.mainline.moves.push(PgnMove::new(mv, san));
+        game.make_move(mv);
+    };
+    record.set_result(status.result());
+    record.set_tag("Termination", &status.to_string());
+
+    Ok(record)
+}
+
+#[cfg(test)]
+pub mod test_selfplay {
+    use super::*;
+
+    #[test]
+    fn adjudicates_mate() {
+        let mut mv_finder = MoveFinder::new(2, 4);
+        let result = play_game("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", &mut mv_finder);
+        assert!(result.is_ok());
+        let record = result.unwrap();
+
+        assert_eq!(record.tag("Result"), Some("1-0"));
+        assert_eq!(record.tag("Termination"), Some("white wins by checkmate"));
+        assert!(write_pgn(&record).ends_with("\n1. Ra8# 1-0\n"));
+    }
+
+    #[test]
+    fn adjudicates_draw() {
+        let mut mv_finder = MoveFinder::new(2, 4);
+        let result = play_game("8/8/4k3/8/8/3K4/8/8 w - - 0 1", &mut mv_finder);
+        assert!(result.is_ok());
+        let record = result.unwrap();
+
+        assert_eq!(record.tag("Result"), Some("1/2-1/2"));
+        assert_eq!(
+            record.tag("Termination"),
+            Some("draw by insufficient material")
+        );
+        assert!(record.mainline.moves.is_empty());
+    }
+}
+
 fn input_uci() {
     println!("id name croChess");
     println!("id author alex");
-    // options go here
+    println!("option name EvalFile type string default <empty>");
+    println!("option name UseNNUE type check default false");
+    println!("option name NnueFile type string default <empty>");
+    println!("option name SyzygyPath type string default <empty>");
+    println!("option name OwnBook type check default false");
+    println!("option name BookFile type string default <empty>");
+    println!("option name BookBestMove type check default false");
+    println!("option name UCI_Chess960 type check default false");
+    let variants: Vec<String> = VARIANTS
+        .iter()
+        .map(|variant| format!("var {}", variant.uci_name()))
+        .collect();
+    println!(
+        "option name UCI_Variant type combo default {} {}",
+        Variant::Standard.uci_name(),
+        variants.join(" ")
+    );
     println!("uciok");
 }
 
@@ -57,38 +300,37 @@ fn input_is_ready() {
     println!("readyok");
 }
 
-fn input_uci_new_game() -> Game {
-    Game::from_fen(STARTING_POSITION_FEN).unwrap()
+fn input_uci_new_game(variant: Variant) -> Game {
+    Game::from_variant_fen(variant.start_fen(), variant).unwrap()
 }
 
 fn input_quit() {
     std::process::exit(0);
 }
 
-fn decode_algebra(move_notation: &str) -> (Square, Square, Option<PromoteType>) {
-    let mut chars = move_notation.chars();
-    let from = chars.next().unwrap() as i32 - 'a' as i32
-        + (8 * (chars.next().unwrap() as i32 - '1' as i32));
-    let to = chars.next().unwrap() as i32 - 'a' as i32
-        + (8 * (chars.next().unwrap() as i32 - '1' as i32));
-
-    let mut promote_pc = None;
-    if move_notation.len() == 5 {
-        match PromoteType::try_from(move_notation.chars().nth(4).unwrap()) {
-            Ok(promote_type) => {
-                promote_pc = Some(promote_type);
-            }
-            Err(err) => {
-                println!("{}", err)
-            }
+// the squares and promotion of long algebraic notation like `e2e4` or `e7e8q`
+fn decode_algebra(move_notation: &str) -> Result<(Square, Square, Option<PromoteType>), String> {
+    let invalid = || format!("{} is invalid move notation", move_notation);
+    let square = |file: u8, rank: u8| match (file, rank) {
+        (b'a'..=b'h', b'1'..=b'8') => {
+            Ok(Square::from((rank - b'1') as usize, (file - b'a') as usize))
         }
-    }
+        _ => Err(invalid()),
+    };
 
-    (
-        Square::new(from as usize),
-        Square::new(to as usize),
-        promote_pc,
-    )
+    match *move_notation.as_bytes() {
+        [from_file, from_rank, to_file, to_rank] => Ok((
+            square(from_file, from_rank)?,
+            square(to_file, to_rank)?,
+            None,
+        )),
+        [from_file, from_rank, to_file, to_rank, promote_pc] => Ok((
+            square(from_file, from_rank)?,
+            square(to_file, to_rank)?,
+            Some(PromoteType::try_from(promote_pc as char).map_err(|_| invalid())?),
+        )),
+        _ => Err(invalid()),
+    }
 }
 
 #[cfg(test)]
@@ -98,7 +340,9 @@ pub mod test_decode_algebra {
 
     #[test]
     fn e2e4() {
-        let (from, to, promote_pc) = decode_algebra("e2e4");
+        let result = decode_algebra("e2e4");
+        assert!(result.is_ok());
+        let (from, to, promote_pc) = result.unwrap();
         assert_eq!(from, E2);
         assert_eq!(to, E4);
         assert_eq!(promote_pc, None);
@@ -106,18 +350,59 @@ pub mod test_decode_algebra {
 
     #[test]
     fn promote_1() {
-        let (from, to, promote_pc) = decode_algebra("e2e4q");
+        let result = decode_algebra("e2e4q");
+        assert!(result.is_ok());
+        let (from, to, promote_pc) = result.unwrap();
         assert_eq!(from, E2);
         assert_eq!(to, E4);
         assert_eq!(promote_pc, Some(PromoteType::Queen));
     }
+
+    #[test]
+    fn invalid_notation() {
+        for move_notation in ["", "e2", "e2e", "e2e9", "i2e4", "e2e4x", "e2e4qq", "é2e4"] {
+            assert!(decode_algebra(move_notation).is_err(), "{}", move_notation);
+        }
+    }
+}
+
+// a crazyhouse drop like `N@f3`, the piece can be either case
+fn parse_drop(move_notation: &str, game: &Game) -> Result<Move, String> {
+    let invalid = || format!("{} is invalid move notation", move_notation);
+    let (piece_type, to) = match move_notation.as_bytes() {
+        [piece, b'@', file @ b'a'..=b'h', rank @ b'1'..=b'8'] => (
+            PieceType::try_from(piece.to_ascii_lowercase() as char).map_err(|_| invalid())?,
+            Square::from((rank - b'1') as usize, (file - b'a') as usize),
+        ),
+        _ => return Err(invalid()),
+    };
+    if piece_type == PieceType::King {
+        return Err(invalid());
+    }
+
+    let side = game.state().side_to_move();
+    if game.position().pocket_count(side, piece_type) == 0 {
+        return Err(format!("there is no {} in the pocket to drop", piece_type));
+    }
+    if game.position().at(to).is_some() {
+        return Err(format!(
+            "{} can't be dropped on an occupied square",
+            move_notation
+        ));
+    }
+
+    Ok(Move::Drop(DropMove::new(to, piece_type)))
 }
 
 pub fn algebra_to_move(move_notation: &str, game: &Game) -> Result<Move, String> {
-    if move_notation.len() < 4 || move_notation.len() > 5 {
-        return Err(format!("{} is invalid move notation", move_notation));
+    if move_notation.contains('@') {
+        return parse_drop(move_notation, game);
+    }
+    let (from, to, promote_pc) = decode_algebra(move_notation)?;
+    // only antichess promotes to a king
+    if promote_pc == Some(PromoteType::King) && game.state().variant() != Variant::Antichess {
+        return Err(format!("{} can't promote to a king", move_notation));
     }
-    let (from, to, promote_pc) = decode_algebra(move_notation);
     let moving_piece_result = game.position().at(from);
     return match moving_piece_result {
         Some(pc) => {
@@ -128,6 +413,15 @@ pub fn algebra_to_move(move_notation: &str, game: &Game) -> Result<Move, String>
                 PieceType::King => {
                     let side = pc.side();
 
+                    // chess960 castles are written as the king taking its own rook
+                    let castle_files = game.state().castle_files();
+                    for castle in [Castle::Queenside, Castle::Kingside] {
+                        let (rook_sq, _) = castle_files.rook_squares(side, castle);
+                        if to == rook_sq && game.state().castle_rights().can(side, castle) {
+                            return Ok(Move::Castle(castle));
+                        }
+                    }
+
                     if from.distance(to) == 2 {
                         let (_, queenside_sq) = Castle::Queenside.king_squares(side);
                         let (_, kingside_sq) = Castle::Kingside.king_squares(side);
@@ -247,7 +541,7 @@ pub mod test_algebra_to_move {
     #[test]
     fn promotion() {
         let fen = "rnbqkbn1/ppp1pppP/8/3p4/8/8/PPPP1PPP/RNBQKBNR w KQkq - 0 1";
-        let result = Game::from_fen(fen);
+        let result = Game::from_fen_unchecked(fen);
         assert!(result.is_ok());
         let game = result.unwrap();
 
@@ -259,6 +553,25 @@ pub mod test_algebra_to_move {
         )
     }
 
+    #[test]
+    fn king_promotion() {
+        let fen = "8/4P3/8/8/8/8/8/k6K w - - 0 1";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert!(algebra_to_move("e7e8k", &game).is_err());
+
+        let result = Game::from_variant_fen(fen, Variant::Antichess);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        let mv_result = algebra_to_move("e7e8k", &game);
+        assert!(mv_result.is_ok());
+        assert_eq!(
+            mv_result.unwrap(),
+            Move::Promotion(PromotionMove::new(E7, E8, &PromoteType::King, false))
+        )
+    }
+
     #[test]
     fn castle_kingside_w() {
         let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
@@ -312,18 +625,54 @@ pub mod test_algebra_to_move {
             Move::Piece(EncodedMove::new(G1, F3, PieceType::Knight, false))
         )
     }
+
+    #[test]
+    fn castle_king_takes_rook() {
+        let fen = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+
+        let mv_result = algebra_to_move("g1h1", &game);
+        assert!(mv_result.is_ok());
+        assert_eq!(mv_result.unwrap(), Move::Castle(Castle::Kingside))
+    }
+
+    #[test]
+    fn drop() {
+        let fen = "r1bqkb1r/pppp1ppp/5n2/4p3/4P3/8/PPPP1PPP/R1BQKBNR[Nn] w KQkq - 0 4";
+        let result = Game::from_variant_fen(fen, Variant::Crazyhouse);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+
+        let mv_result = algebra_to_move("N@f3", &game);
+        assert!(mv_result.is_ok());
+        let mv = mv_result.unwrap();
+        assert_eq!(mv, Move::Drop(DropMove::new(F3, PieceType::Knight)));
+        assert_eq!(move_to_algebra(mv, Side::White), "N@f3");
+
+        // the pocket has no bishop, a king is never dropped and the square must be empty
+        assert!(algebra_to_move("B@f3", &game).is_err());
+        assert!(algebra_to_move("K@f3", &game).is_err());
+        assert!(algebra_to_move("N@e4", &game).is_err());
+    }
 }
 
+// the position is set up in the variant of the current game
 pub fn input_position(input: &str, game: &mut Game) {
+    let variant = game.state().variant();
     let mut input = input[9..].to_owned() + " ";
     if input.contains("startpos") {
         input = input[9..].to_owned();
-        let result = Game::from_fen(STARTING_POSITION_FEN);
+        let result = Game::from_variant_fen(variant.start_fen(), variant);
         *game = result.unwrap();
     } else if input.contains("fen") {
         input = input[4..].to_owned();
-        let result = Game::from_fen(input.trim());
-        *game = result.expect("invalid fen");
+        match Game::from_variant_fen(input.trim(), variant) {
+            Ok(fen_game) => *game = fen_game,
+            // keep the last position rather than searching an invalid one
+            Err(err) => return println!("info string invalid fen: {}", err),
+        }
     }
 
     if input.contains("moves") {
@@ -337,21 +686,206 @@ pub fn input_position(input: &str, game: &mut Game) {
                     game.make_move(mv);
                 }
                 Err(err) => {
-                    println!("{}", err);
-                    panic!("{}\n{}", err, game.to_string());
+                    return println!("info string invalid move {}: {}", move_notation, err);
                 }
             }
         }
     }
 }
 
-fn input_perft(input: &str, game: &mut Game) {
-    let depth_result = input[8..].to_owned().trim().parse();
-    if let Ok(num) = depth_result {
-        count_moves_debug(num, game);
-    } else {
-        println!("wasn't given a valid number for perft depth'")
+// splits `setoption name <name> value <value>` into the name and value, value is empty for
+// button options which have no value
+pub fn parse_set_option(input: &str) -> Result<(String, String), String> {
+    let input = input.trim();
+    let rest = input
+        .strip_prefix("setoption")
+        .and_then(|rest| rest.trim_start().strip_prefix("name "))
+        .ok_or(format!("invalid setoption command: {}", input))?;
+
+    match rest.find(" value") {
+        Some(idx) => Ok((
+            rest[..idx].trim().to_string(),
+            rest[idx + 6..].trim().to_string(),
+        )),
+        None => Ok((rest.trim().to_string(), String::new())),
+    }
+}
+
+fn input_set_option(input: &str, game: &mut Game, mv_finder: &mut MoveFinder, chess960: &mut bool) {
+    let (name, value) = match parse_set_option(input) {
+        Ok(option) => option,
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    };
+
+    match name.as_str() {
+        "EvalFile" => {
+            if value.is_empty() || value == "<empty>" {
+                mv_finder.set_eval_params(EvalParams::default());
+                return;
+            }
+            match EvalParams::load(&value) {
+                Ok(eval_params) => mv_finder.set_eval_params(eval_params),
+                Err(err) => println!("info string {}", err),
+            }
+        }
+        "UseNNUE" => match value.as_str() {
+            "true" => {
+                mv_finder.set_use_nnue(true);
+                if !mv_finder.nnue_enabled() {
+                    println!("info string no network loaded, using classical eval");
+                }
+            }
+            "false" => mv_finder.set_use_nnue(false),
+            _ => println!("info string UseNNUE must be true or false"),
+        },
+        "NnueFile" => {
+            if value.is_empty() || value == "<empty>" {
+                mv_finder.set_network(None);
+                return;
+            }
+            match Network::load(&value) {
+                Ok(network) => mv_finder.set_network(Some(network)),
+                Err(err) => println!("info string {}", err),
+            }
+        }
+        "SyzygyPath" => {
+            if value.is_empty() || value == "<empty>" {
+                mv_finder.set_tablebase(None);
+                return;
+            }
+            match Tablebase::open(&value) {
+                Ok(tablebase) => {
+                    println!(
+                        "info string found {} tablebases with up to {} pieces",
+                        tablebase.table_count(),
+                        tablebase.max_pieces()
+                    );
+                    mv_finder.set_tablebase(Some(Arc::new(tablebase)));
+                }
+                Err(err) => println!("info string {}", err),
+            }
+        }
+        "OwnBook" => match value.as_str() {
+            "true" => mv_finder.set_own_book(true),
+            "false" => mv_finder.set_own_book(false),
+            _ => println!("info string OwnBook must be true or false"),
+        },
+        "BookFile" => {
+            if value.is_empty() || value == "<empty>" {
+                mv_finder.set_book(None);
+                return;
+            }
+            match Book::load(&value) {
+                Ok(book) => mv_finder.set_book(Some(Arc::new(book))),
+                Err(err) => println!("info string {}", err),
+            }
+        }
+        "BookBestMove" => match value.as_str() {
+            "true" => mv_finder.set_book_selection(BookSelection::Best),
+            "false" => mv_finder.set_book_selection(BookSelection::WeightedRandom),
+            _ => println!("info string BookBestMove must be true or false"),
+        },
+        "UCI_Chess960" => match value.as_str() {
+            "true" => *chess960 = true,
+            "false" => *chess960 = false,
+            _ => println!("info string UCI_Chess960 must be true or false"),
+        },
+        // the game starts over in the new variant until the next position command
+        "UCI_Variant" => match Variant::from_uci_name(&value) {
+            Ok(variant) => *game = input_uci_new_game(variant),
+            Err(err) => println!("info string {}", err),
+        },
+        _ => println!("info string unknown option {}", name),
+    }
+}
+
+#[cfg(test)]
+pub mod test_parse_set_option {
+    use super::*;
+
+    #[test]
+    fn name_and_value() {
+        let result = parse_set_option("setoption name EvalFile value /tmp/my params.txt\n");
+        assert_eq!(
+            result,
+            Ok(("EvalFile".to_string(), "/tmp/my params.txt".to_string()))
+        );
+    }
+
+    #[test]
+    fn button() {
+        let result = parse_set_option("setoption name Clear Hash");
+        assert_eq!(result, Ok(("Clear Hash".to_string(), String::new())));
+    }
+
+    #[test]
+    fn missing_name() {
+        assert!(parse_set_option("setoption EvalFile").is_err());
+    }
+}
+
+// `go perft <depth>` prints the nodes under each move in the same format as other engines so the
+// output can be diffed, followed by the speed. `go perft <depth> stats` prints the breakdown for
+// every depth instead
+fn input_perft(input: &str, game: &mut Game, chess960: bool) {
+    let mut args = input["go perft".len()..].split_whitespace();
+    let Some(Ok(depth)) = args.next().map(|arg| arg.parse::<u32>()) else {
+        println!("wasn't given a valid number for perft depth");
+        return;
+    };
+
+    if args.next() == Some("stats") {
+        println!(
+            "{:>5} {:>14} {:>12} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+            "depth",
+            "nodes",
+            "captures",
+            "e.p.",
+            "castles",
+            "promotions",
+            "checks",
+            "discovered",
+            "double",
+            "checkmates"
+        );
+        for (i, stats) in perft_stats(game, depth).iter().enumerate() {
+            println!(
+                "{:>5} {:>14} {:>12} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+                i + 1,
+                stats.nodes,
+                stats.captures,
+                stats.en_passants,
+                stats.castles,
+                stats.promotions,
+                stats.checks,
+                stats.discovered_checks,
+                stats.double_checks,
+                stats.checkmates
+            );
+        }
+        return;
+    }
+
+    let start = Instant::now();
+    let table = PerftTable::new(DEFAULT_PERFT_HASH_MB);
+    let split = parallel_divide(game, depth, default_threads(), &table);
+    let elapsed = start.elapsed();
+
+    let mut total = 0;
+    for (mv, nodes) in split {
+        println!("{}: {}", move_to_uci(mv, game, chess960), nodes);
+        total += nodes;
     }
+    println!("\nNodes searched: {}", total);
+    println!(
+        "info time {} nodes {} nps {}",
+        elapsed.as_millis(),
+        total,
+        nps(total, elapsed)
+    );
 }
 
 #[cfg(test)]
@@ -528,6 +1062,41 @@ pub mod test_input_position {
 
         assert_eq!(position.to_string(), expected);
     }
+
+    #[test]
+    fn invalid_fen_keeps_position() {
+        let fen = STARTING_POSITION_FEN;
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+
+        // no black king
+        input_position("position fen 8/8/8/8/8/8/8/4K3 w - - 0 1", &mut game);
+        assert_eq!(game.to_fen(), STARTING_POSITION_FEN);
+
+        input_position(
+            "position fen 8/8/8/8/8/8/8/4K3 w - - 0 1 moves e1e2",
+            &mut game,
+        );
+        assert_eq!(game.to_fen(), STARTING_POSITION_FEN);
+    }
+
+    #[test]
+    fn truncated_move() {
+        let fen = STARTING_POSITION_FEN;
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+
+        // the moves before the invalid one are still made
+        input_position("position startpos moves e2", &mut game);
+        assert_eq!(game.to_fen(), STARTING_POSITION_FEN);
+        input_position("position startpos moves e2e4 e7", &mut game);
+        assert_eq!(
+            game.to_fen(),
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+        );
+    }
 }
 
 pub fn move_to_algebra(mv: Move, side: Side) -> String {
@@ -563,6 +1132,7 @@ pub fn move_to_algebra(mv: Move, side: Side) -> String {
                 promote_type_char.to_string().to_lowercase()
             )
         }
+        Move::Drop(drop_mv) => drop_mv.to_string(),
     }
 }
 
@@ -606,15 +1176,63 @@ pub mod test_move_to_algebra {
         let mv = Move::Castle(Castle::Queenside);
         assert_eq!(move_to_algebra(mv, Side::Black), "e8c8")
     }
+
+    #[test]
+    fn castle_move_chess960() {
+        let fen = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+        let result = Game::from_fen(fen);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        let mv = Move::Castle(Castle::Kingside);
+        assert_eq!(move_to_uci(mv, &game, true), "g1h1");
+        assert_eq!(move_to_uci(mv, &game, false), "g1g1");
+
+        let mv = Move::Castle(Castle::Queenside);
+        assert_eq!(move_to_uci(mv, &game, true), "g1f1");
+        assert_eq!(move_to_uci(mv, &game, false), "g1c1");
+    }
 }
 
-fn input_go(game: &mut Game, mv_finder: &mut MoveFinder) {
+// like `move_to_algebra` but castles start from the king's real square, and in chess960 mode they
+// are written as the king taking its own rook
+pub fn move_to_uci(mv: Move, game: &Game, chess960: bool) -> String {
+    let Move::Castle(castle) = mv else {
+        return move_to_algebra(mv, game.state().side_to_move());
+    };
+
+    let side = game.state().side_to_move();
+    let castle_files = game.state().castle_files();
+    let (from, king_to) = castle_files.king_squares(side, castle);
+    let to = if chess960 {
+        castle_files.rook_squares(side, castle).0
+    } else {
+        king_to
+    };
+    format!(
+        "{}{}",
+        from.to_string().to_lowercase(),
+        to.to_string().to_lowercase()
+    )
+}
+
+fn input_go(game: &mut Game, mv_finder: &mut MoveFinder, chess960: bool) {
     // search for best move
-    let (best_move, _) = mv_finder.get(game).unwrap();
-    let algebra = move_to_algebra(best_move, game.state().side_to_move());
-    println!("bestmove {}", algebra);
+    match mv_finder.get(game) {
+        Some((best_move, _)) => {
+            println!("info nodes {}", mv_finder.nodes());
+            println!("bestmove {}", move_to_uci(best_move, game, chess960));
+        }
+        // the gui still waits for a bestmove when the game is over, 0000 is the null move
+        None => {
+            println!("info string no move to play, {}", game.status());
+            println!("bestmove 0000");
+        }
+    }
 }
 
-fn print(game: &Game) {
+fn print(game: &mut Game) {
     println!("{}", game.position());
+    println!("Fen: {}", game.to_fen_with(EnPassantFen::Capturable));
+    println!("Key: {:016X}", game.state().zobrist().to_u64());
+    println!("Status: {}", game.status());
 }
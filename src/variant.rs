@@ -0,0 +1,309 @@
+use crate::{bitboard, fen::STARTING_POSITION_FEN, game::Game};
+
+// three-check is won by giving the third check
+pub const CHECKS_TO_WIN: u8 = 3;
+
+// the rules played on top of standard chess. the variant doesn't change during a game so it's kept
+// in the state next to the castle files
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    #[default]
+    Standard,
+    // a king reaching one of the four center squares wins
+    KingOfTheHill,
+    // the third check wins
+    ThreeCheck,
+    // captures are compulsory, the king is an ordinary piece and the side that runs out of moves,
+    // usually by losing all its pieces, wins
+    Antichess,
+    // captured pieces go to the pocket of the side that took them and can be dropped back on an
+    // empty square instead of moving
+    Crazyhouse,
+    // a capture blows up the capturing piece and every piece but pawns around the capture square,
+    // and blowing up the opponent's king wins
+    Atomic,
+}
+
+pub const VARIANTS: [Variant; 6] = [
+    Variant::Standard,
+    Variant::KingOfTheHill,
+    Variant::ThreeCheck,
+    Variant::Antichess,
+    Variant::Crazyhouse,
+    Variant::Atomic,
+];
+
+impl Variant {
+    // the names of the UCI_Variant option, the same ones lichess and other engines use
+    pub fn uci_name(self) -> &'static str {
+        match self {
+            Variant::Standard => "chess",
+            Variant::KingOfTheHill => "kingofthehill",
+            Variant::ThreeCheck => "3check",
+            Variant::Antichess => "antichess",
+            Variant::Crazyhouse => "crazyhouse",
+            Variant::Atomic => "atomic",
+        }
+    }
+
+    pub fn from_uci_name(name: &str) -> Result<Variant, String> {
+        VARIANTS
+            .into_iter()
+            .find(|variant| variant.uci_name() == name)
+            .ok_or(format!("{} is not a supported variant", name))
+    }
+
+    pub fn start_fen(self) -> &'static str {
+        match self {
+            Variant::ThreeCheck => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3+3 0 1",
+            Variant::Antichess => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1",
+            Variant::Crazyhouse => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1",
+            Variant::Standard | Variant::KingOfTheHill | Variant::Atomic => STARTING_POSITION_FEN,
+        }
+    }
+
+    // there is no check in antichess and a side can be left without a king, or have several
+    pub fn has_royal_king(self) -> bool {
+        self != Variant::Antichess
+    }
+
+    // the side to move has lost to the goal of the variant being reached on the last move.
+    // running out of moves is left to the move generator
+    pub fn is_lost(self, game: &Game) -> bool {
+        let side = game.state().side_to_move();
+        let opponent = side.opposite();
+        match self {
+            Variant::KingOfTheHill => {
+                (game.position().king_sq_bb(opponent) & bitboard::INNER_CENTER).not_empty()
+            }
+            Variant::ThreeCheck => game.state().checks_given(opponent) >= CHECKS_TO_WIN,
+            Variant::Atomic => game.position().king_sq_bb(side).empty(),
+            Variant::Standard | Variant::Antichess | Variant::Crazyhouse => false,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_variant {
+    use super::*;
+    use crate::{
+        move_gen::check_legal::LegalCheckPreprocessing,
+        mv::{DropMove, EncodedMove, Move},
+        piece_type::PieceType,
+        side::Side,
+        square::*,
+    };
+
+    #[test]
+    fn uci_names() {
+        for variant in VARIANTS {
+            assert_eq!(Variant::from_uci_name(variant.uci_name()), Ok(variant));
+        }
+        assert!(Variant::from_uci_name("horde").is_err());
+    }
+
+    #[test]
+    fn king_of_the_hill_lost() {
+        let fen = "4k3/8/8/3K4/8/8/8/8 b - - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::KingOfTheHill);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        assert!(game.is_variant_loss());
+        assert!(game.legal_moves().is_empty());
+
+        // only a goal reached by the opponent ends the game
+        let fen = "4k3/8/8/3K4/8/8/8/8 w - - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::KingOfTheHill);
+        assert!(result.is_ok());
+        assert!(!result.unwrap().is_variant_loss());
+    }
+
+    #[test]
+    fn three_check_lost() {
+        let fen = "4k3/8/8/8/8/8/8/4K2R b K - 0+3 0 1";
+        let result = Game::from_variant_fen(fen, Variant::ThreeCheck);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        assert!(game.is_variant_loss());
+        assert!(game.legal_moves().is_empty());
+    }
+
+    #[test]
+    fn three_check_fen() {
+        let fen = "rnbqkbnr/ppp2ppp/8/1B1pp3/4P3/8/PPPP1PPP/RNBQK1NR b KQkq - 2+3 1 3";
+        let result = Game::from_variant_fen(fen, Variant::ThreeCheck);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(game.state().checks_given(Side::White), 1);
+        assert_eq!(game.state().checks_given(Side::Black), 0);
+        assert_eq!(game.to_fen(), fen);
+
+        // lichess writes the checks given after the move counters instead
+        let lichess_fen = "rnbqkbnr/ppp2ppp/8/1B1pp3/4P3/8/PPPP1PPP/RNBQK1NR b KQkq - 1 3 +1+0";
+        let result = Game::from_variant_fen(lichess_fen, Variant::ThreeCheck);
+        assert!(result.is_ok());
+        let lichess_game = result.unwrap();
+        assert_eq!(lichess_game.to_fen(), fen);
+        assert_eq!(lichess_game.state().zobrist(), game.state().zobrist());
+
+        assert!(
+            Game::from_variant_fen("4k3/8/8/8/8/8/8/4K3 w - - 4+3 0 1", Variant::ThreeCheck)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn check_counted_and_unmade() {
+        let fen = "rnbqkbnr/ppp2ppp/8/3pp3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 3";
+        let result = Game::from_variant_fen(fen, Variant::ThreeCheck);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let zobrist = *game.state().zobrist();
+        let prev_state = game.state().encode();
+        let mv = Move::Piece(EncodedMove::new(F1, B5, PieceType::Bishop, false));
+        let capture = game.make_move(mv);
+        assert_eq!(game.state().checks_given(Side::White), 1);
+
+        game.unmake_move(mv, capture, prev_state);
+        assert_eq!(game.state().checks_given(Side::White), 0);
+        assert_eq!(game.state().zobrist(), &zobrist);
+    }
+
+    #[test]
+    fn crazyhouse_fen() {
+        let fen = "r3k2r/8/8/2pP4/8/8/8/R3K2R~[QNbp] w KQkq c6 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Crazyhouse);
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(
+            game.position().pocket_count(Side::White, PieceType::Queen),
+            1
+        );
+        assert_eq!(
+            game.position().pocket_count(Side::Black, PieceType::Pawn),
+            1
+        );
+        assert!(game.state().promoted().is_set(H1));
+        assert_eq!(game.to_fen(), fen);
+
+        // pockets only exist in crazyhouse, a king can't be captured into one and no more pieces
+        // than the starting 32 can be in play
+        assert!(Game::from_fen(fen).is_err());
+        assert!(
+            Game::from_variant_fen("4k3/8/8/8/8/8/8/4K3[K] w - - 0 1", Variant::Crazyhouse)
+                .is_err()
+        );
+        assert!(Game::from_variant_fen(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[P] w KQkq - 0 1",
+            Variant::Crazyhouse
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn captured_promoted_piece_pocketed_as_pawn() {
+        let fen = "3rk3/8/8/8/8/8/3Q~4/4K3[n] b - - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Crazyhouse);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let zobrist = *game.state().zobrist();
+        let prev_state = game.state().encode();
+        let mv = Move::Rook(EncodedMove::new(D8, D2, PieceType::Rook, true));
+        let capture = game.make_move(mv);
+        assert_eq!(
+            game.position().pocket_count(Side::Black, PieceType::Pawn),
+            1
+        );
+        assert_eq!(
+            game.position().pocket_count(Side::Black, PieceType::Queen),
+            0
+        );
+        assert!(game.state().promoted().empty());
+        assert_eq!(game.to_fen(), "4k3/8/8/8/8/8/3r4/4K3[np] w - - 0 2");
+
+        game.unmake_move(mv, capture, prev_state);
+        assert_eq!(game.to_fen(), fen);
+        assert_eq!(game.state().zobrist(), &zobrist);
+    }
+
+    #[test]
+    fn drops_block_check() {
+        let fen = "4k3/8/8/8/8/8/8/r3K3[BP] w - - 0 1";
+        let result = Game::from_variant_fen(fen, Variant::Crazyhouse);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let drops: Vec<Move> = game
+            .legal_moves()
+            .into_iter()
+            .filter(|mv| matches!(mv, Move::Drop(_)))
+            .collect();
+        // a pawn can't be dropped on the first rank so only the bishop blocks
+        assert_eq!(
+            drops,
+            vec![
+                Move::Drop(DropMove::new(B1, PieceType::Bishop)),
+                Move::Drop(DropMove::new(C1, PieceType::Bishop)),
+                Move::Drop(DropMove::new(D1, PieceType::Bishop)),
+            ]
+        );
+    }
+
+    #[test]
+    fn atomic_king_blown_up() {
+        let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4";
+        let result = Game::from_variant_fen(fen, Variant::Atomic);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let zobrist = *game.state().zobrist();
+        let prev_state = game.state().encode();
+
+        // the king, bishop and knight next to f7 blow up with the bishop and the pawn it took
+        let mv = Move::Piece(EncodedMove::new(C4, F7, PieceType::Bishop, true));
+        let capture = game.make_move(mv);
+        assert_eq!(
+            game.to_fen(),
+            "r1bq3r/pppp2pp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQK2R b KQ - 0 4"
+        );
+        assert!(game.is_variant_loss());
+        assert!(game.legal_moves().is_empty());
+
+        game.unmake_move(mv, capture, prev_state);
+        assert_eq!(game.to_fen(), fen);
+        assert_eq!(game.state().zobrist(), &zobrist);
+    }
+
+    #[test]
+    fn atomic_touching_kings() {
+        // the rook on e1 can't take the king next to black's, that would blow up both
+        let fen = "8/8/8/3kK3/8/8/5r2/4r3 w - - 0 1";
+        assert!(Game::from_fen(fen).is_err());
+        let result = Game::from_variant_fen(fen, Variant::Atomic);
+        assert!(result.is_ok());
+        let mut game = result.unwrap();
+        let legal_check_preprocessing = LegalCheckPreprocessing::from(&mut game, Side::White);
+        assert!(!legal_check_preprocessing.in_check());
+
+        // kings can't capture, and only the squares still touching black's king are safe from
+        // the rooks
+        let legal_moves = game.legal_moves();
+        assert_eq!(legal_moves.len(), 4);
+        assert!(legal_moves.contains(&Move::King(EncodedMove::new(
+            E5,
+            E4,
+            PieceType::King,
+            false
+        ))));
+        assert!(!legal_moves.contains(&Move::King(EncodedMove::new(
+            E5,
+            D5,
+            PieceType::King,
+            true
+        ))));
+        assert!(!legal_moves.contains(&Move::King(EncodedMove::new(
+            E5,
+            F5,
+            PieceType::King,
+            false
+        ))));
+    }
+}
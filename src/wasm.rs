@@ -1,18 +1,17 @@
+use core::fmt;
+
 use crate::{
     bitboard,
-    fen::STARTING_POSITION_FEN,
-    game::Game,
-    move_gen::{
-        check_legal::LegalCheckPreprocessing,
-        pseudo_legal::{self},
-    },
-    move_list::MoveList,
-    mv::{castle::Castle, Decode, Move},
+    fen::{FenError, STARTING_POSITION_FEN},
+    game::{Game, GameStatus},
+    mv::{Decode, Move},
+    pgn::{read_pgn, write_pgn, GameRecord, PgnMove},
     piece::Piece,
     piece_type::{PieceType, PromoteType},
-    search::{Depth, MoveFinder, DEFAULT_DEPTH, DEFAULT_MAX_DEPTH},
+    search::{Depth, MoveFinder, SearchProgress, SlicedSearch, DEFAULT_DEPTH, DEFAULT_MAX_DEPTH},
     side::Side,
-    square::{self, Square, ALL_SQUARES},
+    square::{Square, ALL_SQUARES},
+    state::EncodedState,
     uci::{algebra_to_move, move_to_algebra},
 };
 use wasm_bindgen::prelude::*;
@@ -22,131 +21,299 @@ pub fn set_console_error_panic_hook() {
     console_error_panic_hook::set_once();
 }
 
+// why a call from the client was rejected. a panic would take the whole engine down with it, so
+// bad input comes back as an error instead, which javascript sees as a thrown `Error`
+#[derive(Debug, PartialEq)]
+pub enum ClientError {
+    Fen(FenError),
+    Pgn(String),
+    // squares are numbered from 0 for a1 to 63 for h8
+    InvalidSquare(usize),
+    EmptySquare(Square),
+    InvalidNotation(String),
+    IllegalMove(String),
+    InvalidPromotion(char),
+    // a ply past the last move of the game
+    InvalidPly(usize, usize),
+    GameOver(GameStatus),
+    NoSearch,
+    // a move the board can't show as a piece going from one square to another, like a drop
+    UnsupportedMove(Move),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientError::Fen(err) => write!(f, "{}", err),
+            ClientError::Pgn(err) => write!(f, "{}", err),
+            ClientError::InvalidSquare(square) => write!(
+                f,
+                "{} is an invalid square (square must be between 0 and 63)",
+                square
+            ),
+            ClientError::EmptySquare(sq) => write!(f, "no piece found at {}", sq),
+            ClientError::InvalidNotation(notation) => {
+                write!(f, "{} is invalid move notation", notation)
+            }
+            ClientError::IllegalMove(notation) => write!(f, "{} is an illegal move", notation),
+            ClientError::InvalidPromotion(promote_piece) => {
+                write!(f, "{} is not a valid promotion piece", promote_piece)
+            }
+            ClientError::InvalidPly(ply, last_ply) => {
+                write!(f, "{} is past the last ply of the game, {}", ply, last_ply)
+            }
+            ClientError::GameOver(status) => write!(f, "the game is over, {}", status),
+            ClientError::NoSearch => write!(f, "no search has found a move yet"),
+            ClientError::UnsupportedMove(mv) => {
+                write!(f, "{} isn't a move from one square to another", mv)
+            }
+        }
+    }
+}
+
+impl From<FenError> for ClientError {
+    fn from(err: FenError) -> ClientError {
+        ClientError::Fen(err)
+    }
+}
+
+impl From<ClientError> for JsValue {
+    fn from(err: ClientError) -> JsValue {
+        js_sys::Error::new(&err.to_string()).into()
+    }
+}
+
+fn square_at(square: usize) -> Result<Square, ClientError> {
+    if square < 64 {
+        Ok(ALL_SQUARES[square])
+    } else {
+        Err(ClientError::InvalidSquare(square))
+    }
+}
+
+// long algebraic notation like e2e4 or e7e8q
+fn is_move_notation(move_notation: &str) -> bool {
+    let chars: Vec<char> = move_notation.chars().collect();
+    let is_square =
+        |file: char, rank: char| ('a'..='h').contains(&file) && ('1'..='8').contains(&rank);
+
+    match chars[..] {
+        [from_file, from_rank, to_file, to_rank] => {
+            is_square(from_file, from_rank) && is_square(to_file, to_rank)
+        }
+        [from_file, from_rank, to_file, to_rank, promote_piece] => {
+            is_square(from_file, from_rank)
+                && is_square(to_file, to_rank)
+                && PromoteType::try_from(promote_piece).is_ok()
+        }
+        _ => false,
+    }
+}
+
+// nodes searched between looks at the clock in search_for
+const SLICE_NODES: u32 = 2000;
+
+// the progress of a search, handed to the callback of search_nodes and search_for once for every
+// depth it finishes and when a slice stops part way through one
+#[wasm_bindgen]
+pub struct SearchInfo {
+    depth: Depth,
+    eval: i32,
+    pv: String,
+    nodes: u64,
+    finished: bool,
+}
+
+impl SearchInfo {
+    fn new(progress: &SearchProgress, side: Side) -> SearchInfo {
+        let mut side = side;
+        let mut pv = vec![];
+        for mv in &progress.pv {
+            pv.push(move_to_algebra(*mv, side));
+            side = side.opposite();
+        }
+
+        SearchInfo {
+            depth: progress.depth,
+            eval: progress.eval,
+            pv: pv.join(" "),
+            nodes: progress.nodes,
+            finished: progress.finished,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl SearchInfo {
+    pub fn depth(&self) -> Depth {
+        self.depth
+    }
+
+    // in centipawns from white's point of view
+    pub fn eval(&self) -> i32 {
+        self.eval
+    }
+
+    // the moves the engine expects, like `e2e4 e7e5 g1f3`
+    pub fn pv(&self) -> String {
+        self.pv.clone()
+    }
+
+    // a number rather than a u64, which would be a bigint in javascript
+    pub fn nodes(&self) -> f64 {
+        self.nodes as f64
+    }
+
+    // false for the best move so far of a depth that isn't done, whose pv is only that move
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+}
+
+// whether the game is over, why and who won
+#[wasm_bindgen]
+pub struct StatusInfo(GameStatus);
+
+#[wasm_bindgen]
+impl StatusInfo {
+    pub fn is_over(&self) -> bool {
+        self.0.is_over()
+    }
+
+    // like `checkmate` or `threefold_repetition`, `in_progress` while the game goes on
+    pub fn reason(&self) -> String {
+        self.0.reason().to_string()
+    }
+
+    // `white` or `black`, undefined for a draw or a game that isn't over
+    pub fn winner(&self) -> Option<String> {
+        self.0.winner().map(|side| side.to_string())
+    }
+
+    // `1-0`, `0-1`, `1/2-1/2` or `*`
+    pub fn result(&self) -> String {
+        self.0.result().to_string()
+    }
+
+    // like `white wins by checkmate` or `in progress`
+    pub fn description(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+// a move made on the board with what it takes to unmake it
+#[derive(Clone, Copy)]
+struct PlayedMove {
+    mv: Move,
+    capture: Option<Piece>,
+    prev_state: EncodedState,
+}
+
+// the moves, their notation and the board after each are kept for the whole line, so moves that
+// were taken back can be redone until a different move is made. ply is how many of them are on
+// the board
 #[wasm_bindgen]
 pub struct ClientGameInterface {
     game: Game,
     move_finder: MoveFinder,
     board_states: Vec<String>,
     history: Vec<String>,
+    start_fen: String,
+    moves: Vec<PlayedMove>,
+    ply: usize,
+    // status of the position on the board, worked out once each time it changes
+    status: GameStatus,
+    // the search started by start_search, dropped when the position on the board changes
+    search: Option<SlicedSearch>,
 }
 
 impl ClientGameInterface {
-    fn move_notation_from_numbers(from: u32, to: u32) -> String {
-        format!(
-            "{}{}",
-            square::ALL_SQUARES[from as usize].to_string(),
-            square::ALL_SQUARES[to as usize].to_string()
-        )
-    }
+    fn new(fen: &str) -> Result<ClientGameInterface, FenError> {
+        let game = Game::from_fen(fen)?;
 
-    fn pseudo_legal_moves_at_sq(&self, from: Square, piece: Piece) -> MoveList {
-        let side = piece.side();
-        let piece_type = piece.piece_type();
+        let mut interface = ClientGameInterface {
+            board_states: vec![],
+            history: Vec::new(),
+            game,
+            move_finder: MoveFinder::new(DEFAULT_DEPTH, DEFAULT_MAX_DEPTH),
+            start_fen: fen.to_string(),
+            moves: vec![],
+            ply: 0,
+            status: GameStatus::InProgress,
+            search: None,
+        };
+        interface.board_states.push(interface.to_string());
+        interface.position_changed();
 
-        let friendly_occupied = self.game.position().bb_side(side);
-        let enemy_occupied = self.game.position().bb_side(side.opposite());
+        Ok(interface)
+    }
 
-        let en_passant = {
-            if self.game.state().side_to_move() == side {
-                self.game.state().en_passant()
-            } else {
-                None
-            }
-        };
-        let moves_bb = piece_type.pseudo_legal_moves_bb(
-            from,
-            friendly_occupied,
-            enemy_occupied,
-            side,
-            en_passant,
-        );
+    // the notation has to name one of the legal moves, algebra_to_move alone accepts any move a
+    // piece on the from square could make
+    fn parse_move(&mut self, move_notation: &str) -> Result<Move, ClientError> {
+        if !is_move_notation(move_notation) {
+            return Err(ClientError::InvalidNotation(move_notation.to_string()));
+        }
 
-        let mut mv_list: MoveList = MoveList::new();
-        piece_type.push_bb_to_move_list(
-            &mut mv_list,
-            moves_bb,
-            from,
-            side,
-            enemy_occupied,
-            en_passant,
-        );
-        if piece_type == PieceType::King {
-            let castle_rights = self.game.state().castle_rights();
-            if castle_rights.can(side, Castle::Queenside) {
-                mv_list.push_move(Move::Castle(Castle::Queenside))
-            }
-            if castle_rights.can(side, Castle::Kingside) {
-                mv_list.push_move(Move::Castle(Castle::Kingside))
-            }
+        let mv = algebra_to_move(move_notation, &self.game)
+            .map_err(|_| ClientError::IllegalMove(move_notation.to_string()))?;
+        if !self.game.legal_moves().contains(&mv) {
+            return Err(ClientError::IllegalMove(move_notation.to_string()));
         }
 
-        mv_list
-    }
-
-    fn is_move_pseudo_legal(&self, mv: Move, side: Side) -> bool {
-        match mv {
-            Move::King(mv)
-            | Move::Rook(mv)
-            | Move::Pawn(mv)
-            | Move::DoublePawnPush(mv)
-            | Move::EnPassant(mv)
-            | Move::Piece(mv) => {
-                let (from, to) = mv.decode_into_squares();
-                let piece_type = mv.piece_type();
-
-                let friendly_occupied = self.game.position().bb_side(side);
-                let enemy_occupied = self.game.position().bb_side(side.opposite());
-
-                let pseudo_legal_moves = piece_type.pseudo_legal_moves_bb(
-                    from,
-                    friendly_occupied,
-                    enemy_occupied,
-                    side,
-                    self.game.state().en_passant(),
-                );
-                pseudo_legal_moves.is_set(to)
-            }
-            Move::Promotion(mv) => {
-                let (from, to) = mv.decode_into_squares();
-                let friendly_occupied = self.game.position().bb_side(side);
-                let enemy_occupied = self.game.position().bb_side(side.opposite());
-                let pseudo_legal_moves = pseudo_legal::pawn(
-                    from,
-                    friendly_occupied,
-                    enemy_occupied,
-                    self.game.state().en_passant(),
-                    side,
-                );
-
-                pseudo_legal_moves.is_set(to)
-            }
-            Move::Castle(castle_mv) => {
-                if !self.game.state().castle_rights().can(side, castle_mv) {
-                    return false;
-                }
+        Ok(mv)
+    }
 
-                let (king_sq, _) = castle_mv.king_squares(side);
-                let (rook_sq, _) = castle_mv.rook_squares(side);
-
-                let king_bb = self.game.position().bb_pc(PieceType::King, side);
-                assert!(
-                    king_bb.is_set(king_sq),
-                    "king is not on {}. {} is not a legal move.",
-                    king_sq,
-                    mv
-                );
-
-                let rook_bb = self.game.position().bb_pc(PieceType::Rook, side);
-                assert!(
-                    rook_bb.is_set(rook_sq),
-                    "rook is not on {}. {} is not a legal move.",
-                    rook_sq,
-                    mv
-                );
-
-                true
-            }
+    // a new move replaces the moves that were taken back
+    fn push_move(&mut self, mv: Move) {
+        self.moves.truncate(self.ply);
+        self.history.truncate(self.ply);
+        self.board_states.truncate(self.ply + 1);
+
+        self.history.push(self.game.move_to_san(mv));
+        let prev_state = self.game.state().encode();
+        let capture = self.game.make_move(mv);
+        self.board_states.push(self.to_string());
+        self.moves.push(PlayedMove {
+            mv,
+            capture,
+            prev_state,
+        });
+        self.ply += 1;
+        self.position_changed();
+    }
+
+    fn position_changed(&mut self) {
+        self.status = self.game.status();
+        self.search = None;
+    }
+
+    // the from and to squares of the legal moves of side, even when it isn't their turn.
+    // castles go to the king's destination and promotions show up once per promote type
+    fn legal_move_squares(&self, side: Side) -> Result<Vec<(Square, Square)>, ClientError> {
+        let mut game = self.game.clone();
+        if game.state().side_to_move() != side {
+            game.make_null_move();
         }
+
+        let castle_files = game.state().castle_files();
+        game.legal_moves()
+            .into_iter()
+            .map(|mv| match mv {
+                Move::King(mv)
+                | Move::Rook(mv)
+                | Move::Pawn(mv)
+                | Move::DoublePawnPush(mv)
+                | Move::Piece(mv)
+                | Move::EnPassant(mv) => Ok(mv.decode_into_squares()),
+                Move::Castle(castle_mv) => Ok((
+                    castle_files.king_squares(side, castle_mv).0,
+                    castle_mv.king_squares(side).1,
+                )),
+                Move::Promotion(mv) => Ok(mv.decode_into_squares()),
+                Move::Drop(_) => Err(ClientError::UnsupportedMove(mv)),
+            })
+            .collect()
     }
 }
 
@@ -156,22 +323,36 @@ impl ClientGameInterface {
         self.game.state().side_to_move().to_string()
     }
 
-    pub fn board_state(&self, i: usize) -> String {
-        self.board_states[i].clone()
+    pub fn status(&self) -> StatusInfo {
+        StatusInfo(self.status)
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.status.is_over()
+    }
+
+    // the board after the first i moves on it, the moves taken back don't count
+    pub fn board_state(&self, i: usize) -> Result<String, ClientError> {
+        if i > self.ply {
+            return Err(ClientError::InvalidPly(i, self.ply));
+        }
+
+        Ok(self.board_states[i].clone())
     }
 
+    // the moves on the board, without the ones that were taken back
     pub fn history(&self) -> String {
-        self.history
+        self.history[..self.ply]
             .iter()
             .enumerate()
             .fold(String::new(), |history, (i, notation)| {
-                return if i % 2 == 0 {
+                if i % 2 == 0 {
                     let move_no = i / 2 + 1;
 
                     format!("{} {}. {}", history, move_no, notation)
                 } else {
                     format!("{} {}", history, notation)
-                };
+                }
             })
             .trim()
             .to_string()
@@ -185,53 +366,122 @@ impl ClientGameInterface {
         self.move_finder.change_max_depth(depth)
     }
 
-    pub fn from_moves_str(moves_str: &str) -> ClientGameInterface {
-        let game = Game::from_fen(STARTING_POSITION_FEN).unwrap();
+    // the error explains why the fen was rejected
+    pub fn from_fen(fen: &str) -> Result<ClientGameInterface, ClientError> {
+        Ok(ClientGameInterface::new(fen)?)
+    }
 
-        let mut interface = ClientGameInterface {
-            board_states: vec![],
-            history: Vec::new(),
-            game: game.clone(),
-            move_finder: MoveFinder::new(DEFAULT_DEPTH, DEFAULT_MAX_DEPTH),
-        };
-        interface.board_states.push(interface.to_string());
+    pub fn from_moves_str(moves_str: &str) -> Result<ClientGameInterface, ClientError> {
+        let mut interface = ClientGameInterface::new(STARTING_POSITION_FEN)?;
 
         let moves = moves_str.trim().split(' ');
         for move_notation in moves {
-            if move_notation == "" {
+            if move_notation.is_empty() {
                 break;
             };
-            interface.make_move(move_notation);
+            interface.make_move(move_notation)?;
         }
 
-        interface
+        Ok(interface)
     }
 
-    pub fn make_move(&mut self, move_notation: &str) {
-        let mv_result = algebra_to_move(move_notation, &self.game);
-        match mv_result {
-            Ok(mv) => {
-                self.game.make_move(mv);
-                self.board_states.push(self.to_string());
-                self.history.push(mv.to_algebra());
-            }
-            Err(err) => {
-                println!("{}", err);
-                panic!("{}\n{}", err, self.to_string());
-            }
+    pub fn make_move(&mut self, move_notation: &str) -> Result<(), ClientError> {
+        let mv = self.parse_move(move_notation)?;
+        self.push_move(mv);
+
+        Ok(())
+    }
+
+    // the mainline of the first game in the pgn
+    pub fn load_pgn(pgn: &str) -> Result<ClientGameInterface, ClientError> {
+        let record = match read_pgn(pgn).into_iter().next() {
+            Some(Ok(record)) => record,
+            Some(Err(err)) => return Err(ClientError::Pgn(err)),
+            None => return Err(ClientError::Pgn("no game found in the pgn".to_string())),
+        };
+
+        let mut interface = ClientGameInterface::new(record.start_fen())?;
+        for pgn_move in &record.mainline.moves {
+            interface.push_move(pgn_move.mv);
+        }
+
+        Ok(interface)
+    }
+
+    pub fn to_pgn(&self) -> String {
+        let mut record = GameRecord::default();
+        if self.start_fen != STARTING_POSITION_FEN {
+            record.set_tag("SetUp", "1");
+            record.set_tag("FEN", &self.start_fen);
+        }
+        // the game as it is on the board, without the moves that were taken back
+        for (played, san) in self.moves[..self.ply].iter().zip(&self.history) {
+            record
+                .mainline
+                .moves
+                .push(PgnMove::new(played.mv, san.clone()));
         }
+        record.set_result(self.status.result());
+
+        write_pgn(&record)
     }
 
-    pub fn is_promotion(&mut self, from: u32, to: u32) -> bool {
-        let at_from = self.game.position().at(Square(from as usize));
-        if at_from.is_none() {
+    // takes back the last move on the board, false when there is none
+    pub fn undo(&mut self) -> bool {
+        if self.ply == 0 {
+            return false;
+        }
+
+        self.ply -= 1;
+        let played = self.moves[self.ply];
+        self.game
+            .unmake_move(played.mv, played.capture, played.prev_state);
+        self.position_changed();
+        true
+    }
+
+    // makes the move that was last taken back again, false when there is none
+    pub fn redo(&mut self) -> bool {
+        if self.ply == self.moves.len() {
             return false;
         }
 
+        self.game.make_move(self.moves[self.ply].mv);
+        self.ply += 1;
+        self.position_changed();
+        true
+    }
+
+    // undoes or redoes moves until ply of them are on the board
+    pub fn go_to_ply(&mut self, ply: usize) -> Result<(), ClientError> {
+        if ply > self.moves.len() {
+            return Err(ClientError::InvalidPly(ply, self.moves.len()));
+        }
+
+        while self.ply > ply {
+            self.undo();
+        }
+        while self.ply < ply {
+            self.redo();
+        }
+
+        Ok(())
+    }
+
+    pub fn current_ply(&self) -> usize {
+        self.ply
+    }
+
+    pub fn is_promotion(&mut self, from: u32, to: u32) -> Result<bool, ClientError> {
+        let at_from = self.game.position().at(square_at(from as usize)?);
+        let to_sq = square_at(to as usize)?;
+        if at_from.is_none() {
+            return Ok(false);
+        }
+
         let (side, pc) = at_from.unwrap().decode();
-        println!("side: {}, pieceType: {}", side, pc);
         if pc != PieceType::Pawn {
-            return false;
+            return Ok(false);
         }
 
         let promote_rank_bb = if side == Side::White {
@@ -239,88 +489,120 @@ impl ClientGameInterface {
         } else {
             bitboard::ROW_1
         };
-        if !promote_rank_bb.is_set(Square(to as usize)) {
-            return false;
+        if !promote_rank_bb.is_set(to_sq) {
+            return Ok(false);
         }
 
-        return self.validate_move(from, to, side == Side::White);
+        self.validate_move(from, to, side == Side::White)
     }
 
-    pub fn validate_move(&mut self, from: u32, to: u32, is_white: bool) -> bool {
-        let side = if is_white == true { Side::White } else { Side::Black };
+    pub fn validate_move(
+        &mut self,
+        from: u32,
+        to: u32,
+        is_white: bool,
+    ) -> Result<bool, ClientError> {
+        let side = if is_white { Side::White } else { Side::Black };
 
-        let move_notation = ClientGameInterface::move_notation_from_numbers(from, to);
+        let (from, to) = (square_at(from as usize)?, square_at(to as usize)?);
+        Ok(self.legal_move_squares(side)?.contains(&(from, to)))
+    }
 
-        let mv_result = algebra_to_move(&move_notation, &self.game);
-        assert!(mv_result.is_ok(), "{} is not a valid move", move_notation);
-        let mv = mv_result.unwrap();
+    pub fn legal_moves_at_sq(&mut self, from: u32) -> Result<Vec<u32>, ClientError> {
+        let from = square_at(from as usize)?;
 
-        let mut game = self.game.clone();
-        let legal_check_preprocessing = LegalCheckPreprocessing::from(&mut game, side);
+        let side = match self.game.position().at(from) {
+            Some(piece) => piece.side(),
+            None => return Err(ClientError::EmptySquare(from)),
+        };
 
-        self.is_move_pseudo_legal(mv, side) && game.is_legal(mv, &legal_check_preprocessing)
+        Ok(self
+            .legal_move_squares(side)?
+            .into_iter()
+            .filter(|(mv_from, _)| *mv_from == from)
+            .map(|(_, to)| to.to_u32())
+            .collect())
     }
 
-    pub fn legal_moves_at_sq(&mut self, from: u32) -> Vec<u32> {
-        let from = ALL_SQUARES[from as usize];
+    pub fn engine_move(&mut self) -> Result<String, ClientError> {
+        let status = self.status;
+        if status.is_over() {
+            return Err(ClientError::GameOver(status));
+        }
+        let (best_move, _) = self
+            .move_finder
+            .get(&mut self.game)
+            .ok_or(ClientError::GameOver(status))?;
 
-        let piece_result = self.game.position().at(from);
-        assert!(
-            piece_result.is_some(),
-            "no piece found at {}\n{}",
-            from,
-            self.game.position()
-        );
-        let piece = piece_result.unwrap();
-        let pseudo_legal_mv_list = self.pseudo_legal_moves_at_sq(from, piece);
+        Ok(move_to_algebra(best_move, self.game.state().side_to_move()))
+    }
 
-        let mut game = self.game.clone();
-        let side = piece.side();
-        let mut legal_moves: Vec<u32> =
-            Vec::with_capacity(pseudo_legal_mv_list.list().len() as usize);
-        let legal_check_preprocessing = LegalCheckPreprocessing::from(&mut game, side);
-        for mv in pseudo_legal_mv_list.list() {
-            println!("{mv}");
-            if game.is_legal(*mv, &legal_check_preprocessing) {
-                println!("is legal");
-                match mv {
-                    Move::King(mv)
-                    | Move::Rook(mv)
-                    | Move::Pawn(mv)
-                    | Move::DoublePawnPush(mv)
-                    | Move::Piece(mv)
-                    | Move::EnPassant(mv) => {
-                        let (_, to) = mv.decode_into_squares();
-                        legal_moves.push(to.to_u32());
-                    }
-                    Move::Castle(castle_mv) => {
-                        let (_, to) = castle_mv.king_squares(side);
-                        legal_moves.push(to.to_u32());
-                    }
-                    Move::Promotion(mv) => {
-                        let (_, to) = mv.decode_into_squares();
-                        legal_moves.push(to.to_u32());
-                    }
-                };
-            }
+    // starts a search of the position on the board that search_nodes or search_for run a slice at
+    // a time, unlike engine_move which blocks the page until it has a move
+    pub fn start_search(&mut self) -> Result<(), ClientError> {
+        let status = self.status;
+        if status.is_over() {
+            return Err(ClientError::GameOver(status));
         }
+        self.search = Some(self.move_finder.start_sliced(&self.game));
+
+        Ok(())
+    }
 
-        legal_moves
+    // runs the search for about the given number of nodes. true once it has reached the search
+    // depth, though it can be stopped at any time by playing the best move found so far
+    pub fn search_nodes(
+        &mut self,
+        nodes: u32,
+        on_progress: Option<js_sys::Function>,
+    ) -> Result<bool, ClientError> {
+        let search = self.search.as_mut().ok_or(ClientError::NoSearch)?;
+        let side = self.game.state().side_to_move();
+
+        Ok(self
+            .move_finder
+            .search_slice(search, nodes as u64, |progress| {
+                if let Some(on_progress) = &on_progress {
+                    let info = JsValue::from(SearchInfo::new(progress, side));
+                    // an exception thrown by the callback isn't the search's to handle
+                    let _ = on_progress.call1(&JsValue::NULL, &info);
+                }
+            }))
+    }
+
+    // runs the search for about the given number of milliseconds, see search_nodes
+    pub fn search_for(
+        &mut self,
+        millis: f64,
+        on_progress: Option<js_sys::Function>,
+    ) -> Result<bool, ClientError> {
+        let deadline = js_sys::Date::now() + millis;
+        loop {
+            let done = self.search_nodes(SLICE_NODES, on_progress.clone())?;
+            if done || js_sys::Date::now() >= deadline {
+                return Ok(done);
+            }
+        }
     }
 
-    pub fn engine_move(&mut self) -> String {
-        let (best_move, _) = self.move_finder.get(&mut self.game).unwrap();
+    // the best move of the deepest search finished so far
+    pub fn search_best_move(&self) -> Result<String, ClientError> {
+        let best_move = self
+            .search
+            .as_ref()
+            .and_then(|search| search.best_move())
+            .ok_or(ClientError::NoSearch)?;
 
-        move_to_algebra(best_move, self.game.state().side_to_move())
+        Ok(move_to_algebra(best_move, self.game.state().side_to_move()))
     }
 
+    // the board from a8 to h1, one character a square. the name is the one the client calls
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         let mut string = "".to_string();
 
         let char_at = |sq: Square| -> char {
-            let result = self.game.position().at(sq);
-            if let Some(_) = result {
-                let piece = result.unwrap();
+            if let Some(piece) = self.game.position().at(sq) {
                 let (side, pc) = piece.decode();
 
                 match side {
@@ -338,71 +620,64 @@ impl ClientGameInterface {
             }
         }
 
-        format!("{}", &string)
+        string
     }
 
-    pub fn name_of_square(square: usize) -> String {
-        assert!(square < 64, "{} is not a valid square", square);
-        ALL_SQUARES[square].to_string()
+    pub fn to_fen(&self) -> String {
+        self.game.to_fen()
     }
 
-    pub fn file_of_square(square: usize) -> usize {
-        assert!(
-            square < 64,
-            "{} is an invalid square (square must be between 0 and 63)",
-            square
-        );
-        ALL_SQUARES[square].file()
+    pub fn name_of_square(square: usize) -> Result<String, ClientError> {
+        Ok(square_at(square)?.to_string())
     }
 
-    pub fn rank_of_square(square: usize) -> usize {
-        assert!(
-            square < 64,
-            "{} is an invalid square (square must be between 0 and 63)",
-            square
-        );
-        ALL_SQUARES[square].rank()
+    pub fn file_of_square(square: usize) -> Result<usize, ClientError> {
+        Ok(square_at(square)?.file())
     }
 
-    pub fn make_move_notation(from: usize, to: usize, promote_piece: Option<char>) -> String {
-        assert!(
-            from < 64,
-            "{} is an invalid square (square must be between 0 and 63)",
-            from
-        );
-        assert!(
-            to < 64,
-            "{} is an invalid square (square must be between 0 and 63)",
-            to
-        );
+    pub fn rank_of_square(square: usize) -> Result<usize, ClientError> {
+        Ok(square_at(square)?.rank())
+    }
 
-        let mut move_notation = format!(
-            "{}{}",
-            ALL_SQUARES[from].to_string(),
-            ALL_SQUARES[to].to_string()
-        );
-        if promote_piece.is_some() {
-            let promote_piece = promote_piece.unwrap();
-            assert!(
-                PromoteType::try_from(promote_piece).is_ok(),
-                "{} is not a valid promotion piece",
-                promote_piece
-            );
+    pub fn make_move_notation(
+        from: usize,
+        to: usize,
+        promote_piece: Option<char>,
+    ) -> Result<String, ClientError> {
+        let mut move_notation = format!("{}{}", square_at(from)?, square_at(to)?);
+        if let Some(promote_piece) = promote_piece {
+            // promoting to a king is only allowed in antichess, the client plays standard chess
+            if !PromoteType::try_from(promote_piece)
+                .is_ok_and(|promote_type| promote_type != PromoteType::King)
+            {
+                return Err(ClientError::InvalidPromotion(promote_piece));
+            }
 
             move_notation = format!("{}{}", move_notation, promote_piece);
         }
 
-        move_notation
+        Ok(move_notation)
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::{
+        mv::{castle::Castle, EncodedMove},
+        square,
+        variant::Variant,
+    };
+
+    fn from_moves(moves_str: &str) -> ClientGameInterface {
+        let result = ClientGameInterface::from_moves_str(moves_str);
+        assert!(result.is_ok());
+        result.unwrap()
+    }
 
     #[test]
     fn test_to_string() {
-        let game = ClientGameInterface::from_moves_str("");
+        let game = from_moves("");
         let expected = "rnbqkbnrpppppppp................................PPPPPPPPRNBQKBNR";
 
         assert_eq!(game.to_string(), expected)
@@ -410,7 +685,7 @@ mod test {
 
     #[test]
     fn test_to_string_2() {
-        let game = ClientGameInterface::from_moves_str("e2e4 e7e5");
+        let game = from_moves("e2e4 e7e5");
         let expected = "rnbqkbnrpppp.ppp............p.......P...........PPPP.PPPRNBQKBNR";
 
         assert_eq!(game.to_string(), expected)
@@ -420,28 +695,28 @@ mod test {
     fn file_of_square() {
         let square = 0;
         let expected = 0;
-        assert_eq!(expected, ClientGameInterface::file_of_square(square));
+        assert_eq!(Ok(expected), ClientGameInterface::file_of_square(square));
     }
 
     #[test]
     fn file_of_square_2() {
         let square = 7;
         let expected = 7;
-        assert_eq!(expected, ClientGameInterface::file_of_square(square));
+        assert_eq!(Ok(expected), ClientGameInterface::file_of_square(square));
     }
 
     #[test]
     fn rank_of_square() {
         let square = 0;
         let expected = 0;
-        assert_eq!(expected, ClientGameInterface::rank_of_square(square));
+        assert_eq!(Ok(expected), ClientGameInterface::rank_of_square(square));
     }
 
     #[test]
     fn rank_of_square_2() {
         let square = 63;
         let expected = 7;
-        assert_eq!(expected, ClientGameInterface::file_of_square(square));
+        assert_eq!(Ok(expected), ClientGameInterface::file_of_square(square));
     }
 
     #[test]
@@ -453,7 +728,7 @@ mod test {
         let expected = "a1b1";
         assert_eq!(
             ClientGameInterface::make_move_notation(from, to, promote_piece),
-            expected
+            Ok(expected.to_string())
         );
     }
 
@@ -466,23 +741,25 @@ mod test {
         let expected = "a1b1q";
         assert_eq!(
             ClientGameInterface::make_move_notation(from, to, promote_piece),
-            expected
+            Ok(expected.to_string())
         );
     }
 
     #[test]
-    #[should_panic]
     fn make_move_notation_3() {
         let from = 0;
         let to = 1;
         let promote_piece = Some('k');
 
-        ClientGameInterface::make_move_notation(from, to, promote_piece);
+        assert_eq!(
+            ClientGameInterface::make_move_notation(from, to, promote_piece),
+            Err(ClientError::InvalidPromotion('k'))
+        );
     }
 
     #[test]
     fn is_promotion_1() {
-        let mut game = ClientGameInterface::from_moves_str(
+        let mut game = from_moves(
             &unindent::unindent(
                 "e2e4 e7e5 b1c3 b8c6 f1c4 g8f6 g1f3 f8c5 d2d3 h7h6 c3d5 e8g8 d5f6 d8f6 c2c3 d7d6
             b2b4 c5b6 e1g1 c6e7 a2a4 a7a5 b4a5 b6a5 c1b2 e7g6 d3d4 c8g4 h2h3 g4f3 d1f3 g6f4
@@ -498,52 +775,346 @@ mod test {
 
         let is_promotion = game.is_promotion(square::C2.to_u32(), square::C1.to_u32());
 
-        assert!(is_promotion)
+        assert_eq!(is_promotion, Ok(true))
     }
 
     #[test]
     fn is_promotion_2() {
-        let mut game = ClientGameInterface::from_moves_str("");
+        let mut game = from_moves("");
 
         let is_promotion = game.is_promotion(square::E2.to_u32(), square::E4.to_u32());
 
-        assert!(!is_promotion)
+        assert_eq!(is_promotion, Ok(false))
     }
 
     #[test]
     fn legal_moves_at_sq_1() {
-        let mut game = ClientGameInterface::from_moves_str("");
-
-        let legal_sqs = game.legal_moves_at_sq(square::E2.to_u32());
+        let mut game = from_moves("");
 
-        println!("{:?}", legal_sqs.iter().map(|v| Square(*v as usize)));
+        let result = game.legal_moves_at_sq(square::E2.to_u32());
+        assert!(result.is_ok());
+        let legal_sqs = result.unwrap();
 
         assert_eq!(legal_sqs.len(), 2);
     }
 
     #[test]
     fn legal_moves_castling() {
-        let mut game = ClientGameInterface::from_moves_str("e2e4 d7d5 f1e2 c8d7 g1f3 b8c6");
+        let mut game = from_moves("e2e4 d7d5 f1e2 c8d7 g1f3 b8c6");
 
-        let legal_sqs = game.legal_moves_at_sq(square::E1.to_u32());
-
-        println!(
-            "{:?}",
-            legal_sqs
-                .iter()
-                .map(|v| Square(*v as usize).to_string())
-                .collect::<Vec<String>>()
-        );
+        let result = game.legal_moves_at_sq(square::E1.to_u32());
+        assert!(result.is_ok());
+        let legal_sqs = result.unwrap();
 
         assert_eq!(legal_sqs.len(), 2);
     }
 
     #[test]
     fn test_history() {
-        let game = ClientGameInterface::from_moves_str("e2e4 d7d5 f1e2 c8d7 g1f3 b8c6");
+        let game = from_moves("e2e4 d7d5 f1e2 c8d7 g1f3 b8c6");
 
         let history = game.history();
 
         assert_eq!(history, "1. e4 d5 2. Be2 Bd7 3. Nf3 Nc6");
     }
+
+    #[test]
+    fn load_and_write_pgn() {
+        let result = ClientGameInterface::load_pgn(
+            "[Event \"casual\"]\n\n1. f3 {weak} e5 (1... e6) 2. g4 Qh4# 0-1",
+        );
+        assert!(result.is_ok());
+        let game = result.unwrap();
+
+        assert_eq!(game.history(), "1. f3 e5 2. g4 Qh4#");
+        assert_eq!(game.board_states.len(), 5);
+        assert_eq!(game.active_side(), "white");
+
+        let pgn = game.to_pgn();
+        assert!(pgn.contains("[Result \"0-1\"]"));
+        assert!(pgn.ends_with("\n1. f3 e5 2. g4 Qh4# 0-1\n"));
+    }
+
+    #[test]
+    fn load_pgn_from_fen() {
+        let result = ClientGameInterface::load_pgn(
+            "[FEN \"4k3/8/8/8/8/8/8/R3K3 b Q - 0 40\"]\n\n40... Kd7 41. O-O-O+ *",
+        );
+        assert!(result.is_ok());
+        let game = result.unwrap();
+
+        assert_eq!(game.board_states.len(), 3);
+        assert!(game.to_pgn().contains("\n40... Kd7 41. O-O-O+ *\n"));
+    }
+
+    #[test]
+    fn load_pgn_illegal_move() {
+        let result = ClientGameInterface::load_pgn("1. e4 e4 *");
+        assert!(matches!(result, Err(ClientError::Pgn(_))));
+
+        let result = ClientGameInterface::load_pgn("");
+        assert!(matches!(result, Err(ClientError::Pgn(_))));
+    }
+
+    #[test]
+    fn to_fen() {
+        let game = from_moves("e2e4 c7c5 g1f3");
+
+        assert_eq!(
+            game.to_fen(),
+            "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+        );
+    }
+
+    #[test]
+    fn from_fen() {
+        let result = ClientGameInterface::from_fen("4k3/8/8/8/8/8/8/R3K3 b Q - 0 40");
+        assert!(result.is_ok());
+        let game = result.unwrap();
+        assert_eq!(game.active_side(), "black");
+
+        let result = ClientGameInterface::from_fen("4k3/8/8/8/8/8/8/R3K3 b K - 0 40");
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "white can't castle 0-0 without the king and rook on their start squares"
+        );
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut game = from_moves("e2e4 d7d5 e4d5 d8d5");
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            "rnbqkbnr/ppp1pppp/8/3P4/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2",
+            "rnb1kbnr/ppp1pppp/8/3q4/8/8/PPPP1PPP/RNBQKBNR w KQkq - 0 3",
+        ];
+        assert_eq!(game.current_ply(), 4);
+        assert!(!game.redo());
+
+        assert!(game.undo());
+        assert!(game.undo());
+        assert_eq!(game.current_ply(), 2);
+        assert_eq!(
+            game.to_fen(),
+            "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2"
+        );
+        assert_eq!(game.history(), "1. e4 d5");
+        assert_eq!(Ok(game.to_string()), game.board_state(2));
+
+        // the captured pawn comes back and can be taken again
+        assert!(game.redo());
+        assert_eq!(game.to_fen(), fens[1]);
+        assert!(game.redo());
+        assert_eq!(game.to_fen(), fens[2]);
+        assert_eq!(Ok(game.to_string()), game.board_state(4));
+
+        assert!(game.go_to_ply(1).is_ok());
+        assert_eq!(game.to_fen(), fens[0]);
+        assert!(game.go_to_ply(0).is_ok());
+        assert_eq!(game.to_fen(), STARTING_POSITION_FEN);
+        assert!(!game.undo());
+        assert_eq!(game.history(), "");
+        assert!(game.to_pgn().ends_with("\n*\n"));
+
+        // the moves taken back are still there to redo
+        assert!(game.go_to_ply(4).is_ok());
+        assert_eq!(game.history(), "1. e4 d5 2. exd5 Qxd5");
+    }
+
+    #[test]
+    fn move_after_undo_replaces_line() {
+        let mut game = from_moves("e2e4 e7e5 g1f3");
+        assert!(game.go_to_ply(1).is_ok());
+        assert!(game.make_move("c7c5").is_ok());
+
+        assert_eq!(game.current_ply(), 2);
+        assert!(!game.redo());
+        assert_eq!(game.history(), "1. e4 c5");
+        assert_eq!(game.board_states.len(), 3);
+        assert!(game.to_pgn().ends_with("\n1. e4 c5 *\n"));
+    }
+
+    #[test]
+    fn go_past_last_ply() {
+        let mut game = from_moves("e2e4");
+
+        assert_eq!(game.go_to_ply(2), Err(ClientError::InvalidPly(2, 1)));
+        assert_eq!(game.current_ply(), 1);
+        assert_eq!(game.board_state(2), Err(ClientError::InvalidPly(2, 1)));
+    }
+
+    #[test]
+    fn board_state_after_undo() {
+        let mut game = from_moves("e2e4 e7e5");
+        assert!(game.undo());
+
+        // the board the move taken back led to can only be seen again after redoing it
+        assert_eq!(game.board_state(2), Err(ClientError::InvalidPly(2, 1)));
+        assert_eq!(Ok(game.to_string()), game.board_state(1));
+        assert!(game.go_to_ply(0).is_ok());
+        assert_eq!(game.board_state(1), Err(ClientError::InvalidPly(1, 0)));
+        assert!(game.redo());
+        assert_eq!(Ok(game.to_string()), game.board_state(1));
+    }
+
+    #[test]
+    fn status() {
+        let mut game = from_moves("f2f3 e7e5 g2g4");
+        let status = game.status();
+        assert!(!status.is_over());
+        assert_eq!(status.reason(), "in_progress");
+        assert_eq!(status.winner(), None);
+        assert_eq!(status.result(), "*");
+        assert_eq!(status.description(), "in progress");
+        assert!(!game.is_game_over());
+
+        assert!(game.make_move("d8h4").is_ok());
+        let status = game.status();
+        assert!(status.is_over());
+        assert_eq!(status.reason(), "checkmate");
+        assert_eq!(status.winner(), Some("black".to_string()));
+        assert_eq!(status.result(), "0-1");
+        assert_eq!(status.description(), "black wins by checkmate");
+        assert!(game.is_game_over());
+        assert!(game.to_pgn().ends_with("2. g4 Qh4# 0-1\n"));
+        assert_eq!(
+            game.engine_move(),
+            Err(ClientError::GameOver(GameStatus::Checkmate(Side::Black)))
+        );
+        assert_eq!(
+            game.start_search(),
+            Err(ClientError::GameOver(GameStatus::Checkmate(Side::Black)))
+        );
+    }
+
+    #[test]
+    fn search_in_slices() {
+        // white mates with Qxf7
+        let mut game = from_moves("e2e4 e7e5 f1c4 b8c6 d1h5 g8f6");
+        game.change_search_depth(3);
+        assert_eq!(game.search_best_move(), Err(ClientError::NoSearch));
+        assert_eq!(game.search_nodes(100, None), Err(ClientError::NoSearch));
+
+        assert!(game.start_search().is_ok());
+        let mut slices = 0;
+        loop {
+            let result = game.search_nodes(200, None);
+            assert!(result.is_ok());
+            slices += 1;
+            if result.unwrap() {
+                break;
+            }
+        }
+
+        assert!(slices > 1);
+        assert_eq!(game.search_best_move(), Ok("h5f7".to_string()));
+        // the board is unchanged by the search
+        assert_eq!(game.history(), "1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6");
+
+        // a move on the board makes the search stale
+        assert!(game.make_move("h5f7").is_ok());
+        assert_eq!(game.search_best_move(), Err(ClientError::NoSearch));
+    }
+
+    #[test]
+    fn search_info() {
+        let progress = SearchProgress {
+            depth: 3,
+            eval: -40,
+            pv: vec![
+                Move::Castle(Castle::Kingside),
+                Move::DoublePawnPush(EncodedMove::new(
+                    square::D7,
+                    square::D5,
+                    PieceType::Pawn,
+                    false,
+                )),
+            ],
+            nodes: 12345,
+            finished: true,
+        };
+        let info = SearchInfo::new(&progress, Side::Black);
+
+        assert_eq!(info.depth(), 3);
+        assert_eq!(info.eval(), -40);
+        assert_eq!(info.pv(), "e8g8 d7d5");
+        assert_eq!(info.nodes(), 12345.0);
+        assert!(info.finished());
+    }
+
+    #[test]
+    fn invalid_moves() {
+        let mut game = from_moves("e2e4");
+
+        for notation in ["", "e7", "e7e9", "i7i5", "e7e5x", "e7e5e7", "ä7e5"] {
+            assert_eq!(
+                game.make_move(notation),
+                Err(ClientError::InvalidNotation(notation.to_string()))
+            );
+        }
+        // an empty from square, a blocked or too long move and a move of the side not to move
+        for notation in ["e2e4", "e7e4", "d8d6", "e1e2", "e7e8q"] {
+            assert_eq!(
+                game.make_move(notation),
+                Err(ClientError::IllegalMove(notation.to_string()))
+            );
+        }
+        assert_eq!(game.current_ply(), 1);
+        assert!(ClientGameInterface::from_moves_str("e2e4 e2e4").is_err());
+    }
+
+    #[test]
+    fn invalid_squares() {
+        let mut game = from_moves("");
+
+        assert_eq!(
+            game.legal_moves_at_sq(square::E4.to_u32()),
+            Err(ClientError::EmptySquare(square::E4))
+        );
+        assert_eq!(
+            game.legal_moves_at_sq(64),
+            Err(ClientError::InvalidSquare(64))
+        );
+        assert_eq!(
+            game.validate_move(square::E2.to_u32(), 100, true),
+            Err(ClientError::InvalidSquare(100))
+        );
+        assert_eq!(
+            game.is_promotion(64, square::E4.to_u32()),
+            Err(ClientError::InvalidSquare(64))
+        );
+        assert_eq!(
+            ClientGameInterface::name_of_square(64),
+            Err(ClientError::InvalidSquare(64))
+        );
+        assert_eq!(
+            ClientGameInterface::make_move_notation(0, 64, None),
+            Err(ClientError::InvalidSquare(64))
+        );
+        assert_eq!(
+            ClientError::InvalidSquare(64).to_string(),
+            "64 is an invalid square (square must be between 0 and 63)"
+        );
+    }
+    #[test]
+    fn drops_are_unsupported() {
+        // from_fen only reads standard fens, so the crazyhouse game is put on the board directly
+        let mut game = from_moves("");
+        let result =
+            Game::from_variant_fen("4k3/8/8/8/8/8/8/4K3[N] w - - 0 1", Variant::Crazyhouse);
+        assert!(result.is_ok());
+        game.game = result.unwrap();
+
+        let result = game.legal_moves_at_sq(square::E1.to_u32());
+        assert!(matches!(
+            result,
+            Err(ClientError::UnsupportedMove(Move::Drop(_)))
+        ));
+        let result = game.validate_move(square::E1.to_u32(), square::E2.to_u32(), true);
+        assert!(matches!(
+            result,
+            Err(ClientError::UnsupportedMove(Move::Drop(_)))
+        ));
+    }
 }